@@ -10,6 +10,7 @@ pub const OAUTH_REDIRECT_URI: &str = "http://127.0.0.1:8085";
 /// All scopes needed for Google Workspace APIs
 pub const SCOPES: &[&str] = &[
     "https://www.googleapis.com/auth/gmail.modify",
+    "https://www.googleapis.com/auth/gmail.settings.basic",
     "https://www.googleapis.com/auth/drive",
     "https://www.googleapis.com/auth/calendar",
     "https://www.googleapis.com/auth/documents",
@@ -101,7 +102,7 @@ impl WorkspaceAuthenticator {
                         // Parse expires_at from yup-oauth2 format [year, day, month, hour, min, nano, ...]
                         let expires_at = token_obj.get("expires_at")
                             .and_then(|v| v.as_array())
-                            .map(|arr| {
+                            .map(|_arr| {
                                 // Simplified: just use current time + 1 hour if we can't parse
                                 std::time::SystemTime::now()
                                     .duration_since(std::time::UNIX_EPOCH)
@@ -272,6 +273,7 @@ impl WorkspaceAuthenticator {
     }
 
     /// Refresh the access token
+    #[tracing::instrument(name = "auth.refresh", skip(self))]
     async fn refresh_token(&mut self) -> Result<OAuthToken, AuthError> {
         let refresh_token = self.token
             .as_ref()
@@ -46,7 +46,7 @@ impl TokenManager {
         if self.authenticator.is_some() {
             // Validate that the authenticator can still get tokens
             // This checks for expiry and refreshes if needed
-            if let Ok(_) = self.get_access_token().await {
+            if self.get_access_token().await.is_ok() {
                 return Ok(());
             }
             // If token fetch fails, clear the authenticator and retry
@@ -158,6 +158,7 @@ impl TokenManager {
     }
 
     /// Get an access token for API calls
+    #[tracing::instrument(name = "auth.token", skip(self), fields(account = %self.current_account))]
     pub async fn get_access_token(&mut self) -> Result<String, TokenManagerError> {
         let auth = self.authenticator.as_mut()
             .ok_or(TokenManagerError::NotAuthenticated)?;
@@ -279,3 +280,9 @@ pub enum TokenManagerError {
     #[error("{0}")]
     Other(String),
 }
+
+impl From<TokenManagerError> for crate::error::WorkspaceError {
+    fn from(err: TokenManagerError) -> Self {
+        crate::error::WorkspaceError::Auth(err.to_string())
+    }
+}
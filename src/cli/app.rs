@@ -0,0 +1,279 @@
+//! Top-level command line definition
+
+use clap::{Parser, Subcommand};
+
+use super::{
+    AuthCommands, BatchCommands, CacheCommands, CalendarCommands, CliContext, Command,
+    CommandOutput, DocsCommands, DriveCommands, GmailCommands, SheetsCommands, SlidesCommands,
+    TasksCommands,
+};
+use super::schema::schema;
+use crate::error::WorkspaceError;
+
+#[derive(Parser)]
+#[command(name = "workspace-cli")]
+#[command(about = "High-performance Google Workspace CLI for AI agent integration")]
+#[command(long_about = "workspace-cli provides programmatic access to Google Workspace APIs \
+    (Gmail, Drive, Calendar, Docs, Sheets, Slides, Tasks) with structured JSON output \
+    optimized for AI agent consumption.\n\n\
+    All commands output JSON by default. Use --format to change output format.\n\
+    Use --fields to limit response fields for token efficiency.")]
+#[command(author, version)]
+#[command(propagate_version = true)]
+#[command(after_help = super::exit_codes_help())]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    /// Output format: json, jsonl, csv
+    #[arg(long, short = 'f', global = true, default_value = "json")]
+    pub format: String,
+
+    /// Fields to include in response (comma-separated)
+    #[arg(long, global = true)]
+    pub fields: Option<String>,
+
+    /// Write output to file instead of stdout
+    #[arg(long, short = 'o', global = true)]
+    pub output: Option<String>,
+
+    /// Suppress non-essential output
+    #[arg(long, short = 'q', global = true)]
+    pub quiet: bool,
+
+    /// Bypass the response cache for this invocation
+    #[arg(long, global = true, conflicts_with = "cache_only")]
+    pub no_cache: bool,
+
+    /// Serve GET requests only from the response cache (no network)
+    #[arg(long, global = true)]
+    pub cache_only: bool,
+
+    /// Report progress events on stderr: jsonl (machine-readable) or human (progress bar)
+    #[arg(long, global = true, value_name = "MODE", value_parser = ["jsonl", "human", "off"])]
+    pub progress: Option<String>,
+
+    /// Append a `_timings` summary (request latencies, retries, rate-limit waits) to JSON output
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Write trace spans as JSON lines to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub trace_file: Option<String>,
+
+    /// Export trace spans to an OTLP/HTTP collector (e.g. http://localhost:4318)
+    #[arg(long, global = true, value_name = "URL")]
+    pub otlp_endpoint: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Gmail operations
+    #[command(long_about = "Gmail operations for listing, reading, and sending emails.\n\n\
+        Examples:\n\
+        List unread emails:\n  \
+        workspace-cli gmail list --query 'is:unread' --limit 10\n\n\
+        Get specific email with decoded body:\n  \
+        workspace-cli gmail get <message-id> --decode-body\n\n\
+        Send an email:\n  \
+        workspace-cli gmail send --to user@example.com --subject 'Hello' --body 'Message'\n\n\
+        Search emails by sender:\n  \
+        workspace-cli gmail list --query 'from:boss@company.com' --limit 5")]
+    Gmail {
+        #[command(subcommand)]
+        command: GmailCommands,
+    },
+    /// Google Drive operations
+    #[command(long_about = "Google Drive operations for file management and storage.\n\n\
+        Examples:\n\
+        List recent files:\n  \
+        workspace-cli drive list --limit 20\n\n\
+        Search for documents:\n  \
+        workspace-cli drive list --query \"mimeType='application/vnd.google-apps.document'\"\n\n\
+        Get file metadata:\n  \
+        workspace-cli drive get <file-id>\n\n\
+        Upload a file:\n  \
+        workspace-cli drive upload /path/to/file.pdf --parent <folder-id>\n\n\
+        Download a file:\n  \
+        workspace-cli drive download <file-id> --output /path/to/save")]
+    Drive {
+        #[command(subcommand)]
+        command: DriveCommands,
+    },
+    /// Google Calendar operations
+    #[command(long_about = "Google Calendar operations for event management and scheduling.\n\n\
+        Examples:\n\
+        List upcoming events:\n  \
+        workspace-cli calendar list --time-min 2025-01-01T00:00:00Z --limit 10\n\n\
+        List today's events:\n  \
+        workspace-cli calendar list --time-min $(date -u +%Y-%m-%dT00:00:00Z) \\\n    \
+        --time-max $(date -u -d '+1 day' +%Y-%m-%dT00:00:00Z)\n\n\
+        Create an event:\n  \
+        workspace-cli calendar create --summary 'Team Meeting' \\\n    \
+        --start 2025-01-15T14:00:00Z --end 2025-01-15T15:00:00Z\n\n\
+        Update an event:\n  \
+        workspace-cli calendar update <event-id> --summary 'Updated Meeting'\n\n\
+        Delete an event:\n  \
+        workspace-cli calendar delete <event-id>")]
+    Calendar {
+        #[command(subcommand)]
+        command: CalendarCommands,
+    },
+    /// Google Docs operations
+    #[command(long_about = "Google Docs operations for document access and editing.\n\n\
+        Examples:\n\
+        Get document content:\n  \
+        workspace-cli docs get <document-id>\n\n\
+        Get document as markdown:\n  \
+        workspace-cli docs get <document-id> --markdown\n\n\
+        Append text to document:\n  \
+        workspace-cli docs append <document-id> 'New paragraph text'\n\n\
+        Extract content for AI processing:\n  \
+        workspace-cli docs get <document-id> --markdown --fields content")]
+    Docs {
+        #[command(subcommand)]
+        command: DocsCommands,
+    },
+    /// Google Sheets operations
+    #[command(long_about = "Google Sheets operations for spreadsheet data access and manipulation.\n\n\
+        Examples:\n\
+        Get range of cells:\n  \
+        workspace-cli sheets get <spreadsheet-id> --range 'Sheet1!A1:C10'\n\n\
+        Update cells:\n  \
+        workspace-cli sheets update <spreadsheet-id> --range 'Sheet1!A1:B2' \\\n    \
+        --values '[[\"Name\",\"Value\"],[\"Item1\",\"100\"]]'\n\n\
+        Append rows:\n  \
+        workspace-cli sheets append <spreadsheet-id> --range 'Sheet1!A:B' \\\n    \
+        --values '[[\"New Row\",\"Data\"]]'\n\n\
+        Extract data for analysis:\n  \
+        workspace-cli sheets get <spreadsheet-id> --range 'Sheet1!A:Z' --format jsonl")]
+    Sheets {
+        #[command(subcommand)]
+        command: SheetsCommands,
+    },
+    /// Google Slides operations
+    #[command(long_about = "Google Slides operations for presentation access and content extraction.\n\n\
+        Examples:\n\
+        Get presentation info:\n  \
+        workspace-cli slides get <presentation-id>\n\n\
+        Get presentation text only:\n  \
+        workspace-cli slides get <presentation-id> --text-only\n\n\
+        Get specific slide:\n  \
+        workspace-cli slides page <presentation-id> --page 0\n\n\
+        Extract all text from presentation:\n  \
+        workspace-cli slides get <presentation-id> --text-only --format json")]
+    Slides {
+        #[command(subcommand)]
+        command: SlidesCommands,
+    },
+    /// Google Tasks operations
+    #[command(long_about = "Google Tasks operations for task and to-do list management.\n\n\
+        Examples:\n\
+        List all task lists:\n  \
+        workspace-cli tasks lists\n\n\
+        List tasks in default list:\n  \
+        workspace-cli tasks list\n\n\
+        List tasks including completed:\n  \
+        workspace-cli tasks list --show-completed\n\n\
+        Create a task:\n  \
+        workspace-cli tasks create 'Buy groceries' --due 2025-01-20T12:00:00Z\n\n\
+        Update and complete a task:\n  \
+        workspace-cli tasks update <task-id> --complete\n\n\
+        Delete a task:\n  \
+        workspace-cli tasks delete <task-id>")]
+    Tasks {
+        #[command(subcommand)]
+        command: TasksCommands,
+    },
+    /// Authentication management
+    #[command(long_about = "Authentication management for Google Workspace APIs.\n\n\
+        Examples:\n\
+        Login with OAuth2 (interactive browser flow):\n  \
+        workspace-cli auth login --credentials credentials.json --account user@example.com\n\n\
+        Login with auto-generated account name:\n  \
+        workspace-cli auth login --credentials credentials.json\n\n\
+        List all authenticated accounts:\n  \
+        workspace-cli auth accounts\n\n\
+        Switch to a different account:\n  \
+        workspace-cli auth switch user2@example.com\n\n\
+        Check authentication status:\n  \
+        workspace-cli auth status\n\n\
+        Logout specific account:\n  \
+        workspace-cli auth logout --account user@example.com\n\n\
+        Logout all accounts:\n  \
+        workspace-cli auth logout --all\n\n\
+        Note: Multi-account support allows you to manage multiple Google accounts simultaneously.")]
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+    /// Execute batch API requests (up to 100 per batch)
+    #[command(long_about = "Execute multiple API requests in a single HTTP call for efficiency.\n\n\
+        Batch requests allow you to combine up to 100 API calls into a single request,\n\
+        significantly reducing latency and quota usage for bulk operations.\n\n\
+        Input format (JSON array):\n  \
+        [{\"id\":\"req1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc123\"},\n   \
+        {\"id\":\"req2\",\"method\":\"POST\",\"path\":\"/users/me/messages/xyz/modify\",\n    \
+        \"body\":{\"addLabelIds\":[\"STARRED\"]}}]\n\n\
+        Examples:\n\
+        Batch Gmail requests from JSON string:\n  \
+        workspace-cli batch gmail --requests '[{\"id\":\"1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc\"}]'\n\n\
+        Batch Gmail requests from file:\n  \
+        workspace-cli batch gmail --file requests.json\n\n\
+        Batch from stdin (pipe):\n  \
+        echo '[{\"id\":\"1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc\"}]' | workspace-cli batch gmail")]
+    Batch {
+        #[command(subcommand)]
+        command: BatchCommands,
+    },
+    /// Manage the on-disk response cache
+    #[command(long_about = "Inspect and clear the on-disk cache of GET responses.\n\n\
+        Caching is enabled with `enabled = true` under [cache] in config.toml or\n\
+        WORKSPACE_CACHE=1. Stale entries are revalidated with If-None-Match.\n\n\
+        Examples:\n\
+        Show cache usage and hit rates:\n  \
+        workspace-cli cache stats\n\n\
+        Clear the whole cache:\n  \
+        workspace-cli cache clear\n\n\
+        Clear one account's entries:\n  \
+        workspace-cli cache clear --account user@example.com\n\n\
+        Bypass or force the cache for a single command:\n  \
+        workspace-cli docs get <id> --no-cache\n  \
+        workspace-cli docs get <id> --cache-only")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// Print a JSON description of all commands, arguments and exit codes
+    #[command(long_about = "Print a machine-readable JSON description of every command and\n\
+        argument, plus the table of process exit codes.\n\n\
+        Examples:\n\
+        Dump the full schema:\n  \
+        workspace-cli schema\n\n\
+        Only the exit codes:\n  \
+        workspace-cli schema --fields exitCodes")]
+    Schema,
+}
+
+impl Command for Commands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        // Service commands need credentials; auth, cache and schema do not
+        if !matches!(self, Commands::Auth { .. } | Commands::Cache { .. } | Commands::Schema) {
+            ctx.authenticate().await?;
+        }
+
+        match self {
+            Commands::Gmail { command } => command.run(ctx).await,
+            Commands::Drive { command } => command.run(ctx).await,
+            Commands::Calendar { command } => command.run(ctx).await,
+            Commands::Docs { command } => command.run(ctx).await,
+            Commands::Sheets { command } => command.run(ctx).await,
+            Commands::Slides { command } => command.run(ctx).await,
+            Commands::Tasks { command } => command.run(ctx).await,
+            Commands::Auth { command } => command.run(ctx).await,
+            Commands::Batch { command } => command.run(ctx).await,
+            Commands::Cache { command } => command.run(ctx).await,
+            Commands::Schema => Ok(CommandOutput::Value(schema())),
+        }
+    }
+}
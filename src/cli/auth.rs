@@ -0,0 +1,163 @@
+//! Authentication and account management subcommands
+
+use clap::Subcommand;
+use std::path::PathBuf;
+
+use super::{CliContext, Command, CommandOutput};
+use crate::auth::TokenManager;
+use crate::config::Config;
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum AuthCommands {
+    /// Login with OAuth2 (interactive browser flow)
+    Login {
+        /// Path to OAuth2 client credentials JSON
+        #[arg(long)]
+        credentials: Option<String>,
+        /// Account identifier (email or name). Defaults to extracted email from OAuth.
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Logout and clear stored tokens
+    Logout {
+        /// Specific account to logout (defaults to current account)
+        #[arg(long)]
+        account: Option<String>,
+        /// Logout all accounts
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show current authentication status
+    Status,
+    /// List all authenticated accounts
+    Accounts,
+    /// Switch to a different account
+    Switch {
+        /// Account identifier (email or name)
+        account: String,
+    },
+}
+
+impl Command for AuthCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        match self {
+            AuthCommands::Login { credentials, account } => {
+                let creds_path = credentials.map(PathBuf::from);
+
+                // Use a timestamp-based account name if none was given
+                let account_name = account.unwrap_or_else(|| {
+                    format!("account_{}", std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0))
+                });
+
+                let mut tm = TokenManager::new_for_account(Config::load(), &account_name);
+                tm.login_interactive(creds_path.clone()).await?;
+
+                // Save account info to config (without environment overrides)
+                let mut config = Config::load();
+                config.auth.current_account = Some(account_name.clone());
+                if let Some(path) = creds_path {
+                    let abs_path = std::fs::canonicalize(&path).unwrap_or(path.clone());
+                    config.auth.accounts.insert(account_name.clone(), abs_path);
+                    config.auth.credentials_path = Some(path);
+                }
+                if let Err(e) = config.save() {
+                    ctx.warn(&format!("Login succeeded but failed to save config: {}", e));
+                }
+
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "status": "success",
+                    "message": "Login successful",
+                    "account": account_name
+                })))
+            }
+            AuthCommands::Logout { account, all } => {
+                let config = Config::load();
+
+                if all {
+                    let mut any_error = false;
+                    for acc in TokenManager::list_accounts()? {
+                        let mut tm = TokenManager::new_for_account(config.clone(), &acc);
+                        if let Err(e) = tm.logout() {
+                            ctx.warn(&format!("Failed to logout {}: {}", acc, e));
+                            any_error = true;
+                        }
+                    }
+
+                    // Clear current account from config
+                    let mut config = config;
+                    config.auth.current_account = None;
+                    config.auth.accounts.clear();
+                    let _ = config.save();
+
+                    Ok(CommandOutput::success(if any_error {
+                        "Logged out all accounts (with some warnings)"
+                    } else {
+                        "Logged out all accounts"
+                    }))
+                } else {
+                    // Logout specific account or current
+                    let account_name = account
+                        .or_else(|| config.auth.current_account.clone())
+                        .unwrap_or_else(|| "default".to_string());
+
+                    let mut tm = TokenManager::new_for_account(config.clone(), &account_name);
+                    tm.logout()?;
+
+                    // Remove from config if it was the current account
+                    let mut config = config;
+                    if config.auth.current_account.as_deref() == Some(&account_name) {
+                        config.auth.current_account = None;
+                    }
+                    config.auth.accounts.remove(&account_name);
+                    let _ = config.save();
+
+                    Ok(CommandOutput::success(format!("Logged out account '{}'", account_name)))
+                }
+            }
+            AuthCommands::Status => {
+                let config = Config::load();
+                let token_manager = ctx.token_manager();
+                let status = token_manager.read().await.status();
+
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "authenticated": status.authenticated,
+                    "current_account": config.auth.current_account,
+                    "storage_type": status.storage_type,
+                    "token_cache_path": status.token_cache_path.display().to_string()
+                })))
+            }
+            AuthCommands::Accounts => {
+                let accounts = TokenManager::list_accounts()?;
+                let config = Config::load();
+                let current = config.auth.current_account.as_deref();
+
+                let accounts: Vec<_> = accounts
+                    .iter()
+                    .map(|name| serde_json::json!({
+                        "name": name,
+                        "current": current == Some(name.as_str())
+                    }))
+                    .collect();
+                Ok(CommandOutput::Value(serde_json::json!({ "accounts": accounts })))
+            }
+            AuthCommands::Switch { account } => {
+                if !TokenManager::list_accounts()?.contains(&account) {
+                    return Err(WorkspaceError::NotFound(format!(
+                        "Account '{}' not found. Use 'workspace-cli auth accounts' to list available accounts.",
+                        account
+                    )));
+                }
+
+                let mut config = Config::load();
+                config.auth.current_account = Some(account.clone());
+                config.save().map_err(|e| WorkspaceError::Config(format!("Failed to save config: {}", e)))?;
+
+                Ok(CommandOutput::success(format!("Switched to account '{}'", account)))
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Batch request subcommands
+
+use clap::Subcommand;
+
+use super::{read_input, CliContext, Command, CommandOutput};
+use crate::commands::batch::{execute_batch, BatchRequestInput};
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum BatchCommands {
+    /// Execute a batch of Gmail API requests
+    Gmail {
+        /// JSON array of requests
+        #[arg(long)]
+        requests: Option<String>,
+        /// Read requests from JSON file
+        #[arg(long)]
+        file: Option<String>,
+    },
+    /// Execute a batch of Drive API requests
+    Drive {
+        /// JSON array of requests
+        #[arg(long)]
+        requests: Option<String>,
+        /// Read requests from JSON file
+        #[arg(long)]
+        file: Option<String>,
+    },
+    /// Execute a batch of Calendar API requests
+    Calendar {
+        /// JSON array of requests
+        #[arg(long)]
+        requests: Option<String>,
+        /// Read requests from JSON file
+        #[arg(long)]
+        file: Option<String>,
+    },
+}
+
+impl Command for BatchCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let (service, requests, file) = match self {
+            BatchCommands::Gmail { requests, file } => ("gmail", requests, file),
+            BatchCommands::Drive { requests, file } => ("drive", requests, file),
+            BatchCommands::Calendar { requests, file } => ("calendar", requests, file),
+        };
+
+        // Input JSON comes from the argument, a file, or stdin
+        let json = read_input(requests, file)?;
+        let inputs: Vec<BatchRequestInput> = serde_json::from_str(&json)
+            .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid JSON input: {}", e)))?;
+
+        let access_token = ctx.access_token().await?;
+        let output = execute_batch(service, inputs, &access_token).await?;
+        if output.status == "success" {
+            CommandOutput::value(&output)
+        } else {
+            // Some (or all) requests failed; results are still written
+            Ok(CommandOutput::Partial(serde_json::to_value(&output)?))
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Response cache management subcommands
+
+use clap::Subcommand;
+
+use super::{CliContext, Command, CommandOutput};
+use crate::client::cache;
+use crate::config::Config;
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommands {
+    /// Show cache size, entries per service and hit/miss counters
+    Stats,
+    /// Remove cached responses
+    Clear {
+        /// Only clear entries for this account
+        #[arg(long)]
+        account: Option<String>,
+    },
+}
+
+impl Command for CacheCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let cache_dir = ctx.config.cache.dir.clone().or_else(Config::cache_dir).ok_or_else(|| {
+            WorkspaceError::Config("Could not determine cache directory".to_string())
+        })?;
+
+        match self {
+            CacheCommands::Stats => {
+                CommandOutput::value(&cache::stats(&cache_dir, ctx.config.cache.enabled))
+            }
+            CacheCommands::Clear { account } => {
+                let removed = cache::clear(&cache_dir, account.as_deref())?;
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "status": "success",
+                    "removed": removed,
+                    "account": account,
+                })))
+            }
+        }
+    }
+}
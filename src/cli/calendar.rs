@@ -0,0 +1,181 @@
+//! Google Calendar subcommands
+
+use clap::Subcommand;
+
+use super::{split_list, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::calendar::types::MinimalEventList;
+use crate::commands::calendar::{create, delete, list, update};
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum CalendarCommands {
+    /// List events
+    List {
+        /// Calendar ID (default: primary)
+        #[arg(long, default_value = "primary")]
+        calendar: String,
+        /// Start time (RFC3339)
+        #[arg(long)]
+        time_min: Option<String>,
+        /// End time (RFC3339)
+        #[arg(long)]
+        time_max: Option<String>,
+        /// Maximum results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Sync token for incremental sync
+        #[arg(long)]
+        sync_token: Option<String>,
+        /// Return full event data (includes attendees, organizer, description, etc.)
+        #[arg(long)]
+        full: bool,
+    },
+    /// Get a specific event by ID
+    Get {
+        /// Event ID
+        id: String,
+        /// Calendar ID (default: primary)
+        #[arg(long, default_value = "primary")]
+        calendar: String,
+    },
+    /// Create an event
+    Create {
+        /// Event summary/title
+        #[arg(long)]
+        summary: String,
+        /// Start time (RFC3339)
+        #[arg(long)]
+        start: String,
+        /// End time (RFC3339)
+        #[arg(long)]
+        end: String,
+        /// Description
+        #[arg(long)]
+        description: Option<String>,
+        /// Attendee emails (comma-separated)
+        #[arg(long)]
+        attendees: Option<String>,
+        /// Calendar ID
+        #[arg(long, default_value = "primary")]
+        calendar: String,
+        /// Recurrence rule (e.g., "RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR")
+        #[arg(long)]
+        recurrence: Option<String>,
+        /// Reminders (e.g., "email:30,popup:10" for email 30 min before and popup 10 min before)
+        #[arg(long)]
+        reminders: Option<String>,
+    },
+    /// Update an event
+    Update {
+        /// Event ID
+        id: String,
+        /// New summary
+        #[arg(long)]
+        summary: Option<String>,
+        /// New start time
+        #[arg(long)]
+        start: Option<String>,
+        /// New end time
+        #[arg(long)]
+        end: Option<String>,
+        /// Calendar ID
+        #[arg(long, default_value = "primary")]
+        calendar: String,
+    },
+    /// Delete an event
+    Delete {
+        /// Event ID
+        id: String,
+        /// Calendar ID
+        #[arg(long, default_value = "primary")]
+        calendar: String,
+    },
+    /// Query free/busy information for calendars
+    FreeBusy {
+        /// Start time (RFC3339)
+        #[arg(long)]
+        time_min: String,
+        /// End time (RFC3339)
+        #[arg(long)]
+        time_max: String,
+        /// Calendar IDs to check (comma-separated, use "primary" for your calendar)
+        #[arg(long, default_value = "primary")]
+        calendars: String,
+        /// Timezone (e.g., "America/New_York")
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+}
+
+impl Command for CalendarCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::calendar);
+
+        match self {
+            CalendarCommands::List { calendar, time_min, time_max, limit, sync_token, full } => {
+                let params = list::ListEventsParams {
+                    calendar_id: calendar,
+                    time_min,
+                    time_max,
+                    max_results: limit,
+                    single_events: true,
+                    order_by: Some("startTime".to_string()),
+                    sync_token,
+                    page_token: None,
+                };
+                let events = list::list_events(&client, params).await?;
+                if full {
+                    CommandOutput::value(&events)
+                } else {
+                    // Default: minimal event data (id, summary, start, end, status)
+                    CommandOutput::value(&MinimalEventList::from_event_list(&events))
+                }
+            }
+            CalendarCommands::Get { id, calendar } => {
+                CommandOutput::value(&list::get_event(&client, &calendar, &id).await?)
+            }
+            CalendarCommands::Create { summary, start, end, description, attendees, calendar, recurrence, reminders } => {
+                let params = create::CreateEventParams {
+                    calendar_id: calendar,
+                    summary,
+                    start,
+                    end,
+                    description,
+                    location: None,
+                    attendees: attendees.as_deref().map(split_list),
+                    time_zone: None,
+                    recurrence,
+                    reminders,
+                };
+                CommandOutput::value(&create::create_event(&client, params).await?)
+            }
+            CalendarCommands::Update { id, summary, start, end, calendar } => {
+                let params = update::UpdateEventParams {
+                    calendar_id: calendar,
+                    event_id: id,
+                    summary,
+                    description: None,
+                    location: None,
+                    start,
+                    end,
+                    time_zone: None,
+                };
+                CommandOutput::value(&update::update_event(&client, params).await?)
+            }
+            CalendarCommands::Delete { id, calendar } => {
+                delete::delete_event(&client, &calendar, &id).await?;
+                Ok(CommandOutput::success("Event deleted"))
+            }
+            CalendarCommands::FreeBusy { time_min, time_max, calendars, timezone } => {
+                let params = list::FreeBusyParams {
+                    time_min,
+                    time_max,
+                    calendars: split_list(&calendars),
+                    time_zone: timezone,
+                };
+                CommandOutput::value(&list::query_free_busy(&client, params).await?)
+            }
+        }
+    }
+}
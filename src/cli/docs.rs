@@ -0,0 +1,180 @@
+//! Google Docs subcommands
+
+use clap::Subcommand;
+
+use super::{export_document, written_output, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::docs::{create, get, update};
+use crate::commands::drive;
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum DocsCommands {
+    /// Get document content
+    Get {
+        /// Document ID
+        id: String,
+        /// Output as markdown
+        #[arg(long)]
+        markdown: bool,
+        /// Output as plain text (most token-efficient)
+        #[arg(long)]
+        text: bool,
+    },
+    /// Append text to document
+    Append {
+        /// Document ID
+        id: String,
+        /// Text to append
+        text: String,
+    },
+    /// Create a new document
+    Create {
+        /// Document title
+        title: String,
+    },
+    /// Replace text in document
+    Replace {
+        /// Document ID
+        id: String,
+        /// Text to find
+        #[arg(long)]
+        find: String,
+        /// Text to replace with
+        #[arg(long, name = "with")]
+        replace_with: String,
+        /// Match case
+        #[arg(long)]
+        match_case: bool,
+    },
+    /// Move document to trash
+    Delete {
+        /// Document ID
+        id: String,
+    },
+    /// Insert an image into document
+    InsertImage {
+        /// Document ID
+        id: String,
+        /// Image URL (must be publicly accessible)
+        #[arg(long)]
+        uri: String,
+        /// Insert position (index in document, use 1 for start, or omit to append)
+        #[arg(long)]
+        index: Option<i64>,
+        /// Image width in points
+        #[arg(long)]
+        width: Option<f64>,
+        /// Image height in points
+        #[arg(long)]
+        height: Option<f64>,
+    },
+    /// Insert a table into document
+    InsertTable {
+        /// Document ID
+        id: String,
+        /// Number of rows
+        #[arg(long)]
+        rows: i64,
+        /// Number of columns
+        #[arg(long)]
+        columns: i64,
+        /// Insert position (index in document, omit to append at end)
+        #[arg(long)]
+        index: Option<i64>,
+    },
+    /// Export document to file
+    Export {
+        /// Document ID
+        id: String,
+        /// Output file path, or - for stdout
+        #[arg(long, short)]
+        output: String,
+        /// Export format: pdf, docx, txt, html, odt, rtf, epub
+        #[arg(long, short, default_value = "pdf")]
+        format: String,
+    },
+}
+
+impl Command for DocsCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::docs);
+
+        match self {
+            DocsCommands::Get { id, markdown, text } => {
+                let doc = get::get_document(&client, &id).await?;
+                if text {
+                    // Plain text output (most token-efficient)
+                    Ok(CommandOutput::Text(get::document_to_text(&doc)))
+                } else if markdown {
+                    Ok(CommandOutput::Text(get::document_to_markdown(&doc)))
+                } else {
+                    CommandOutput::value(&doc)
+                }
+            }
+            DocsCommands::Append { id, text } => {
+                CommandOutput::value(&update::append_text(&client, &id, &text).await?)
+            }
+            DocsCommands::Create { title } => {
+                CommandOutput::value(&create::create_document(&client, &title).await?)
+            }
+            DocsCommands::Replace { id, find, replace_with, match_case } => {
+                let response = update::replace_text(&client, &id, &find, &replace_with, match_case).await?;
+                CommandOutput::value(&response)
+            }
+            DocsCommands::Delete { id } => {
+                // Documents are deleted by moving them to the Drive trash
+                let drive_client = ctx.client(ApiClient::drive);
+                let file = drive::delete::trash_file(&drive_client, &id).await?;
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "id": file.id,
+                    "trashed": true
+                })))
+            }
+            DocsCommands::InsertImage { id, uri, index, width, height } => {
+                let response = match index {
+                    Some(idx) => update::insert_image(&client, &id, &uri, idx, width, height).await?,
+                    None => update::append_image(&client, &id, &uri, width, height).await?,
+                };
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "documentId": response.document_id,
+                    "uri": uri
+                })))
+            }
+            DocsCommands::InsertTable { id, rows, columns, index } => {
+                let response = match index {
+                    Some(idx) => update::insert_table(&client, &id, rows, columns, idx).await?,
+                    None => update::append_table(&client, &id, rows, columns).await?,
+                };
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "documentId": response.document_id,
+                    "rows": rows,
+                    "columns": columns
+                })))
+            }
+            DocsCommands::Export { id, output, format } => {
+                let mime_type = match format.to_lowercase().as_str() {
+                    "pdf" => "application/pdf",
+                    "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                    "txt" | "text" => "text/plain",
+                    "html" => "text/html",
+                    "odt" => "application/vnd.oasis.opendocument.text",
+                    "rtf" => "application/rtf",
+                    "epub" => "application/epub+zip",
+                    _ => {
+                        return Err(WorkspaceError::InvalidInput(format!(
+                            "Unsupported format '{}'. Supported: pdf, docx, txt, html, odt, rtf, epub",
+                            format
+                        )));
+                    }
+                };
+
+                let result = export_document(ctx, &id, &id, mime_type, &output, &format).await?;
+                Ok(written_output(&output, result))
+            }
+        }
+    }
+}
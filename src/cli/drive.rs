@@ -0,0 +1,534 @@
+//! Google Drive subcommands
+
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+use super::{written_output, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::drive::{delete, download, list, mkdir, operations, share, sync, tree, upload, watch};
+use crate::utils::glob::GlobFilter;
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum DriveCommands {
+    /// List files
+    List {
+        /// Search query (Drive query syntax)
+        #[arg(long)]
+        query: Option<String>,
+        /// Maximum results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Parent folder ID
+        #[arg(long)]
+        parent: Option<String>,
+        /// Order by field
+        #[arg(long)]
+        order_by: Option<String>,
+    },
+    /// Upload a file, or a directory with --recursive
+    #[command(long_about = "Upload a file, or a directory with --recursive.\n\n\
+        With --recursive the directory becomes a new Drive folder (named after it, or\n\
+        --name) with the local tree mirrored below it. Files are uploaded concurrently.\n\
+        Globs without '/' match file names at any depth; excluded directories are\n\
+        skipped entirely. The output lists every folder and file with its status;\n\
+        entries that can't be read, such as dangling symlinks, are listed as skipped.\n\n\
+        Files over 5MB use a resumable session that is saved in the config directory.\n\
+        If the process dies, uploading the same unchanged file again continues where\n\
+        the server left off, and --resume finishes every interrupted upload. Failed\n\
+        chunks are retried. Every upload is checked against the md5 of what was\n\
+        sent (converted files have no md5 on Drive and are not checked).\n\n\
+        With - as the file, stdin is streamed in chunks (memory stays bounded) to a\n\
+        new file named by --name.\n\n\
+        --convert turns Word/ODT/RTF/Markdown/HTML/text files into Google Docs,\n\
+        Excel/ODS/CSV/TSV into Sheets and PowerPoint/ODP into Slides, dropping the\n\
+        extension from the name. With --recursive, other files upload unchanged.\n\n\
+        Examples:\n  \
+        workspace-cli drive upload ./site --parent <folderId> --recursive\n  \
+        workspace-cli drive upload ./docs --recursive --include '*.md' --exclude drafts\n  \
+        workspace-cli drive upload --resume\n  \
+        workspace-cli drive upload ./budget.xlsx --convert\n  \
+        pg_dump mydb | workspace-cli drive upload - --name mydb.sql --mime application/sql")]
+    Upload {
+        /// Local file (or directory with --recursive), or - for stdin
+        #[arg(required_unless_present = "resume")]
+        file: Option<String>,
+        /// Finish uploads interrupted by a crash or restart
+        #[arg(long, conflicts_with_all = ["file", "recursive"])]
+        resume: bool,
+        /// Destination folder ID
+        #[arg(long)]
+        parent: Option<String>,
+        /// Custom name for the uploaded file or folder (required for stdin)
+        #[arg(long)]
+        name: Option<String>,
+        /// MIME type of the content (guessed from the name by default)
+        #[arg(long, conflicts_with = "recursive")]
+        mime: Option<String>,
+        /// Convert Office, CSV, Markdown and HTML files to Docs, Sheets or Slides
+        #[arg(long)]
+        convert: bool,
+        /// Upload a directory and everything below it
+        #[arg(long)]
+        recursive: bool,
+        /// Only transfer files matching this glob (repeatable)
+        #[arg(long, requires = "recursive", action = clap::ArgAction::Append)]
+        include: Vec<String>,
+        /// Skip files and directories matching this glob (repeatable)
+        #[arg(long, requires = "recursive", action = clap::ArgAction::Append)]
+        exclude: Vec<String>,
+        /// Parallel uploads with --recursive
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+        /// Also write the JSON manifest of results to this file
+        #[arg(long, requires = "recursive")]
+        manifest: Option<String>,
+    },
+    /// Download a file, or a folder with --recursive
+    #[command(long_about = "Download a file, or a folder with --recursive.\n\n\
+        A Google Doc, Sheet, Slides or Drawing is exported instead, as docx, xlsx,\n\
+        pptx or png unless --export-format says otherwise; without -o it is saved\n\
+        under its Drive name with that extension.\n\n\
+        With --recursive the folder tree is recreated locally (in -o, or a directory\n\
+        named after the folder). Google Docs, Sheets, Slides and Drawings are exported;\n\
+        pick formats with --export KIND=FORMAT (defaults: docs=docx, sheets=xlsx,\n\
+        slides=pptx, drawing=png). Other Google types such as Forms are skipped.\n\n\
+        Examples:\n  \
+        workspace-cli drive download <docId> --export-format pdf\n  \
+        workspace-cli drive download <folderId> --recursive -o ./backup\n  \
+        workspace-cli drive download <folderId> --recursive --export docs=pdf --include '*.pdf'")]
+    Download {
+        /// File ID (or folder ID with --recursive)
+        id: String,
+        /// Output path, or - for stdout
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+        /// Export format for a Google Doc, Sheet, Slides or Drawing (default docx, xlsx, pptx, png)
+        #[arg(long, conflicts_with = "recursive")]
+        export_format: Option<String>,
+        /// Download a folder and everything below it
+        #[arg(long)]
+        recursive: bool,
+        /// Only transfer files matching this glob (repeatable)
+        #[arg(long, requires = "recursive", action = clap::ArgAction::Append)]
+        include: Vec<String>,
+        /// Skip files and folders matching this glob (repeatable)
+        #[arg(long, requires = "recursive", action = clap::ArgAction::Append)]
+        exclude: Vec<String>,
+        /// Export format for Google-native files: docs|sheets|slides|drawing=FORMAT (repeatable)
+        #[arg(long, value_name = "KIND=FORMAT", requires = "recursive", action = clap::ArgAction::Append)]
+        export: Vec<String>,
+        /// Parallel downloads with --recursive
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+        /// Also write the JSON manifest of results to this file
+        #[arg(long, requires = "recursive")]
+        manifest: Option<String>,
+    },
+    /// Sync a local directory with a Drive folder
+    #[command(long_about = "Sync a local directory with a Drive folder, transferring only what changed.\n\n\
+        Files are compared by md5Checksum, size and modifiedTime against the state\n\
+        recorded at the last sync (kept per directory/folder pair in the config\n\
+        directory). After the first run, remote changes come from the Drive changes\n\
+        feed instead of a full listing. Google Docs, Sheets and Slides are skipped,\n\
+        as are local entries that can't be read (they are not treated as deleted).\n\n\
+        Modes: push applies local changes to Drive, pull applies Drive changes\n\
+        locally, bidirectional (default) does both. A deletion on one side is\n\
+        propagated unless the other side modified the file.\n\n\
+        Files changed on both sides are conflicts, handled by --conflict:\n  \
+        report     list them and change nothing (default)\n  \
+        newer      the most recently modified version wins\n  \
+        local      the local version wins\n  \
+        remote     the Drive version wins\n  \
+        keep-both  keep the Drive version as a \"(conflict <time>)\" copy on both sides\n\n\
+        Examples:\n  \
+        workspace-cli drive sync ./notes <folderId>\n  \
+        workspace-cli drive sync ./site <folderId> --mode push --exclude node_modules\n  \
+        workspace-cli drive sync ./notes <folderId> --conflict newer --dry-run")]
+    Sync {
+        /// Local directory
+        local_dir: String,
+        /// Drive folder ID
+        folder_id: String,
+        /// Sync direction: push, pull, bidirectional
+        #[arg(long, default_value = "bidirectional")]
+        mode: String,
+        /// Conflict policy: report, newer, local, remote, keep-both
+        #[arg(long, default_value = "report")]
+        conflict: String,
+        /// Only sync files matching this glob (repeatable)
+        #[arg(long, action = clap::ArgAction::Append)]
+        include: Vec<String>,
+        /// Skip files and folders matching this glob (repeatable)
+        #[arg(long, action = clap::ArgAction::Append)]
+        exclude: Vec<String>,
+        /// Show what would be transferred without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Parallel transfers
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
+    /// Get file metadata
+    Get {
+        /// File ID
+        id: String,
+    },
+    /// Permanently delete a file (bypasses trash)
+    Delete {
+        /// File ID to delete
+        id: String,
+    },
+    /// Move file to trash
+    Trash {
+        /// File ID to trash
+        id: String,
+    },
+    /// Restore file from trash
+    Untrash {
+        /// File ID to restore
+        id: String,
+    },
+    /// Create a new folder
+    Mkdir {
+        /// Folder name
+        name: String,
+        /// Parent folder ID
+        #[arg(long)]
+        parent: Option<String>,
+    },
+    /// Move a file to a different folder
+    Move {
+        /// File ID to move
+        id: String,
+        /// Destination folder ID
+        #[arg(long)]
+        to: String,
+    },
+    /// Copy a file
+    Copy {
+        /// File ID to copy
+        id: String,
+        /// New name for the copy
+        #[arg(long)]
+        name: Option<String>,
+        /// Destination folder ID
+        #[arg(long)]
+        parent: Option<String>,
+    },
+    /// Rename a file
+    Rename {
+        /// File ID to rename
+        id: String,
+        /// New name
+        name: String,
+    },
+    /// Share a file
+    Share {
+        /// File ID to share
+        id: String,
+        /// Share with this email address
+        #[arg(long)]
+        email: Option<String>,
+        /// Share with anyone (make public)
+        #[arg(long)]
+        anyone: bool,
+        /// Role: reader, commenter, writer
+        #[arg(long, default_value = "reader")]
+        role: String,
+    },
+    /// List permissions on a file
+    Permissions {
+        /// File ID
+        id: String,
+    },
+    /// Remove a permission from a file
+    Unshare {
+        /// File ID
+        id: String,
+        /// Permission ID to remove
+        permission_id: String,
+    },
+    /// Get a start page token for watching changes
+    StartPageToken,
+    /// Watch for changes to Drive (requires webhook URL)
+    Watch {
+        /// Page token from start-page-token command
+        #[arg(long)]
+        page_token: String,
+        /// Webhook URL to receive notifications (must be HTTPS)
+        #[arg(long)]
+        webhook: String,
+        /// Optional verification token
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Watch for changes to a specific file
+    WatchFile {
+        /// File ID to watch
+        id: String,
+        /// Webhook URL to receive notifications (must be HTTPS)
+        #[arg(long)]
+        webhook: String,
+        /// Optional verification token
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Stop watching for changes
+    StopWatch {
+        /// Channel ID (from watch response)
+        #[arg(long)]
+        channel_id: String,
+        /// Resource ID (from watch response)
+        #[arg(long)]
+        resource_id: String,
+    },
+    /// List recent changes to Drive
+    Changes {
+        /// Page token (from start-page-token or previous changes response)
+        #[arg(long)]
+        page_token: String,
+    },
+}
+
+impl Command for DriveCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::drive);
+
+        match self {
+            DriveCommands::List { query, limit, parent, order_by } => {
+                // Build query with optional parent filter
+                let query = match (query, parent) {
+                    (Some(q), Some(p)) => Some(format!("'{}' in parents and ({})", p, q)),
+                    (Some(q), None) => Some(q),
+                    (None, Some(p)) => Some(format!("'{}' in parents", p)),
+                    (None, None) => None,
+                };
+                let params = list::ListParams {
+                    query,
+                    max_results: limit,
+                    page_token: None,
+                    fields: None,
+                    order_by,
+                };
+                CommandOutput::value(&list::list_files(&client, params).await?)
+            }
+            DriveCommands::Upload { file, resume, parent, name, mime, convert, recursive, include, exclude, concurrency, manifest } => {
+                let Some(file) = file.filter(|_| !resume) else {
+                    let results = upload::resume_uploads().await;
+                    client.invalidate_cache();
+                    let value = serde_json::json!({ "resumed": results });
+                    return if results.iter().any(|r| r.status == "failed") {
+                        Ok(CommandOutput::Partial(value))
+                    } else {
+                        Ok(CommandOutput::Value(value))
+                    };
+                };
+                if file == "-" {
+                    if recursive {
+                        return Err(WorkspaceError::InvalidInput("--recursive can't read from stdin".to_string()));
+                    }
+                    let name = name.ok_or_else(|| WorkspaceError::InvalidInput(
+                        "--name is required when uploading from stdin".to_string()
+                    ))?;
+                    let mime = mime.unwrap_or_else(|| mime_guess::from_path(&name).first_or_octet_stream().to_string());
+                    if convert {
+                        check_convertible(&mime)?;
+                    }
+                    let access_token = ctx.access_token().await?;
+                    let file = upload::upload_stream(&access_token, tokio::io::stdin(), &name, &mime, parent, convert).await?;
+                    client.invalidate_cache();
+                    return CommandOutput::value(&file);
+                }
+                if recursive {
+                    let options = tree::TreeUploadOptions {
+                        parent_id: parent,
+                        name,
+                        filter: GlobFilter::new(include, exclude),
+                        concurrency,
+                        convert,
+                    };
+                    let access_token = ctx.access_token().await?;
+                    let result = tree::upload_tree(&client, &access_token, Path::new(&file), options).await?;
+                    return tree_output(&result, manifest.as_deref());
+                }
+                if Path::new(&file).is_dir() {
+                    return Err(WorkspaceError::InvalidInput(format!("{} is a directory; use --recursive", file)));
+                }
+                if convert {
+                    let content_mime = mime.clone().unwrap_or_else(|| mime_guess::from_path(&file).first_or_octet_stream().to_string());
+                    check_convertible(&content_mime)?;
+                }
+                let params = upload::UploadParams {
+                    file_path: file,
+                    name,
+                    parent_id: parent,
+                    mime_type: mime,
+                    convert,
+                };
+                // Uploads go to the upload endpoint directly with the raw token
+                let access_token = ctx.access_token().await?;
+                let file = upload::upload_file(&access_token, params).await?;
+                client.invalidate_cache();
+                CommandOutput::value(&file)
+            }
+            DriveCommands::Download { id, output, export_format, recursive, include, exclude, export, concurrency, manifest } => {
+                if recursive && output.as_deref() == Some("-") {
+                    return Err(WorkspaceError::InvalidInput("--recursive can't write to stdout".to_string()));
+                }
+                if recursive {
+                    let options = tree::TreeDownloadOptions {
+                        filter: GlobFilter::new(include, exclude),
+                        formats: tree::ExportFormats::parse(&export)?,
+                        concurrency,
+                    };
+                    let access_token = ctx.access_token().await?;
+                    let dir = output.map(PathBuf::from);
+                    let result = tree::download_tree(&client, &access_token, &id, dir.as_deref(), options).await?;
+                    return tree_output(&result, manifest.as_deref());
+                }
+                // Google Docs, Sheets, Slides and Drawings have no content to download, only exports
+                let metadata = list::get_file(&client, &id, Some("id,name,mimeType")).await?;
+                if metadata.mime_type == tree::FOLDER_MIME {
+                    return Err(WorkspaceError::InvalidInput(format!("{} is a folder; use --recursive", id)));
+                }
+                let access_token = ctx.access_token().await?;
+                if metadata.mime_type.starts_with("application/vnd.google-apps.") {
+                    let (export_mime, extension) = tree::export_target(&metadata.mime_type, export_format.as_deref())?;
+                    let output = output.unwrap_or_else(|| format!("{}.{}", metadata.name.replace(['/', '\\'], "_"), extension));
+                    let bytes = download::export_file(&access_token, &id, export_mime, Path::new(&output)).await?;
+                    return Ok(written_output(&output, serde_json::json!({
+                        "status": "success",
+                        "file": output,
+                        "bytes": bytes,
+                        "exportedAs": extension
+                    })));
+                }
+                let output_path = PathBuf::from(output.unwrap_or_else(|| id.clone()));
+                let bytes = download::download_file(&access_token, &id, &output_path).await?;
+                let output = output_path.display().to_string();
+                Ok(written_output(&output, serde_json::json!({
+                    "status": "success",
+                    "file": output,
+                    "bytes": bytes
+                })))
+            }
+            DriveCommands::Sync { local_dir, folder_id, mode, conflict, include, exclude, dry_run, concurrency } => {
+                let options = sync::SyncOptions {
+                    mode: sync::SyncMode::from_str(&mode).ok_or_else(|| WorkspaceError::InvalidInput(
+                        format!("Invalid sync mode '{}'. Use push, pull or bidirectional", mode)
+                    ))?,
+                    conflict: sync::ConflictPolicy::from_str(&conflict).ok_or_else(|| WorkspaceError::InvalidInput(
+                        format!("Invalid conflict policy '{}'. Use report, newer, local, remote or keep-both", conflict)
+                    ))?,
+                    filter: GlobFilter::new(include, exclude),
+                    dry_run,
+                    concurrency,
+                };
+                let access_token = ctx.access_token().await?;
+                let result = sync::sync(&client, &access_token, Path::new(&local_dir), &folder_id, options).await?;
+                let value = serde_json::to_value(&result)?;
+                if result.failed > 0 {
+                    Ok(CommandOutput::Partial(value))
+                } else {
+                    Ok(CommandOutput::Value(value))
+                }
+            }
+            DriveCommands::Get { id } => {
+                CommandOutput::value(&list::get_file(&client, &id, None).await?)
+            }
+            DriveCommands::Delete { id } => {
+                delete::delete_file(&client, &id).await?;
+                Ok(CommandOutput::success("File deleted permanently"))
+            }
+            DriveCommands::Trash { id } => {
+                CommandOutput::value(&delete::trash_file(&client, &id).await?)
+            }
+            DriveCommands::Untrash { id } => {
+                CommandOutput::value(&delete::untrash_file(&client, &id).await?)
+            }
+            DriveCommands::Mkdir { name, parent } => {
+                CommandOutput::value(&mkdir::create_folder(&client, &name, parent.as_deref()).await?)
+            }
+            DriveCommands::Move { id, to } => {
+                CommandOutput::value(&operations::move_file(&client, &id, &to, true).await?)
+            }
+            DriveCommands::Copy { id, name, parent } => {
+                let copied = operations::copy_file(&client, &id, name.as_deref(), parent.as_deref()).await?;
+                CommandOutput::value(&copied)
+            }
+            DriveCommands::Rename { id, name } => {
+                CommandOutput::value(&operations::rename_file(&client, &id, &name).await?)
+            }
+            DriveCommands::Share { id, email, anyone, role } => {
+                let permission = if anyone {
+                    share::share_with_anyone(&client, &id, &role).await?
+                } else if let Some(email) = email {
+                    share::share_with_user(&client, &id, &email, &role).await?
+                } else {
+                    return Err(WorkspaceError::InvalidInput("Must specify --email or --anyone".to_string()));
+                };
+                CommandOutput::value(&permission)
+            }
+            DriveCommands::Permissions { id } => {
+                CommandOutput::value(&share::list_permissions(&client, &id).await?)
+            }
+            DriveCommands::Unshare { id, permission_id } => {
+                share::remove_permission(&client, &id, &permission_id).await?;
+                Ok(CommandOutput::success("Permission removed"))
+            }
+            DriveCommands::StartPageToken => {
+                CommandOutput::value(&watch::get_start_page_token(&client).await?)
+            }
+            DriveCommands::Watch { page_token, webhook, token } => {
+                let params = watch::WatchChangesParams {
+                    page_token,
+                    webhook_url: webhook,
+                    token,
+                    expiration: None,
+                };
+                CommandOutput::value(&watch::watch_changes(&client, params).await?)
+            }
+            DriveCommands::WatchFile { id, webhook, token } => {
+                let params = watch::WatchFileParams {
+                    file_id: id,
+                    webhook_url: webhook,
+                    token,
+                    expiration: None,
+                };
+                CommandOutput::value(&watch::watch_file(&client, params).await?)
+            }
+            DriveCommands::StopWatch { channel_id, resource_id } => {
+                watch::stop_channel(&client, &channel_id, &resource_id).await?;
+                Ok(CommandOutput::success("Watch channel stopped"))
+            }
+            DriveCommands::Changes { page_token } => {
+                CommandOutput::value(&watch::list_changes(&client, &page_token).await?)
+            }
+        }
+    }
+}
+
+/// Reject `--convert` for content that has no Google-native equivalent
+fn check_convertible(mime_type: &str) -> Result<(), WorkspaceError> {
+    if upload::conversion_target(mime_type).is_none() {
+        return Err(WorkspaceError::InvalidInput(format!(
+            "Can't convert {} content; --convert handles Office, OpenDocument, CSV/TSV, Markdown, HTML and text files",
+            mime_type
+        )));
+    }
+    Ok(())
+}
+
+/// Output of a recursive transfer, optionally also written as a manifest file
+fn tree_output(result: &tree::TreeResult, manifest: Option<&str>) -> Result<CommandOutput, WorkspaceError> {
+    let value = serde_json::to_value(result)?;
+    if let Some(path) = manifest {
+        std::fs::write(path, serde_json::to_string_pretty(&value)?)?;
+    }
+    if result.failed > 0 {
+        Ok(CommandOutput::Partial(value))
+    } else {
+        Ok(CommandOutput::Value(value))
+    }
+}
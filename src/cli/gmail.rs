@@ -0,0 +1,1595 @@
+//! Gmail subcommands
+
+use clap::Subcommand;
+
+use super::{read_input, split_list, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::gmail::types::{DraftResponse, ModifyResponse, SendResponse};
+use crate::commands::gmail::{archive, attachments, bulk, delete, drafts, extract, filter_sync, filters, merge, get, history, labels, list, queue, send, settings, stats, threads, trash};
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum GmailCommands {
+    /// List messages
+    List {
+        /// Search query (Gmail search syntax)
+        #[arg(long)]
+        query: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Label name or ID to filter by
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Get a specific message
+    Get {
+        /// Message ID
+        id: String,
+        /// Return full message structure (includes all headers, MIME parts, raw body)
+        #[arg(long)]
+        full: bool,
+        /// Add links, unsubscribe info, calendar invites, SPF/DKIM/DMARC results and tracking pixels
+        #[arg(long, conflicts_with = "full")]
+        extract: bool,
+    },
+    /// Unsubscribe from the mailing list a message came from
+    #[command(long_about = "Unsubscribe from the mailing list a message came from.\n\n\
+        Uses the sender's RFC 8058 one-click unsubscribe when offered, otherwise sends\n\
+        the mailto: unsubscribe request from List-Unsubscribe. Senders that only link\n\
+        to a web page can't be unsubscribed automatically; the URL is reported instead.")]
+    Unsubscribe {
+        /// Message ID
+        id: String,
+        /// Show which method would be used without unsubscribing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Send an email
+    Send {
+        /// Recipient email
+        #[arg(long)]
+        to: String,
+        /// Send-as alias to send from (see `gmail sendas list`)
+        #[arg(long)]
+        from: Option<String>,
+        /// CC recipients (comma-separated)
+        #[arg(long)]
+        cc: Option<String>,
+        /// BCC recipients (comma-separated)
+        #[arg(long)]
+        bcc: Option<String>,
+        /// Email subject
+        #[arg(long)]
+        subject: String,
+        /// Email body (or use --body-file)
+        #[arg(long)]
+        body: Option<String>,
+        /// Read body from file
+        #[arg(long)]
+        body_file: Option<String>,
+        /// File attachments (can be specified multiple times)
+        #[arg(long, action = clap::ArgAction::Append)]
+        attachment: Vec<String>,
+        /// Send as HTML content
+        #[arg(long)]
+        html: bool,
+        /// Render the body from Markdown to HTML (with a plain-text alternative)
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+        /// Inline image referenced from the body as cid:<cid> (format: cid=path, repeatable)
+        #[arg(long, value_name = "CID=PATH", action = clap::ArgAction::Append)]
+        inline_image: Vec<String>,
+        /// Queue the message to send later: RFC 3339, 'YYYY-MM-DD HH:MM' (local) or +30m/+2h/+1d
+        #[arg(long, value_name = "TIME")]
+        at: Option<String>,
+        /// With --at, also save the message as a Gmail draft until it is sent
+        #[arg(long, requires = "at")]
+        with_draft: bool,
+    },
+    /// Create a draft
+    Draft {
+        /// Recipient email
+        #[arg(long)]
+        to: String,
+        /// Send-as alias to send from (see `gmail sendas list`)
+        #[arg(long)]
+        from: Option<String>,
+        /// CC recipients (comma-separated)
+        #[arg(long)]
+        cc: Option<String>,
+        /// BCC recipients (comma-separated)
+        #[arg(long)]
+        bcc: Option<String>,
+        /// Email subject
+        #[arg(long)]
+        subject: String,
+        /// Email body
+        #[arg(long)]
+        body: Option<String>,
+        /// File attachments (can be specified multiple times)
+        #[arg(long, action = clap::ArgAction::Append)]
+        attachment: Vec<String>,
+        /// Send as HTML content
+        #[arg(long)]
+        html: bool,
+        /// Render the body from Markdown to HTML (with a plain-text alternative)
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+        /// Inline image referenced from the body as cid:<cid> (format: cid=path, repeatable)
+        #[arg(long, value_name = "CID=PATH", action = clap::ArgAction::Append)]
+        inline_image: Vec<String>,
+    },
+    /// Permanently delete a message (bypasses trash)
+    Delete {
+        /// Message ID to delete
+        id: String,
+    },
+    /// Move message to trash
+    Trash {
+        /// Message ID to trash
+        id: String,
+    },
+    /// Remove message from trash
+    Untrash {
+        /// Message ID to untrash
+        id: String,
+    },
+    /// List all labels
+    Labels,
+    /// Create, rename, recolor or delete a label
+    Label {
+        #[command(subcommand)]
+        command: LabelCommands,
+    },
+    /// Modify labels on a message
+    Modify {
+        /// Message ID
+        id: String,
+        /// Labels to add (comma-separated names or IDs)
+        #[arg(long)]
+        add_labels: Option<String>,
+        /// Labels to remove (comma-separated names or IDs)
+        #[arg(long)]
+        remove_labels: Option<String>,
+        /// Create labels in --add-labels that do not exist yet
+        #[arg(long)]
+        create_labels: bool,
+        /// Mark as read
+        #[arg(long)]
+        mark_read: bool,
+        /// Mark as unread
+        #[arg(long)]
+        mark_unread: bool,
+        /// Star message
+        #[arg(long)]
+        star: bool,
+        /// Unstar message
+        #[arg(long)]
+        unstar: bool,
+        /// Archive message (remove from inbox)
+        #[arg(long)]
+        archive: bool,
+    },
+    /// Reply to a message
+    Reply {
+        /// Message ID to reply to
+        id: String,
+        /// Reply body (or use --body-file)
+        #[arg(long)]
+        body: Option<String>,
+        /// Read body from file
+        #[arg(long)]
+        body_file: Option<String>,
+        /// Reply-all (include Cc recipients)
+        #[arg(long)]
+        all: bool,
+        /// Send as HTML content
+        #[arg(long)]
+        html: bool,
+        /// Render the body from Markdown to HTML (with a plain-text alternative)
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+        /// Inline image referenced from the body as cid:<cid> (format: cid=path, repeatable)
+        #[arg(long, value_name = "CID=PATH", action = clap::ArgAction::Append)]
+        inline_image: Vec<String>,
+    },
+    /// List, read, revise, send or delete drafts
+    Drafts {
+        #[command(subcommand)]
+        command: DraftsCommands,
+    },
+    /// Create a draft reply to a message
+    ReplyDraft {
+        /// Message ID to reply to
+        id: String,
+        /// Reply body
+        #[arg(long)]
+        body: Option<String>,
+        /// Reply-all (include Cc recipients)
+        #[arg(long)]
+        all: bool,
+        /// Send as HTML content
+        #[arg(long)]
+        html: bool,
+        /// Render the body from Markdown to HTML (with a plain-text alternative)
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+        /// Inline image referenced from the body as cid:<cid> (format: cid=path, repeatable)
+        #[arg(long, value_name = "CID=PATH", action = clap::ArgAction::Append)]
+        inline_image: Vec<String>,
+    },
+    /// Mailbox analytics: top senders, volume, unread, response time, storage
+    #[command(long_about = "Mailbox analytics for the messages matching a query.\n\n\
+        Reports top senders and domains, volume per day (received/sent) and per label,\n\
+        unread counts, your average and median reply time in threads you took part in,\n\
+        the largest messages and which senders' attachments use the most storage.\n\
+        Metadata is fetched in batches, so tens of thousands of messages are fine.\n\
+        Reply times only see messages matched by the query, so keep sent mail in it.\n\n\
+        Examples:\n  \
+        workspace-cli gmail stats --since 90d\n  \
+        workspace-cli gmail stats --query \"in:inbox\" --since 7d --top 20")]
+    Stats {
+        /// Search query (Gmail search syntax); all mail except spam and trash by default
+        #[arg(long)]
+        query: Option<String>,
+        /// Only messages newer than this: 90d, 12w, 6m, 1y or YYYY-MM-DD
+        #[arg(long)]
+        since: Option<String>,
+        /// Maximum number of messages to analyze (newest first)
+        #[arg(long, default_value = "50000")]
+        max: usize,
+        /// Entries in each top list (senders, domains, largest messages, storage)
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+    /// List attachments in a message, or pull attachments by query
+    #[command(args_conflicts_with_subcommands = true)]
+    Attachments {
+        /// Message ID
+        id: Option<String>,
+        #[command(subcommand)]
+        command: Option<AttachmentsCommands>,
+    },
+    /// Download an attachment from a message
+    Attachment {
+        /// Message ID
+        message_id: String,
+        /// Attachment ID
+        attachment_id: String,
+        /// Output file path
+        #[arg(long, short = 'o')]
+        output: String,
+    },
+    /// Forward a message to another recipient
+    Forward {
+        /// Message ID to forward
+        id: String,
+        /// Recipient email
+        #[arg(long)]
+        to: String,
+        /// CC recipients (comma-separated)
+        #[arg(long)]
+        cc: Option<String>,
+        /// BCC recipients (comma-separated)
+        #[arg(long)]
+        bcc: Option<String>,
+        /// Optional message to include before the forwarded content
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// List, export or apply email filters
+    Filters {
+        #[command(subcommand)]
+        command: Option<FiltersCommands>,
+    },
+    /// Create an email filter
+    CreateFilter {
+        /// Match emails from this sender
+        #[arg(long)]
+        from: Option<String>,
+        /// Match emails to this recipient
+        #[arg(long)]
+        to: Option<String>,
+        /// Match emails with this subject
+        #[arg(long)]
+        subject: Option<String>,
+        /// Match emails containing this query (Gmail search syntax)
+        #[arg(long)]
+        query: Option<String>,
+        /// Match emails with attachments
+        #[arg(long)]
+        has_attachment: bool,
+        /// Labels to add (comma-separated names or IDs)
+        #[arg(long)]
+        add_labels: Option<String>,
+        /// Labels to remove (comma-separated names or IDs)
+        #[arg(long)]
+        remove_labels: Option<String>,
+        /// Create labels in --add-labels that do not exist yet
+        #[arg(long)]
+        create_labels: bool,
+        /// Forward matching emails to this address
+        #[arg(long)]
+        forward_to: Option<String>,
+        /// Skip the inbox (archive)
+        #[arg(long)]
+        skip_inbox: bool,
+        /// Mark as read
+        #[arg(long)]
+        mark_read: bool,
+        /// Star the message
+        #[arg(long)]
+        star: bool,
+        /// Move to trash
+        #[arg(long)]
+        trash: bool,
+    },
+    /// Delete an email filter
+    DeleteFilter {
+        /// Filter ID to delete
+        id: String,
+    },
+    /// List mailbox changes since a history ID
+    #[command(long_about = "List mailbox changes (messages added/deleted, labels added/removed)\n\
+        since a history ID, using the Gmail History API. Added messages include\n\
+        Subject, From and Date.\n\n\
+        Without --all one page is returned; when it carries a nextPageToken, pass\n\
+        it as --page-token (with the same --since) for the next page. The returned\n\
+        historyId is only a safe --since for the next call once no nextPageToken\n\
+        is left, since it is the mailbox's current position.\n\n\
+        Examples:\n  \
+        workspace-cli gmail history --since 123456 --all\n  \
+        workspace-cli gmail history --since 123456 --page-token <nextPageToken>\n  \
+        workspace-cli gmail history --since 123456 --types messageAdded --label INBOX")]
+    History {
+        /// History ID to start from (e.g. a previous historyId)
+        #[arg(long)]
+        since: String,
+        /// Follow nextPageToken until all changes are fetched
+        #[arg(long)]
+        all: bool,
+        /// Continue from the nextPageToken of a previous call
+        #[arg(long)]
+        page_token: Option<String>,
+        /// Maximum history records per page
+        #[arg(long, default_value = "100")]
+        limit: u32,
+        /// Only changes to messages with this label (name or ID)
+        #[arg(long)]
+        label: Option<String>,
+        /// Comma-separated history types: messageAdded, messageDeleted, labelAdded, labelRemoved
+        #[arg(long)]
+        types: Option<String>,
+    },
+    /// Report changes since the last sync and remember the new position
+    #[command(long_about = "Report mailbox changes since the previous `gmail sync` for the current\n\
+        account. The last historyId is stored per account in gmail_sync.json in the\n\
+        config directory. The first run (or a run after the stored ID expired)\n\
+        records a baseline and reports no changes.")]
+    Sync,
+    /// Read a conversation thread
+    Thread {
+        #[command(subcommand)]
+        command: ThreadCommands,
+    },
+    /// Search conversation threads
+    Threads {
+        #[command(subcommand)]
+        command: ThreadsCommands,
+    },
+    /// Send one templated message per row of a Sheet or CSV file
+    #[command(long_about = "Send one templated message per row of a Sheet or CSV file.\n\n\
+        The first row holds column names; {{column}} placeholders in --subject and\n\
+        the template are replaced per row. Templates ending in .md are rendered\n\
+        from Markdown, .html templates are sent as HTML; values are escaped in both.\n\
+        Optional cc, bcc and attachments columns (paths separated by ';') apply\n\
+        per row.\n\n\
+        Results are written to the status column of the sheet (sent:<messageId>,\n\
+        draft:<draftId> or error: ...) or to <file>.merge-status.json for CSV, and\n\
+        rows already sent are skipped, so an interrupted merge can be re-run.\n\
+        After a --draft run, a normal run sends the reviewed drafts.\n\n\
+        Examples:\n  \
+        workspace-cli gmail merge --template body.md --subject \"Hi {{first_name}}\" --data contacts.csv --dry-run\n  \
+        workspace-cli gmail merge --template body.md --subject \"Hi {{first_name}}\" --data sheet:<id>!A1:Z --draft")]
+    Merge {
+        /// Body template file ({{column}} placeholders)
+        #[arg(long)]
+        template: String,
+        /// Subject template
+        #[arg(long)]
+        subject: String,
+        /// Rows to merge: sheet:<spreadsheet-id>!<range> or a CSV file path
+        #[arg(long)]
+        data: String,
+        /// Create drafts instead of sending
+        #[arg(long)]
+        draft: bool,
+        /// Render every row without sending
+        #[arg(long)]
+        dry_run: bool,
+        /// Column with the recipient address
+        #[arg(long, default_value = "email")]
+        to_column: String,
+        /// Column with Cc recipients
+        #[arg(long, default_value = "cc")]
+        cc_column: String,
+        /// Column with Bcc recipients
+        #[arg(long, default_value = "bcc")]
+        bcc_column: String,
+        /// Column with attachment paths (separated by ';')
+        #[arg(long, default_value = "attachments")]
+        attachments_column: String,
+        /// Sheet column that records the result of each row (created if missing)
+        #[arg(long, default_value = "status")]
+        status_column: String,
+        /// Maximum messages per minute
+        #[arg(long, default_value = "20")]
+        per_minute: u32,
+        /// Stop after this many messages (the rest stay pending for a later run)
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Treat the template as HTML regardless of its extension
+        #[arg(long, conflicts_with = "markdown")]
+        html: bool,
+        /// Treat the template as Markdown regardless of its extension
+        #[arg(long)]
+        markdown: bool,
+    },
+    /// Export messages matching a query to an mbox file or a directory of .eml files
+    #[command(long_about = "Export messages matching a query to an mbox file or a directory of .eml\n\
+        files (with index.json). Paths ending in .mbox are written as mbox unless\n\
+        --format says otherwise. Label names are kept in an X-Gmail-Labels header\n\
+        so `gmail import` can restore them.\n\n\
+        Examples:\n  \
+        workspace-cli gmail export --query \"label:projects/apollo\" --out apollo.mbox\n  \
+        workspace-cli gmail export --query \"before:2020/01/01\" --out ./archive-2019 --format eml")]
+    Export {
+        /// Search query (Gmail search syntax; all mail when omitted)
+        #[arg(long)]
+        query: Option<String>,
+        /// Output mbox file or EML directory
+        #[arg(long)]
+        out: String,
+        /// Archive format: mbox or eml (default from --out)
+        #[arg(long, value_parser = ["mbox", "eml"])]
+        format: Option<String>,
+        /// Maximum number of messages to export
+        #[arg(long)]
+        max: Option<usize>,
+        /// Messages fetched in parallel
+        #[arg(long, default_value = "8")]
+        concurrency: usize,
+    },
+    /// Import an .eml file, an mbox file or a directory of .eml files
+    #[command(long_about = "Import an .eml file, an mbox file or a directory of .eml files with\n\
+        users.messages.import. Dates come from each message's Date header, and\n\
+        labels are restored from X-Gmail-Labels headers (written by `gmail export`\n\
+        and Google Takeout). Labels missing from the mailbox are reported in\n\
+        unknownLabels unless --create-labels is given.")]
+    Import {
+        /// File or directory to import
+        path: String,
+        /// Labels to add to every imported message (comma-separated names or IDs)
+        #[arg(long)]
+        label: Option<String>,
+        /// Create missing labels
+        #[arg(long)]
+        create_labels: bool,
+        /// Ignore X-Gmail-Labels headers
+        #[arg(long)]
+        no_archive_labels: bool,
+        /// Use messages.insert (skips spam scanning and inbox classification)
+        #[arg(long)]
+        insert: bool,
+    },
+    /// Apply an action to every message matching a search query
+    #[command(long_about = "Apply an action to every message matching a search query.\n\n\
+        Matches are collected page by page and changed with batchModify (or\n\
+        batchDelete for `delete`) in chunks of 1000. At most --max messages are\n\
+        touched; use --dry-run to see how many would be affected first. A dry run\n\
+        with --create-labels lists the labels it would create without creating them.\n\
+        `delete` is permanent and needs the full https://mail.google.com/ scope;\n\
+        prefer `trash`.\n\n\
+        Examples:\n  \
+        workspace-cli gmail bulk archive --query \"older_than:1y category:promotions\" --dry-run\n  \
+        workspace-cli gmail bulk modify-labels --query \"from:billing@example.com\" --add-labels Receipts")]
+    Bulk {
+        /// Action to apply
+        #[arg(value_parser = ["modify-labels", "archive", "mark-read", "trash", "delete"])]
+        action: String,
+        /// Search query (Gmail search syntax)
+        #[arg(long)]
+        query: String,
+        /// Labels to add (comma-separated names or IDs, modify-labels only)
+        #[arg(long)]
+        add_labels: Option<String>,
+        /// Labels to remove (comma-separated names or IDs, modify-labels only)
+        #[arg(long)]
+        remove_labels: Option<String>,
+        /// Create labels in --add-labels that do not exist yet
+        #[arg(long)]
+        create_labels: bool,
+        /// Only count the matching messages
+        #[arg(long)]
+        dry_run: bool,
+        /// Maximum number of messages to change
+        #[arg(long, default_value = "1000")]
+        max: usize,
+    },
+    /// Messages scheduled with `gmail send --at`
+    Queue {
+        #[command(subcommand)]
+        command: QueueCommands,
+    },
+    /// View and change account settings (vacation responder, signatures, ...)
+    Settings {
+        #[command(subcommand)]
+        command: SettingsCommands,
+    },
+    /// Send-as aliases
+    #[command(name = "sendas")]
+    SendAs {
+        #[command(subcommand)]
+        command: SendAsCommands,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DraftsCommands {
+    /// List drafts
+    List {
+        /// Search query (Gmail search syntax)
+        #[arg(long)]
+        query: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+    },
+    /// Get a draft (headers and plain text body)
+    Get {
+        /// Draft ID
+        id: String,
+    },
+    /// Replace fields of a draft; unset fields keep their current value
+    #[command(long_about = "Replace fields of a draft; unset fields keep their current value.\n\n\
+        The message is rebuilt with the new content. From, In-Reply-To, References\n\
+        and the thread are preserved, so reply drafts stay in their conversation.\n\
+        Existing attachments are kept unless --clear-attachments is given.")]
+    Update {
+        /// Draft ID
+        id: String,
+        /// Recipient email
+        #[arg(long)]
+        to: Option<String>,
+        /// CC recipients (comma-separated)
+        #[arg(long)]
+        cc: Option<String>,
+        /// BCC recipients (comma-separated)
+        #[arg(long)]
+        bcc: Option<String>,
+        /// Email subject
+        #[arg(long)]
+        subject: Option<String>,
+        /// Email body
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+        /// Read email body from file
+        #[arg(long)]
+        body_file: Option<String>,
+        /// Treat the body as HTML
+        #[arg(long)]
+        html: bool,
+        /// Attachments to add (can be specified multiple times)
+        #[arg(long, action = clap::ArgAction::Append)]
+        attachment: Vec<String>,
+        /// Remove the draft's current attachments
+        #[arg(long)]
+        clear_attachments: bool,
+    },
+    /// Send a draft
+    Send {
+        /// Draft ID
+        id: String,
+    },
+    /// Delete a draft
+    Delete {
+        /// Draft ID
+        id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LabelCommands {
+    /// Create a label (use `Parent/Child` for nested labels)
+    Create {
+        /// Label name
+        name: String,
+        /// Background color from Gmail's palette (e.g. "#16a766")
+        #[arg(long)]
+        color: Option<String>,
+        /// Text color from Gmail's palette
+        #[arg(long, default_value = "#ffffff")]
+        text_color: String,
+    },
+    /// Rename or recolor a label (nested labels follow a renamed parent)
+    Update {
+        /// Label name or ID
+        label: String,
+        /// New name
+        #[arg(long)]
+        name: Option<String>,
+        /// Background color from Gmail's palette
+        #[arg(long)]
+        color: Option<String>,
+        /// Text color from Gmail's palette
+        #[arg(long, default_value = "#ffffff")]
+        text_color: String,
+    },
+    /// Delete a label
+    Delete {
+        /// Label name or ID
+        label: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AttachmentsCommands {
+    /// Download the attachments of every message matching a query
+    #[command(long_about = "Download the attachments of every message matching a query.\n\n\
+        Files are deduplicated by content (SHA-256), also against the manifest.json\n\
+        left by an earlier pull into the same directory, so re-running only fetches\n\
+        new files. manifest.json maps each file to the messages it came from.\n\
+        Template fields: {date} {from} {subject} {filename} {messageId} {hash}.\n\n\
+        Examples:\n  \
+        workspace-cli gmail attachments pull --query \"has:attachment from:invoices@vendor.com\" --dir ./out\n  \
+        workspace-cli gmail attachments pull --query \"has:attachment label:receipts\" \\\n    \
+        --template \"{date}_{from}_{filename}\" --to-drive <folderId>")]
+    Pull {
+        /// Search query (Gmail search syntax)
+        #[arg(long)]
+        query: String,
+        /// Directory for files and manifest.json (with --to-drive: manifest only)
+        #[arg(long, required_unless_present = "to_drive")]
+        dir: Option<String>,
+        /// Upload to this Drive folder instead of saving locally
+        #[arg(long, value_name = "FOLDER_ID")]
+        to_drive: Option<String>,
+        /// File name template
+        #[arg(long, default_value = "{filename}")]
+        template: String,
+        /// Maximum number of messages
+        #[arg(long)]
+        max: Option<usize>,
+        /// Messages fetched in parallel
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueueCommands {
+    /// List scheduled messages
+    List,
+    /// Cancel a scheduled message (and delete its draft)
+    Cancel {
+        /// Queue entry ID
+        id: String,
+    },
+    /// Put a failed message, or one left claimed by a crashed run, back in the queue
+    #[command(long_about = "Put a failed message, or one left claimed by a crashed run, back in the queue.\n\n\
+        A run claims each message before sending it. If the run dies mid-send the\n\
+        message stays claimed (status `sending`) and is not picked up again. Once the\n\
+        claim is 10 minutes old, retry makes the message due again with its attempts\n\
+        reset. Check Sent mail first: the message may have gone out before the crash.\n\
+        Messages with a draft are safe, since a sent draft no longer exists.\n\n\
+        Examples:\n  \
+        workspace-cli gmail queue retry 3f9c2a7b41d0")]
+    Retry {
+        /// Queue entry ID
+        id: String,
+    },
+    /// Send messages that are due
+    #[command(long_about = "Send messages that are due.\n\n\
+        Scheduled messages are stored in the config directory and only go out when\n\
+        `queue run` is called, so run it from cron or keep it going with --watch.\n\
+        Failed sends are retried on later runs, up to 5 attempts; see `queue retry`\n\
+        for messages that gave up or were left claimed by a crash. With --watch, a\n\
+        pass that fails is reported in errors and the next pass runs as usual.\n\n\
+        Examples:\n  \
+        */5 * * * * workspace-cli gmail queue run --quiet\n  \
+        workspace-cli gmail queue run --watch --interval 30")]
+    Run {
+        /// Keep running, checking the queue every --interval seconds (Ctrl-C to stop)
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between checks with --watch
+        #[arg(long, default_value = "60")]
+        interval: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FiltersCommands {
+    /// List filters as returned by the API (label IDs)
+    List,
+    /// Export filters with label names, for keeping them in a file
+    #[command(long_about = "Export filters with label names, for keeping them in a file.\n\n\
+        Examples:\n  \
+        workspace-cli gmail filters export --format toml --output filters.toml\n  \
+        workspace-cli gmail filters export --format xml --output mailFilters.xml")]
+    Export {
+        /// File format: json, toml or xml (Gmail's mailFilters.xml)
+        #[arg(long, default_value = "json", value_parser = ["json", "toml", "xml"])]
+        format: String,
+    },
+    /// Make the account's filters match a TOML or JSON filters file
+    #[command(long_about = "Make the account's filters match a TOML or JSON filters file.\n\n\
+        Filters are matched by criteria and actions. Filters in the file with no\n\
+        live match are created, and live filters not in the file are deleted\n\
+        (unless --keep-unmanaged). Filters can't be edited in place, so a changed\n\
+        filter is created anew and the old one deleted. The plan is always part of\n\
+        the output; --dry-run stops there.\n\n\
+        Examples:\n  \
+        workspace-cli gmail filters apply filters.toml --dry-run\n  \
+        workspace-cli gmail filters apply filters.toml --create-labels")]
+    Apply {
+        /// Filters file (.toml, or .json)
+        path: String,
+        /// Show the plan without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Keep live filters that are not in the file
+        #[arg(long)]
+        keep_unmanaged: bool,
+        /// Create labels referenced by the file that do not exist yet
+        #[arg(long)]
+        create_labels: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SettingsCommands {
+    /// Vacation responder (out-of-office auto-reply)
+    Vacation {
+        #[command(subcommand)]
+        command: VacationCommands,
+    },
+    /// Signature of a send-as alias
+    Signature {
+        #[command(subcommand)]
+        command: SignatureCommands,
+    },
+    /// Show auto-forwarding and forwarding addresses
+    Forwarding,
+    /// Show IMAP settings
+    Imap,
+    /// Show POP settings
+    Pop,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VacationCommands {
+    /// Show the vacation responder
+    Get,
+    /// Turn the vacation responder on (replacing its settings) or off
+    #[command(long_about = "Turn the vacation responder on (replacing its settings) or off.\n\n\
+        Dates are YYYY-MM-DD in local time (--end includes the whole day) or\n\
+        RFC 3339 timestamps. Without dates the responder runs until turned off.\n\n\
+        Examples:\n  \
+        workspace-cli gmail settings vacation set --subject \"Out of office\" --markdown \\\n    \
+        --message \"Back on **Monday**\" --start 2026-12-22 --end 2026-12-31\n  \
+        workspace-cli gmail settings vacation set --off")]
+    Set {
+        /// Turn the responder off (other settings are kept)
+        #[arg(long, conflicts_with_all = ["subject", "message", "message_file", "start", "end"])]
+        off: bool,
+        /// Reply subject
+        #[arg(long)]
+        subject: Option<String>,
+        /// Reply message (or use --message-file, or stdin)
+        #[arg(long, conflicts_with = "message_file")]
+        message: Option<String>,
+        /// Read the reply message from file
+        #[arg(long)]
+        message_file: Option<String>,
+        /// The message is HTML
+        #[arg(long)]
+        html: bool,
+        /// Render the message from Markdown to HTML
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+        /// First day (YYYY-MM-DD) or time (RFC 3339) to reply
+        #[arg(long)]
+        start: Option<String>,
+        /// Last day (YYYY-MM-DD) or time (RFC 3339) to reply
+        #[arg(long)]
+        end: Option<String>,
+        /// Only reply to people in your Workspace domain
+        #[arg(long)]
+        domain_only: bool,
+        /// Only reply to people in your contacts
+        #[arg(long)]
+        contacts_only: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SignatureCommands {
+    /// Show the signature of an alias (default alias if --from is omitted)
+    Get {
+        /// Send-as address
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Replace the signature of an alias (default alias if --from is omitted)
+    Set {
+        /// Send-as address
+        #[arg(long)]
+        from: Option<String>,
+        /// Signature text (or use --signature-file, or stdin); empty clears it
+        #[arg(long, conflicts_with = "signature_file")]
+        signature: Option<String>,
+        /// Read the signature from file
+        #[arg(long)]
+        signature_file: Option<String>,
+        /// The signature is HTML
+        #[arg(long)]
+        html: bool,
+        /// Render the signature from Markdown to HTML
+        #[arg(long, conflicts_with = "html")]
+        markdown: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SendAsCommands {
+    /// List addresses mail can be sent from
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ThreadCommands {
+    /// Get a thread as a condensed conversation (quoted text and signatures removed)
+    Get {
+        /// Thread ID (the threadId of any message in it)
+        id: String,
+        /// Return the full thread with every message's headers and MIME parts
+        #[arg(long)]
+        full: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ThreadsCommands {
+    /// List threads
+    List {
+        /// Search query (Gmail search syntax)
+        #[arg(long)]
+        query: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Label name or ID to filter by
+        #[arg(long)]
+        label: Option<String>,
+    },
+}
+
+impl Command for GmailCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::gmail);
+
+        match self {
+            GmailCommands::List { query, limit, label } => {
+                let params = list::ListParams {
+                    query,
+                    max_results: limit,
+                    label_ids: resolve_label_filter(&client, label).await?,
+                    page_token: None,
+                };
+                // Metadata is fetched with a batch request, which needs the raw token
+                let access_token = ctx.access_token().await?;
+                let response = list::list_messages_with_metadata(&client, params, &access_token).await?;
+                CommandOutput::value(&response)
+            }
+            GmailCommands::Get { id, full, extract: with_extract } => {
+                if full {
+                    CommandOutput::value(&get::get_message(&client, &id, "full").await?)
+                } else if with_extract {
+                    CommandOutput::value(&extract::extract_message(&client, &id).await?)
+                } else {
+                    // Default: minimal format (essential headers + plain text body)
+                    CommandOutput::value(&get::get_message_minimal(&client, &id).await?)
+                }
+            }
+            GmailCommands::Unsubscribe { id, dry_run } => {
+                CommandOutput::value(&extract::unsubscribe(&client, &id, dry_run).await?)
+            }
+            GmailCommands::Send { to, from, cc, bcc, subject, body, body_file, attachment, html, markdown, inline_image, at, with_draft } => {
+                let body = match body_file {
+                    Some(path) => std::fs::read_to_string(path)?,
+                    None => body.unwrap_or_default(),
+                };
+                let mut params = send::ComposeParams {
+                    to,
+                    subject,
+                    body,
+                    from: resolve_from(&client, from).await?,
+                    cc,
+                    bcc,
+                    in_reply_to: None,
+                    references: None,
+                    thread_id: None,
+                    is_html: html,
+                    text_body: None,
+                    inline_images: Vec::new(),
+                    attachments: load_attachments(&attachment)?,
+                };
+
+                apply_body_format(&mut params, markdown, &inline_image)?;
+
+                if let Some(at) = at {
+                    let send_at = queue::parse_send_at(&at, chrono::Utc::now())?;
+                    let account = ctx.config.auth.current_account.as_deref().unwrap_or("default");
+                    let send_queue = queue::SendQueue::open_default()?;
+                    return CommandOutput::value(&queue::schedule(&client, &send_queue, account, params, send_at, with_draft).await?);
+                }
+
+                let message = send::send_message(&client, params).await?;
+                CommandOutput::value(&SendResponse::from_message(&message))
+            }
+            GmailCommands::Draft { to, from, cc, bcc, subject, body, attachment, html, markdown, inline_image } => {
+                let mut params = send::ComposeParams {
+                    to,
+                    subject,
+                    body: body.unwrap_or_default(),
+                    from: resolve_from(&client, from).await?,
+                    cc,
+                    bcc,
+                    in_reply_to: None,
+                    references: None,
+                    thread_id: None,
+                    is_html: html,
+                    text_body: None,
+                    inline_images: Vec::new(),
+                    attachments: load_attachments(&attachment)?,
+                };
+
+                apply_body_format(&mut params, markdown, &inline_image)?;
+
+                let draft = send::create_draft(&client, params).await?;
+                CommandOutput::value(&DraftResponse::from_draft(&draft))
+            }
+            GmailCommands::Delete { id } => {
+                delete::delete_message(&client, &id).await?;
+                Ok(CommandOutput::success("Message deleted permanently"))
+            }
+            GmailCommands::Trash { id } => {
+                CommandOutput::value(&trash::trash_message(&client, &id).await?)
+            }
+            GmailCommands::Untrash { id } => {
+                CommandOutput::value(&trash::untrash_message(&client, &id).await?)
+            }
+            GmailCommands::Labels => {
+                CommandOutput::value(&labels::list_labels(&client).await?)
+            }
+            GmailCommands::Label { command } => match command {
+                LabelCommands::Create { name, color, text_color } => {
+                    let color = color.map(|bg| labels::LabelColor { text_color, background_color: bg });
+                    CommandOutput::value(&labels::create_label(&client, &name, color).await?)
+                }
+                LabelCommands::Update { label, name, color, text_color } => {
+                    if name.is_none() && color.is_none() {
+                        return Err(WorkspaceError::InvalidInput("Nothing to update: pass --name or --color".to_string()));
+                    }
+                    let id = labels::LabelResolver::load(&client).await?.resolve(&client, &label).await?;
+                    let update = labels::LabelUpdate {
+                        name,
+                        color: color.map(|bg| labels::LabelColor { text_color, background_color: bg }),
+                    };
+                    CommandOutput::value(&labels::update_label(&client, &id, &update).await?)
+                }
+                LabelCommands::Delete { label } => {
+                    let id = labels::LabelResolver::load(&client).await?.resolve(&client, &label).await?;
+                    labels::delete_label(&client, &id).await?;
+                    Ok(CommandOutput::success(format!("Label {} deleted", id)))
+                }
+            },
+            GmailCommands::Modify { id, add_labels, remove_labels, create_labels, mark_read, mark_unread, star, unstar, archive } => {
+                let (mut add, mut remove) = resolve_label_changes(&client, add_labels, remove_labels, create_labels).await?;
+
+                // Handle convenience flags
+                if mark_read {
+                    remove.push("UNREAD".to_string());
+                }
+                if mark_unread {
+                    add.push("UNREAD".to_string());
+                }
+                if star {
+                    add.push("STARRED".to_string());
+                }
+                if unstar {
+                    remove.push("STARRED".to_string());
+                }
+                if archive {
+                    remove.push("INBOX".to_string());
+                }
+
+                let message = labels::modify_labels(&client, &id, add, remove).await?;
+                // Minimal response (success + id + labels) to reduce token usage
+                CommandOutput::value(&ModifyResponse::from_message(&message))
+            }
+            GmailCommands::Reply { id, body, body_file, all, html, markdown, inline_image } => {
+                let body = match body_file {
+                    Some(path) => std::fs::read_to_string(&path).map_err(|e| {
+                        WorkspaceError::InvalidInput(format!("Failed to read body file '{}': {}", path, e))
+                    })?,
+                    None => body.unwrap_or_default(),
+                };
+                let mut params = reply_params(&client, &id, body, all, html).await?;
+                apply_body_format(&mut params, markdown, &inline_image)?;
+
+                let message = send::send_message(&client, params).await?;
+                CommandOutput::value(&SendResponse::from_message(&message))
+            }
+            GmailCommands::Drafts { command } => match command {
+                DraftsCommands::List { query, limit } => {
+                    let access_token = ctx.access_token().await?;
+                    CommandOutput::value(&drafts::list_drafts(&client, query.as_deref(), limit, &access_token).await?)
+                }
+                DraftsCommands::Get { id } => {
+                    CommandOutput::value(&drafts::get_draft_minimal(&client, &id).await?)
+                }
+                DraftsCommands::Update { id, to, cc, bcc, subject, body, body_file, html, attachment, clear_attachments } => {
+                    let body = match body_file {
+                        Some(path) => Some(read_input(None, Some(path))?),
+                        None => body,
+                    };
+                    let update = drafts::DraftUpdate {
+                        to,
+                        cc,
+                        bcc,
+                        subject,
+                        body,
+                        is_html: html,
+                        attachments: load_attachments(&attachment)?,
+                        clear_attachments,
+                    };
+                    let draft = drafts::update_draft(&client, &id, update).await?;
+                    CommandOutput::value(&DraftResponse::from_draft(&draft))
+                }
+                DraftsCommands::Send { id } => {
+                    let message = drafts::send_draft(&client, &id).await?;
+                    CommandOutput::value(&SendResponse::from_message(&message))
+                }
+                DraftsCommands::Delete { id } => {
+                    drafts::delete_draft(&client, &id).await?;
+                    Ok(CommandOutput::success("Draft deleted"))
+                }
+            },
+            GmailCommands::ReplyDraft { id, body, all, html, markdown, inline_image } => {
+                let mut params = reply_params(&client, &id, body.unwrap_or_default(), all, html).await?;
+                apply_body_format(&mut params, markdown, &inline_image)?;
+
+                let draft = send::create_draft(&client, params).await?;
+                CommandOutput::value(&DraftResponse::from_draft(&draft))
+            }
+            GmailCommands::Stats { query, since, max, top } => {
+                let access_token = ctx.access_token().await?;
+                let options = stats::StatsOptions { query, since, max, top };
+                CommandOutput::value(&stats::stats(&client, &access_token, &options).await?)
+            }
+            GmailCommands::Attachments { id, command } => match (id, command) {
+                (Some(id), _) => {
+                    let message = get::get_message(&client, &id, "full").await?;
+                    CommandOutput::value(&get::list_attachments(&message))
+                }
+                (None, Some(AttachmentsCommands::Pull { query, dir, to_drive, template, max, concurrency })) => {
+                    let target = match to_drive {
+                        Some(folder_id) => attachments::PullTarget::Drive {
+                            folder_id,
+                            access_token: ctx.access_token().await?,
+                            manifest_dir: dir.map(std::path::PathBuf::from),
+                        },
+                        None => attachments::PullTarget::Dir(std::path::PathBuf::from(dir.unwrap_or_default())),
+                    };
+                    let to_drive = matches!(target, attachments::PullTarget::Drive { .. });
+                    let options = attachments::PullOptions {
+                        query,
+                        target,
+                        template,
+                        max,
+                        concurrency,
+                    };
+                    let result = attachments::pull(&client, &options).await?;
+                    if to_drive {
+                        // Drive uploads use the raw token, so drop cached Drive listings
+                        ctx.client(ApiClient::drive).invalidate_cache();
+                    }
+                    if result.failed > 0 {
+                        Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                    } else {
+                        CommandOutput::value(&result)
+                    }
+                }
+                (None, None) => Err(WorkspaceError::InvalidInput(
+                    "Give a message ID, or use `gmail attachments pull --query ...`".to_string(),
+                )),
+            },
+            GmailCommands::Attachment { message_id, attachment_id, output } => {
+                let data = get::download_attachment(&client, &message_id, &attachment_id).await?;
+                std::fs::write(&output, &data)?;
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "path": output,
+                    "size": data.len()
+                })))
+            }
+            GmailCommands::Forward { id, to, cc, bcc, message } => {
+                let original = get::get_message(&client, &id, "full").await?;
+                let body = get::extract_body(&original).unwrap_or_default();
+                let metadata = send::extract_forward_metadata(&original, &body).ok_or_else(|| {
+                    WorkspaceError::InvalidInput("Could not extract forward metadata from message".to_string())
+                })?;
+
+                let params = send::ComposeParams {
+                    to,
+                    body: send::build_forward_body(&metadata, message.as_deref()),
+                    subject: metadata.subject,
+                    from: None,
+                    cc,
+                    bcc,
+                    in_reply_to: None,
+                    references: None,
+                    thread_id: None,
+                    is_html: false,
+                    text_body: None,
+                    inline_images: Vec::new(),
+                    attachments: Vec::new(),
+                };
+
+                let sent = send::send_message(&client, params).await?;
+                CommandOutput::value(&SendResponse::from_message(&sent))
+            }
+            GmailCommands::Filters { command } => match command.unwrap_or(FiltersCommands::List) {
+                FiltersCommands::List => {
+                    CommandOutput::value(&filters::list_filters(&client).await?)
+                }
+                FiltersCommands::Export { format } => {
+                    let file = filter_sync::export_filters(&client).await?;
+                    match filter_sync::FiltersFormat::from_str(&format) {
+                        Some(filter_sync::FiltersFormat::Json) | None => CommandOutput::value(&file),
+                        Some(format) => Ok(CommandOutput::Text(filter_sync::render_filters(&file, format)?)),
+                    }
+                }
+                FiltersCommands::Apply { path, dry_run, keep_unmanaged, create_labels } => {
+                    let file = filter_sync::load_filters_file(std::path::Path::new(&path))?;
+                    let options = filter_sync::ApplyOptions {
+                        dry_run,
+                        delete_unmanaged: !keep_unmanaged,
+                        create_labels,
+                    };
+                    let result = filter_sync::apply(&client, &file.filters, &options).await?;
+                    if result.errors.is_empty() {
+                        CommandOutput::value(&result)
+                    } else {
+                        Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                    }
+                }
+            },
+            GmailCommands::CreateFilter { from, to, subject, query, has_attachment, add_labels, remove_labels, create_labels, forward_to, skip_inbox, mark_read, star, trash } => {
+                let (add, remove) = resolve_label_changes(&client, add_labels, remove_labels, create_labels).await?;
+                let filter = filters::build_filter(
+                    from.as_deref(),
+                    to.as_deref(),
+                    subject.as_deref(),
+                    query.as_deref(),
+                    if has_attachment { Some(true) } else { None },
+                    add,
+                    remove,
+                    forward_to.as_deref(),
+                    skip_inbox,
+                    mark_read,
+                    star,
+                    false,
+                    trash,
+                );
+
+                CommandOutput::value(&filters::create_filter(&client, &filter).await?)
+            }
+            GmailCommands::DeleteFilter { id } => {
+                filters::delete_filter(&client, &id).await?;
+                Ok(CommandOutput::success("Filter deleted"))
+            }
+            GmailCommands::History { since, all, page_token, limit, label, types } => {
+                let params = history::HistoryParams {
+                    start_history_id: since,
+                    max_results: limit,
+                    label_id: resolve_label_filter(&client, label).await?.and_then(|mut ids| ids.pop()),
+                    history_types: types.as_deref().map(split_list).unwrap_or_default(),
+                    page_token,
+                };
+                let access_token = ctx.access_token().await?;
+                CommandOutput::value(&history::get_changes(&client, params, all, &access_token).await?)
+            }
+            GmailCommands::Sync => {
+                let account = ctx.config.auth.current_account.as_deref().unwrap_or("default");
+                let access_token = ctx.access_token().await?;
+                CommandOutput::value(&history::sync(&client, account, &access_token).await?)
+            }
+            GmailCommands::Thread { command: ThreadCommands::Get { id, full } } => {
+                if full {
+                    CommandOutput::value(&threads::get_thread(&client, &id, "full").await?)
+                } else {
+                    CommandOutput::value(&threads::get_conversation(&client, &id).await?)
+                }
+            }
+            GmailCommands::Threads { command: ThreadsCommands::List { query, limit, label } } => {
+                let params = list::ListParams {
+                    query,
+                    max_results: limit,
+                    label_ids: resolve_label_filter(&client, label).await?,
+                    page_token: None,
+                };
+                CommandOutput::value(&threads::list_threads(&client, params).await?)
+            }
+            GmailCommands::Merge {
+                template, subject, data, draft, dry_run, to_column, cc_column, bcc_column,
+                attachments_column, status_column, per_minute, limit, html, markdown,
+            } => {
+                let format = if html {
+                    merge::BodyFormat::Html
+                } else if markdown {
+                    merge::BodyFormat::Markdown
+                } else {
+                    merge::BodyFormat::from_path(std::path::Path::new(&template))
+                };
+                let options = merge::MergeOptions {
+                    subject,
+                    template: read_input(None, Some(template))?,
+                    format,
+                    to_column,
+                    cc_column,
+                    bcc_column,
+                    attachments_column,
+                    status_column,
+                    draft,
+                    dry_run,
+                    per_minute,
+                    limit,
+                };
+
+                let source = merge::MergeSource::parse(&data)?;
+                let sheets = ctx.client(ApiClient::sheets);
+                let table = merge::load_table(&sheets, &source).await?;
+                let result = merge::run_merge(&client, &sheets, &source, &table, &options).await?;
+                if result.failed > 0 || result.stopped.is_some() {
+                    Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                } else {
+                    CommandOutput::value(&result)
+                }
+            }
+            GmailCommands::Export { query, out, format, max, concurrency } => {
+                let out = std::path::PathBuf::from(out);
+                let options = archive::ExportOptions {
+                    query: query.unwrap_or_default(),
+                    format: format.as_deref()
+                        .and_then(archive::ArchiveFormat::from_str)
+                        .unwrap_or_else(|| archive::ArchiveFormat::from_path(&out)),
+                    out,
+                    max,
+                    concurrency,
+                };
+                let result = archive::export(&client, &options).await?;
+                if result.failed > 0 {
+                    Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                } else {
+                    CommandOutput::value(&result)
+                }
+            }
+            GmailCommands::Import { path, label, create_labels, no_archive_labels, insert } => {
+                let options = archive::ImportOptions {
+                    labels: label.as_deref().map(split_list).unwrap_or_default(),
+                    create_labels,
+                    archive_labels: !no_archive_labels,
+                    insert,
+                };
+                let result = archive::import(&client, std::path::Path::new(&path), &options).await?;
+                if result.failed > 0 {
+                    Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                } else {
+                    CommandOutput::value(&result)
+                }
+            }
+            GmailCommands::Bulk { action, query, add_labels, remove_labels, create_labels, dry_run, max } => {
+                let mut labels_to_create = Vec::new();
+                let action = match action.as_str() {
+                    "modify-labels" if dry_run && create_labels => {
+                        // A dry run must not change the mailbox: report missing labels instead of creating them
+                        let (add, remove, missing) = preview_label_changes(&client, add_labels, remove_labels).await?;
+                        labels_to_create = missing;
+                        bulk::BulkAction::ModifyLabels { add, remove }
+                    }
+                    "modify-labels" => {
+                        let (add, remove) = resolve_label_changes(&client, add_labels, remove_labels, create_labels).await?;
+                        bulk::BulkAction::ModifyLabels { add, remove }
+                    }
+                    "archive" => bulk::BulkAction::Archive,
+                    "mark-read" => bulk::BulkAction::MarkRead,
+                    "trash" => bulk::BulkAction::Trash,
+                    "delete" => bulk::BulkAction::Delete,
+                    other => unreachable!("clap rejects bulk action {}", other),
+                };
+                let mut result = bulk::bulk(&client, &action, &query, max, dry_run).await?;
+                result.labels_to_create = labels_to_create;
+                if result.errors.is_empty() {
+                    CommandOutput::value(&result)
+                } else {
+                    Ok(CommandOutput::Partial(serde_json::to_value(&result)?))
+                }
+            }
+            GmailCommands::Queue { command } => {
+                let send_queue = queue::SendQueue::open_default()?;
+                match command {
+                    QueueCommands::List => {
+                        let entries = send_queue.list()?;
+                        let summaries: Vec<queue::QueueSummary> = entries.iter().map(queue::QueueSummary::from).collect();
+                        CommandOutput::value(&summaries)
+                    }
+                    QueueCommands::Cancel { id } => {
+                        CommandOutput::value(&queue::cancel(&client, &send_queue, &id).await?)
+                    }
+                    QueueCommands::Retry { id } => {
+                        let entry = send_queue.retry(&id, chrono::Utc::now())?;
+                        CommandOutput::value(&queue::QueueSummary::from(&entry))
+                    }
+                    QueueCommands::Run { watch, interval } => {
+                        let account = ctx.config.auth.current_account.as_deref().unwrap_or("default");
+                        let mut total = match queue::run_due(&client, &send_queue, account, chrono::Utc::now()).await {
+                            Ok(pass) => pass,
+                            Err(e) if watch => queue::RunResult { errors: vec![e.to_string()], ..Default::default() },
+                            Err(e) => return Err(e),
+                        };
+                        if watch {
+                            loop {
+                                tokio::select! {
+                                    _ = tokio::time::sleep(std::time::Duration::from_secs(interval.max(1))) => {}
+                                    _ = tokio::signal::ctrl_c() => break,
+                                }
+                                // A failed pass is recorded; the next one tries again
+                                let pass = match queue::run_due(&client, &send_queue, account, chrono::Utc::now()).await {
+                                    Ok(pass) => pass,
+                                    Err(e) => {
+                                        total.errors.push(e.to_string());
+                                        continue;
+                                    }
+                                };
+                                total.sent.extend(pass.sent);
+                                total.cancelled.extend(pass.cancelled);
+                                total.errors.extend(pass.errors);
+                                total.pending = pass.pending;
+                                total.next_send_at = pass.next_send_at;
+                            }
+                        }
+                        if total.errors.is_empty() {
+                            CommandOutput::value(&total)
+                        } else {
+                            Ok(CommandOutput::Partial(serde_json::to_value(&total)?))
+                        }
+                    }
+                }
+            }
+            GmailCommands::Settings { command } => match command {
+                SettingsCommands::Vacation { command } => match command {
+                    VacationCommands::Get => {
+                        CommandOutput::value(&settings::get_vacation(&client).await?)
+                    }
+                    VacationCommands::Set { off: true, .. } => {
+                        let mut vacation = settings::get_vacation(&client).await?;
+                        vacation.enable_auto_reply = false;
+                        CommandOutput::value(&settings::set_vacation(&client, &vacation).await?)
+                    }
+                    VacationCommands::Set { subject, message, message_file, html, markdown, start, end, domain_only, contacts_only, .. } => {
+                        let message = read_input(message, message_file)?;
+                        let (plain, html_body) = if markdown {
+                            (None, Some(crate::utils::markdown_to_html(&message)))
+                        } else if html {
+                            (None, Some(message))
+                        } else {
+                            (Some(message), None)
+                        };
+                        let vacation = settings::VacationSettings {
+                            enable_auto_reply: true,
+                            response_subject: subject,
+                            response_body_plain_text: plain,
+                            response_body_html: html_body,
+                            restrict_to_contacts: Some(contacts_only),
+                            restrict_to_domain: Some(domain_only),
+                            start_time: start.map(|s| settings::parse_vacation_time(&s, false)).transpose()?.map(|t| t.to_string()),
+                            end_time: end.map(|e| settings::parse_vacation_time(&e, true)).transpose()?.map(|t| t.to_string()),
+                        };
+                        CommandOutput::value(&settings::set_vacation(&client, &vacation).await?)
+                    }
+                },
+                SettingsCommands::Signature { command } => match command {
+                    SignatureCommands::Get { from } => {
+                        let alias = settings::resolve_send_as(&client, from.as_deref()).await?;
+                        Ok(CommandOutput::Value(serde_json::json!({
+                            "sendAsEmail": alias.send_as_email,
+                            "signature": alias.signature.unwrap_or_default(),
+                        })))
+                    }
+                    SignatureCommands::Set { from, signature, signature_file, html, markdown } => {
+                        let alias = settings::resolve_send_as(&client, from.as_deref()).await?;
+                        let signature = read_input(signature, signature_file)?;
+                        let signature = if markdown {
+                            crate::utils::markdown_to_html(&signature)
+                        } else if html {
+                            signature
+                        } else {
+                            settings::text_to_signature_html(signature.trim_end())
+                        };
+                        let updated = settings::update_signature(&client, &alias.send_as_email, &signature).await?;
+                        Ok(CommandOutput::Value(serde_json::json!({
+                            "sendAsEmail": updated.send_as_email,
+                            "signature": updated.signature.unwrap_or_default(),
+                        })))
+                    }
+                },
+                SettingsCommands::Forwarding => {
+                    CommandOutput::value(&settings::get_forwarding(&client).await?)
+                }
+                SettingsCommands::Imap => {
+                    CommandOutput::value(&settings::get_imap(&client).await?)
+                }
+                SettingsCommands::Pop => {
+                    CommandOutput::value(&settings::get_pop(&client).await?)
+                }
+            },
+            GmailCommands::SendAs { command } => match command {
+                SendAsCommands::List => {
+                    CommandOutput::value(&settings::list_send_as(&client).await?)
+                }
+            },
+        }
+    }
+}
+
+/// Load attachments from file paths
+fn load_attachments(paths: &[String]) -> Result<Vec<send::Attachment>, WorkspaceError> {
+    paths
+        .iter()
+        .map(|path| {
+            send::load_attachment(path).map_err(|e| {
+                WorkspaceError::InvalidInput(format!("Failed to load attachment '{}': {}", path, e))
+            })
+        })
+        .collect()
+}
+
+/// Render a Markdown body to HTML and load inline images
+fn apply_body_format(params: &mut send::ComposeParams, markdown: bool, inline_images: &[String]) -> Result<(), WorkspaceError> {
+    if markdown {
+        // Text-only clients get the Markdown rendered to plain text, not its syntax
+        params.text_body = Some(crate::utils::markdown_to_text(&params.body));
+        params.body = crate::utils::markdown_to_html(&params.body);
+        params.is_html = true;
+    }
+    if inline_images.is_empty() {
+        return Ok(());
+    }
+    if !params.is_html {
+        return Err(WorkspaceError::InvalidInput("--inline-image requires --html or --markdown".to_string()));
+    }
+
+    params.inline_images = inline_images
+        .iter()
+        .map(|spec| {
+            send::load_inline_image(spec).map_err(|e| {
+                WorkspaceError::InvalidInput(format!("Failed to load inline image '{}': {}", spec, e))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(())
+}
+
+/// Validate `--from` against the account's send-as aliases and build the From header
+async fn resolve_from(client: &ApiClient, from: Option<String>) -> Result<Option<String>, WorkspaceError> {
+    let Some(from) = from else {
+        return Ok(None);
+    };
+    let alias = settings::resolve_send_as(client, Some(&from)).await?;
+    Ok(Some(settings::from_header(&alias, &from)))
+}
+
+/// Build reply parameters from the headers of the original message
+async fn reply_params(
+    client: &ApiClient,
+    id: &str,
+    body: String,
+    all: bool,
+    html: bool,
+) -> Result<send::ComposeParams, WorkspaceError> {
+    let original = get::get_message(client, id, "metadata").await?;
+    let metadata = send::extract_reply_metadata(&original).ok_or_else(|| {
+        WorkspaceError::InvalidInput(
+            "Could not extract reply metadata from message (missing Message-ID or From header)".to_string(),
+        )
+    })?;
+
+    Ok(send::ComposeParams {
+        to: metadata.to,
+        subject: metadata.subject,
+        body,
+        from: None,
+        cc: if all { metadata.cc } else { None },
+        bcc: None,
+        in_reply_to: Some(metadata.in_reply_to),
+        references: Some(metadata.references),
+        thread_id: Some(metadata.thread_id),
+        is_html: html,
+        text_body: None,
+        inline_images: Vec::new(),
+        attachments: Vec::new(),
+    })
+}
+
+/// Resolve a single `--label` filter (name or ID) to label IDs
+async fn resolve_label_filter(client: &ApiClient, label: Option<String>) -> Result<Option<Vec<String>>, WorkspaceError> {
+    match label {
+        Some(label) => Ok(Some(labels::resolve_label_ids(client, &[label], false).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Resolve `--add-labels`/`--remove-labels` names to IDs with one label lookup
+///
+/// With `create_missing`, unknown labels to add are created; labels to remove must exist.
+async fn resolve_label_changes(
+    client: &ApiClient,
+    add: Option<String>,
+    remove: Option<String>,
+    create_missing: bool,
+) -> Result<(Vec<String>, Vec<String>), WorkspaceError> {
+    let add = add.as_deref().map(split_list).unwrap_or_default();
+    let remove = remove.as_deref().map(split_list).unwrap_or_default();
+    if add.is_empty() && remove.is_empty() {
+        return Ok((add, remove));
+    }
+
+    let mut resolver = labels::LabelResolver::load(client).await?;
+    let remove = resolver.resolve_all(client, &remove).await?;
+    let mut resolver = resolver.with_create_missing(create_missing);
+    let add = resolver.resolve_all(client, &add).await?;
+    Ok((add, remove))
+}
+
+/// Like `resolve_label_changes` with label creation, but creates nothing
+///
+/// Labels to add that do not exist yet are kept by name and also returned
+/// separately, for a dry run to report.
+async fn preview_label_changes(
+    client: &ApiClient,
+    add: Option<String>,
+    remove: Option<String>,
+) -> Result<(Vec<String>, Vec<String>, Vec<String>), WorkspaceError> {
+    let add = add.as_deref().map(split_list).unwrap_or_default();
+    let remove = remove.as_deref().map(split_list).unwrap_or_default();
+    if add.is_empty() && remove.is_empty() {
+        return Ok((add, remove, Vec::new()));
+    }
+
+    let mut resolver = labels::LabelResolver::load(client).await?;
+    let remove = resolver.resolve_all(client, &remove).await?;
+    let missing = resolver.missing(&add);
+    let mut ids = Vec::with_capacity(add.len());
+    for key in add {
+        if missing.contains(&key) {
+            ids.push(key);
+        } else {
+            ids.push(resolver.resolve(client, &key).await?);
+        }
+    }
+    Ok((ids, remove, missing))
+}
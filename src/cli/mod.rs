@@ -0,0 +1,400 @@
+//! Command-line interface: argument definitions, command handlers and the
+//! single output/error path shared by every subcommand.
+//!
+//! Each service module defines its clap subcommand enum and implements
+//! [`Command`] for it. Handlers return a [`CommandOutput`] or a
+//! [`WorkspaceError`]; [`run`] formats the result (honoring `--format`,
+//! `--fields`, `--output` and `--quiet`) or prints a structured [`CliError`]
+//! and picks the process exit code.
+
+mod app;
+mod auth;
+mod batch;
+mod cache;
+mod calendar;
+mod docs;
+mod drive;
+mod gmail;
+mod schema;
+mod sheets;
+mod slides;
+mod tasks;
+
+pub use app::{Cli, Commands};
+pub use auth::AuthCommands;
+pub use batch::BatchCommands;
+pub use cache::CacheCommands;
+pub use calendar::CalendarCommands;
+pub use docs::DocsCommands;
+pub use drive::DriveCommands;
+pub use gmail::{
+    AttachmentsCommands, DraftsCommands, FiltersCommands, GmailCommands, LabelCommands, QueueCommands, SendAsCommands, SettingsCommands, SignatureCommands, ThreadCommands,
+    ThreadsCommands, VacationCommands,
+};
+pub use schema::schema;
+pub use sheets::SheetsCommands;
+pub use slides::SlidesCommands;
+pub use tasks::TasksCommands;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::auth::TokenManager;
+use crate::client::{ApiClient, CacheMode, ResponseCache};
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode, WorkspaceError, EXIT_PARTIAL};
+use crate::output::{Formatter, OutputFormat};
+
+/// Shared token manager handle used by API clients
+pub type SharedTokenManager = Arc<RwLock<TokenManager>>;
+
+/// CLI execution context
+pub struct CliContext {
+    pub format: OutputFormat,
+    pub output_file: Option<String>,
+    pub fields: Option<Vec<String>>,
+    pub quiet: bool,
+    pub timings: bool,
+    pub config: Config,
+    token_manager: SharedTokenManager,
+    cache: Option<ResponseCache>,
+}
+
+impl CliContext {
+    pub fn new(format: &str, output: Option<String>, fields: Option<String>, quiet: bool) -> Self {
+        let config = Config::default();
+        Self {
+            format: OutputFormat::from_str(format).unwrap_or(OutputFormat::Json),
+            output_file: output,
+            fields: fields.map(|f| {
+                f.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+            }),
+            quiet,
+            timings: false,
+            token_manager: Arc::new(RwLock::new(TokenManager::new(config.clone()))),
+            config,
+            cache: None,
+        }
+    }
+
+    /// Use this configuration for authentication and caching
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.token_manager = Arc::new(RwLock::new(TokenManager::new(config.clone())));
+        self.config = config;
+        self
+    }
+
+    /// Set up the response cache for GET requests
+    pub fn with_cache_mode(mut self, mode: CacheMode) -> Self {
+        let account = self.config.auth.current_account.as_deref().unwrap_or("default");
+        self.cache = ResponseCache::from_config(&self.config.cache, account, mode);
+        self
+    }
+
+    /// Append a `_timings` summary to JSON output
+    pub fn with_timings(mut self, timings: bool) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Shared token manager
+    pub fn token_manager(&self) -> SharedTokenManager {
+        self.token_manager.clone()
+    }
+
+    /// Ensure stored credentials are usable before making API calls
+    pub async fn authenticate(&self) -> Result<(), WorkspaceError> {
+        let mut tm = self.token_manager.write().await;
+        tm.ensure_authenticated().await?;
+        Ok(())
+    }
+
+    /// Get an access token for endpoints called outside [`ApiClient`] (uploads, exports, batch)
+    pub async fn access_token(&self) -> Result<String, WorkspaceError> {
+        let mut tm = self.token_manager.write().await;
+        Ok(tm.get_access_token().await?)
+    }
+
+    /// Build an API client sharing this context's token manager and cache
+    ///
+    /// ```ignore
+    /// let client = ctx.client(ApiClient::gmail);
+    /// ```
+    pub fn client(&self, constructor: fn(SharedTokenManager) -> ApiClient) -> ApiClient {
+        constructor(self.token_manager.clone()).with_cache(self.cache.clone())
+    }
+
+    /// Create a formatter for this context
+    pub fn formatter(&self) -> io::Result<Formatter> {
+        let mut formatter = Formatter::new(self.format)
+            .with_fields(self.fields.clone())
+            .with_quiet(self.quiet)
+            .with_timings(self.timings);
+
+        if let Some(ref path) = self.output_file {
+            let file = File::create(path)?;
+            let writer = BufWriter::new(file);
+            formatter = formatter.with_writer(writer);
+        }
+
+        Ok(formatter)
+    }
+
+    /// Output a result, handling file output if specified
+    pub fn output<T: serde::Serialize>(&self, value: &T) -> io::Result<()> {
+        let mut formatter = self.formatter()?;
+        formatter.write(value)?;
+        formatter.flush()
+    }
+
+    /// Output a command result
+    pub fn output_command(&self, output: &CommandOutput) -> io::Result<()> {
+        match output {
+            CommandOutput::Value(value) | CommandOutput::Partial(value) => self.output(value),
+            CommandOutput::Text(text) => {
+                if let Some(ref path) = self.output_file {
+                    return std::fs::write(path, text);
+                }
+                if self.quiet {
+                    return Ok(());
+                }
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(text.as_bytes())?;
+                if !text.ends_with('\n') {
+                    stdout.write_all(b"\n")?;
+                }
+                stdout.flush()
+            }
+            CommandOutput::None => Ok(()),
+        }
+    }
+
+    /// Output an error in structured JSON format
+    pub fn output_error(&self, error: &CliError) {
+        eprintln!("{}", error.to_json());
+    }
+
+    /// Output a success message
+    pub fn output_success(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        let success = serde_json::json!({
+            "status": "success",
+            "message": message
+        });
+        println!("{}", serde_json::to_string(&success).unwrap());
+    }
+
+    /// Print a structured warning on stderr
+    pub fn warn(&self, message: &str) {
+        let warning = serde_json::json!({
+            "status": "warning",
+            "message": message
+        });
+        eprintln!("{}", warning);
+    }
+
+    /// Print info message (only if not quiet)
+    pub fn info(&self, message: &str) {
+        if !self.quiet {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+/// Result of a command, written by the central output path
+#[derive(Debug)]
+pub enum CommandOutput {
+    /// Structured data, formatted according to `--format` and `--fields`
+    Value(serde_json::Value),
+    /// Pre-rendered text (markdown, plain text, CSV) written as-is
+    Text(String),
+    /// Structured data for an operation where some items failed; written like
+    /// `Value` but exits with [`EXIT_PARTIAL`]
+    Partial(serde_json::Value),
+    /// Nothing to print
+    None,
+}
+
+impl CommandOutput {
+    /// Serialize a result for formatted output
+    pub fn value<T: serde::Serialize>(value: &T) -> Result<Self, WorkspaceError> {
+        Ok(Self::Value(serde_json::to_value(value)?))
+    }
+
+    /// A `{"status":"success","message":...}` result
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::Value(serde_json::json!({
+            "status": "success",
+            "message": message.into(),
+        }))
+    }
+}
+
+/// A subcommand that can be executed against a [`CliContext`]
+#[allow(async_fn_in_trait)]
+pub trait Command {
+    /// Run the command and return its result for central formatting
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError>;
+}
+
+/// Process exit code for a failed command (see [`ErrorCode::exit_code`])
+pub fn exit_code(error: &WorkspaceError) -> i32 {
+    CliError::from(error).error_code.exit_code()
+}
+
+/// Exit code table for `--help`
+pub fn exit_codes_help() -> String {
+    let mut help = String::from("Exit codes:\n  0   success\n");
+    for code in ErrorCode::ALL {
+        help.push_str(&format!("  {:<3} {:<22} {}\n", code.exit_code(), code.as_str(), code.description()));
+    }
+    help.push_str(&format!(
+        "  {:<3} {:<22} {}",
+        EXIT_PARTIAL, "partial", "Completed with some failed items (e.g. batch status \"partial\")"
+    ));
+    help
+}
+
+/// Exit code table for the schema output
+pub fn exit_codes_json() -> serde_json::Value {
+    let mut codes = vec![serde_json::json!({
+        "code": 0,
+        "name": "success",
+        "description": "Command succeeded",
+    })];
+    codes.extend(ErrorCode::ALL.iter().map(|code| serde_json::json!({
+        "code": code.exit_code(),
+        "name": code.as_str(),
+        "description": code.description(),
+    })));
+    codes.push(serde_json::json!({
+        "code": EXIT_PARTIAL,
+        "name": "partial",
+        "description": "Completed with some failed items (e.g. batch status \"partial\")",
+    }));
+    serde_json::Value::Array(codes)
+}
+
+/// Execute a parsed command line and return the process exit code
+pub async fn run(cli: Cli, config: Config) -> i32 {
+    let cache_mode = if cli.no_cache {
+        CacheMode::Bypass
+    } else if cli.cache_only {
+        CacheMode::Only
+    } else {
+        CacheMode::Normal
+    };
+
+    if let Some(ref mode) = cli.progress {
+        crate::output::progress::init(
+            crate::output::progress::ProgressMode::from_str(mode).unwrap_or_default(),
+        );
+    }
+
+    let ctx = CliContext::new(&cli.format, cli.output.clone(), cli.fields.clone(), cli.quiet)
+        .with_config(config)
+        .with_cache_mode(cache_mode)
+        .with_timings(cli.timings);
+
+    match cli.command.run(&ctx).await {
+        Ok(output) => match ctx.output_command(&output) {
+            Ok(()) if matches!(output, CommandOutput::Partial(_)) => EXIT_PARTIAL,
+            Ok(()) => 0,
+            Err(e) => {
+                let err = WorkspaceError::Io(e);
+                ctx.output_error(&CliError::from(&err));
+                exit_code(&err)
+            }
+        },
+        Err(e) => {
+            ctx.output_error(&CliError::from(&e));
+            exit_code(&e)
+        }
+    }
+}
+
+/// Read a text argument from a file path or inline value, falling back to stdin
+pub(crate) fn read_input(inline: Option<String>, file: Option<String>) -> Result<String, WorkspaceError> {
+    if let Some(path) = file {
+        return std::fs::read_to_string(&path).map_err(|e| {
+            WorkspaceError::InvalidInput(format!("Failed to read file '{}': {}", path, e))
+        });
+    }
+    if let Some(value) = inline {
+        return Ok(value);
+    }
+
+    use std::io::Read;
+    let mut buffer = String::new();
+    io::stdin()
+        .read_to_string(&mut buffer)
+        .map_err(|e| WorkspaceError::InvalidInput(format!("Failed to read from stdin: {}", e)))?;
+    Ok(buffer)
+}
+
+/// Split a comma-separated argument into trimmed values
+pub(crate) fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// Export a Google Docs/Sheets/Slides file through Drive and describe the result
+pub(crate) async fn export_document(
+    ctx: &CliContext,
+    id: &str,
+    export_id: &str,
+    mime_type: &str,
+    output: &str,
+    format: &str,
+) -> Result<serde_json::Value, WorkspaceError> {
+    let access_token = ctx.access_token().await?;
+    let bytes = crate::commands::drive::download::export_file(
+        &access_token,
+        export_id,
+        mime_type,
+        std::path::Path::new(output),
+    )
+    .await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "id": id,
+        "format": format,
+        "output": output,
+        "bytes": bytes
+    }))
+}
+
+/// Output of a command that wrote a file; nothing is printed when the file went to stdout (`-`)
+pub(crate) fn written_output(output: &str, value: serde_json::Value) -> CommandOutput {
+    if output == "-" {
+        CommandOutput::None
+    } else {
+        CommandOutput::Value(value)
+    }
+}
+
+/// Result type for CLI operations
+pub type CliResult<T> = Result<T, CliError>;
+
+/// Helper macro for handling command results
+#[macro_export]
+macro_rules! handle_result {
+    ($ctx:expr, $result:expr) => {
+        match $result {
+            Ok(value) => {
+                $ctx.output(&value).unwrap_or_else(|e| {
+                    eprintln!("Output error: {}", e);
+                });
+            }
+            Err(e) => {
+                let cli_err = $crate::error::CliError::from(&e);
+                $ctx.output_error(&cli_err);
+                std::process::exit($crate::cli::exit_code(&e));
+            }
+        }
+    };
+}
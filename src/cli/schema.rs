@@ -0,0 +1,91 @@
+//! Machine-readable description of the command line
+//!
+//! `workspace-cli schema` walks the clap definition and prints every command
+//! with its arguments, plus the exit code table, so agents and wrappers can
+//! discover the interface without parsing `--help` text.
+
+use clap::{Arg, CommandFactory};
+use serde_json::{json, Value};
+
+use super::{exit_codes_json, Cli};
+
+/// Build the schema document for the whole CLI
+pub fn schema() -> Value {
+    let cli = Cli::command();
+    let global_args: Vec<Value> = cli
+        .get_arguments()
+        .filter(|arg| arg.is_global_set() && !is_builtin(arg))
+        .map(arg_schema)
+        .collect();
+
+    json!({
+        "name": cli.get_name(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "globalArgs": global_args,
+        "commands": cli.get_subcommands().map(command_schema).collect::<Vec<_>>(),
+        "exitCodes": exit_codes_json(),
+    })
+}
+
+fn command_schema(cmd: &clap::Command) -> Value {
+    let mut schema = json!({
+        "name": cmd.get_name(),
+        "about": cmd.get_about().map(|s| s.to_string()),
+        "args": cmd
+            .get_arguments()
+            .filter(|arg| !arg.is_global_set() && !is_builtin(arg))
+            .map(arg_schema)
+            .collect::<Vec<_>>(),
+    });
+
+    let subcommands: Vec<Value> = cmd.get_subcommands().map(command_schema).collect();
+    if !subcommands.is_empty() {
+        schema["subcommands"] = Value::Array(subcommands);
+    }
+    schema
+}
+
+fn arg_schema(arg: &Arg) -> Value {
+    let mut schema = json!({
+        "name": arg.get_id().as_str(),
+        "help": arg.get_help().map(|s| s.to_string()),
+        "required": arg.is_required_set(),
+        "positional": arg.is_positional(),
+        "takesValue": arg.get_action().takes_values(),
+    });
+
+    if let Some(long) = arg.get_long() {
+        schema["long"] = json!(format!("--{}", long));
+    }
+    if let Some(short) = arg.get_short() {
+        schema["short"] = json!(format!("-{}", short));
+    }
+    if matches!(arg.get_action(), clap::ArgAction::Append) {
+        schema["multiple"] = json!(true);
+    }
+
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() {
+        schema["default"] = json!(defaults.join(","));
+    }
+
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if !possible.is_empty() {
+        schema["possibleValues"] = json!(possible);
+    }
+
+    schema
+}
+
+/// clap's generated --help / --version flags
+fn is_builtin(arg: &Arg) -> bool {
+    matches!(arg.get_id().as_str(), "help" | "version")
+}
@@ -0,0 +1,214 @@
+//! Google Sheets subcommands
+
+use clap::Subcommand;
+
+use super::{export_document, written_output, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::drive;
+use crate::commands::sheets::{create, get, update, SheetListResponse, ValueInputOption};
+use crate::error::WorkspaceError;
+use crate::output::OutputFormat;
+
+#[derive(Debug, Subcommand)]
+pub enum SheetsCommands {
+    /// Get spreadsheet values
+    Get {
+        /// Spreadsheet ID
+        id: String,
+        /// Range in A1 notation (e.g., Sheet1!A1:C10)
+        #[arg(long)]
+        range: String,
+        /// Return full ValueRange (includes range, majorDimension metadata)
+        #[arg(long)]
+        full: bool,
+    },
+    /// Update spreadsheet values
+    Update {
+        /// Spreadsheet ID
+        id: String,
+        /// Range in A1 notation
+        #[arg(long)]
+        range: String,
+        /// Values as JSON array of arrays
+        #[arg(long)]
+        values: String,
+    },
+    /// Append rows to spreadsheet
+    Append {
+        /// Spreadsheet ID
+        id: String,
+        /// Range in A1 notation
+        #[arg(long)]
+        range: String,
+        /// Values as JSON array of arrays
+        #[arg(long)]
+        values: String,
+    },
+    /// Create a new spreadsheet
+    Create {
+        /// Spreadsheet title
+        title: String,
+    },
+    /// Clear a range of cells
+    Clear {
+        /// Spreadsheet ID
+        id: String,
+        /// Range to clear in A1 notation
+        #[arg(long)]
+        range: String,
+    },
+    /// List all sheets (tabs) in a spreadsheet
+    ListSheets {
+        /// Spreadsheet ID
+        id: String,
+    },
+    /// Move spreadsheet to trash
+    Delete {
+        /// Spreadsheet ID
+        id: String,
+    },
+    /// Add a new sheet (tab) to a spreadsheet
+    AddSheet {
+        /// Spreadsheet ID
+        id: String,
+        /// Name for the new sheet
+        #[arg(long)]
+        title: String,
+        /// Position index (0 = first, omit for end)
+        #[arg(long)]
+        index: Option<i64>,
+    },
+    /// Rename a sheet (tab) in a spreadsheet
+    RenameSheet {
+        /// Spreadsheet ID
+        id: String,
+        /// Sheet ID (numeric, from list-sheets command)
+        #[arg(long)]
+        sheet_id: i64,
+        /// New name for the sheet
+        #[arg(long)]
+        title: String,
+    },
+    /// Export spreadsheet to file
+    Export {
+        /// Spreadsheet ID
+        id: String,
+        /// Output file path, or - for stdout
+        #[arg(long, short)]
+        output: String,
+        /// Export format: csv, xlsx, pdf, ods, tsv, html
+        #[arg(long, short, default_value = "csv")]
+        format: String,
+        /// Sheet name for CSV/TSV export (optional, exports first sheet by default)
+        #[arg(long)]
+        sheet: Option<String>,
+    },
+}
+
+impl Command for SheetsCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::sheets);
+
+        match self {
+            SheetsCommands::Get { id, range, full } => {
+                let response = get::get_values(&client, &id, &range).await?;
+                if ctx.format == OutputFormat::Csv {
+                    Ok(CommandOutput::Text(get::values_to_csv(&response)))
+                } else if full {
+                    // Full ValueRange with metadata
+                    CommandOutput::value(&response)
+                } else {
+                    // Default: just the values array (minimal, token-efficient)
+                    CommandOutput::value(&response.values)
+                }
+            }
+            SheetsCommands::Update { id, range, values } => {
+                let params = update::UpdateParams {
+                    spreadsheet_id: id,
+                    range,
+                    values: update::parse_values_json(&values)?,
+                    value_input_option: ValueInputOption::UserEntered,
+                };
+                CommandOutput::value(&update::update_values(&client, params).await?)
+            }
+            SheetsCommands::Append { id, range, values } => {
+                let values = update::parse_values_json(&values)?;
+                let response =
+                    update::append_values(&client, &id, &range, values, ValueInputOption::UserEntered).await?;
+                CommandOutput::value(&response)
+            }
+            SheetsCommands::Create { title } => {
+                CommandOutput::value(&create::create_spreadsheet(&client, &title).await?)
+            }
+            SheetsCommands::Clear { id, range } => {
+                CommandOutput::value(&update::clear_values(&client, &id, &range).await?)
+            }
+            SheetsCommands::ListSheets { id } => {
+                let spreadsheet = get::get_spreadsheet(&client, &id).await?;
+                CommandOutput::value(&SheetListResponse::from_spreadsheet(&spreadsheet))
+            }
+            SheetsCommands::Delete { id } => {
+                // Spreadsheets are deleted by moving them to the Drive trash
+                let drive_client = ctx.client(ApiClient::drive);
+                let file = drive::delete::trash_file(&drive_client, &id).await?;
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "id": file.id,
+                    "trashed": true
+                })))
+            }
+            SheetsCommands::AddSheet { id, title, index } => {
+                let response = update::add_sheet(&client, &id, &title, index).await?;
+                // Extract the new sheet info from the reply
+                let sheet_info = response.replies.first()
+                    .and_then(|r| r.get("addSheet"))
+                    .and_then(|s| s.get("properties"));
+
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "spreadsheetId": response.spreadsheet_id,
+                    "title": title,
+                    "sheetId": sheet_info.and_then(|p| p.get("sheetId")),
+                    "index": sheet_info.and_then(|p| p.get("index"))
+                })))
+            }
+            SheetsCommands::RenameSheet { id, sheet_id, title } => {
+                let response = update::rename_sheet(&client, &id, sheet_id, &title).await?;
+                Ok(CommandOutput::Value(serde_json::json!({
+                    "success": true,
+                    "spreadsheetId": response.spreadsheet_id,
+                    "sheetId": sheet_id,
+                    "newTitle": title
+                })))
+            }
+            SheetsCommands::Export { id, output, format, sheet } => {
+                let mime_type = match format.to_lowercase().as_str() {
+                    "csv" => "text/csv",
+                    "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                    "pdf" => "application/pdf",
+                    "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+                    "tsv" => "text/tab-separated-values",
+                    "html" => "text/html",
+                    _ => {
+                        return Err(WorkspaceError::InvalidInput(format!(
+                            "Unsupported format '{}'. Supported: csv, xlsx, pdf, ods, tsv, html",
+                            format
+                        )));
+                    }
+                };
+
+                // CSV/TSV exports of a single sheet select it with the gid parameter
+                let export_id = match sheet {
+                    Some(ref sheet_name) => format!("{}?gid={}", id, sheet_name),
+                    None => id.clone(),
+                };
+
+                let mut result = export_document(ctx, &id, &export_id, mime_type, &output, &format).await?;
+                if let Some(sheet) = sheet {
+                    result["sheet"] = serde_json::json!(sheet);
+                }
+                Ok(written_output(&output, result))
+            }
+        }
+    }
+}
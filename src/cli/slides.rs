@@ -0,0 +1,143 @@
+//! Google Slides subcommands
+
+use clap::Subcommand;
+
+use super::{export_document, written_output, CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::slides;
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum SlidesCommands {
+    /// Get presentation info
+    Get {
+        /// Presentation ID
+        id: String,
+        /// Return full presentation structure (includes masters, layouts, transforms, etc.)
+        #[arg(long)]
+        full: bool,
+    },
+    /// Get specific page
+    Page {
+        /// Presentation ID
+        id: String,
+        /// Page number (0-indexed)
+        #[arg(long)]
+        page: u32,
+        /// Return full page structure (includes transforms, sizes, etc.)
+        #[arg(long)]
+        full: bool,
+    },
+    /// Export presentation to file
+    Export {
+        /// Presentation ID
+        id: String,
+        /// Output file path, or - for stdout
+        #[arg(long, short)]
+        output: String,
+        /// Export format: pdf, pptx, odp, txt
+        #[arg(long, short, default_value = "pdf")]
+        format: String,
+    },
+    /// Create a new presentation
+    Create {
+        /// Presentation title
+        #[arg(long)]
+        title: String,
+    },
+    /// Add a slide to a presentation
+    AddSlide {
+        /// Presentation ID
+        id: String,
+        /// Slide index (0-based position to insert)
+        #[arg(long)]
+        index: Option<i32>,
+        /// Layout: BLANK, TITLE, TITLE_AND_BODY, TITLE_ONLY, etc.
+        #[arg(long, default_value = "BLANK")]
+        layout: String,
+    },
+    /// Add text to a slide
+    AddText {
+        /// Presentation ID
+        id: String,
+        /// Page/slide object ID
+        #[arg(long)]
+        page_id: String,
+        /// Text content
+        #[arg(long)]
+        text: String,
+        /// X position in points (from left edge)
+        #[arg(long, default_value = "100")]
+        x: f64,
+        /// Y position in points (from top edge)
+        #[arg(long, default_value = "100")]
+        y: f64,
+        /// Width in points
+        #[arg(long, default_value = "400")]
+        width: f64,
+        /// Height in points
+        #[arg(long, default_value = "50")]
+        height: f64,
+    },
+}
+
+impl Command for SlidesCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::slides);
+
+        match self {
+            SlidesCommands::Get { id, full } => {
+                let presentation = slides::get_presentation(&client, &id).await?;
+                if full {
+                    CommandOutput::value(&presentation)
+                } else {
+                    // Default: text extraction (minimal, token-efficient)
+                    Ok(CommandOutput::Text(slides::extract_all_text(&presentation)))
+                }
+            }
+            SlidesCommands::Page { id, page, full } => {
+                let presentation = slides::get_presentation(&client, &id).await?;
+                let slide = presentation.slides.get(page as usize).ok_or_else(|| {
+                    WorkspaceError::NotFound(format!(
+                        "Page {} not found. Presentation has {} slides.",
+                        page,
+                        presentation.slides.len()
+                    ))
+                })?;
+
+                if full {
+                    CommandOutput::value(slide)
+                } else {
+                    Ok(CommandOutput::Text(slides::extract_page_text(slide)))
+                }
+            }
+            SlidesCommands::Export { id, output, format } => {
+                let mime_type = match format.to_lowercase().as_str() {
+                    "pdf" => "application/pdf",
+                    "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+                    "odp" => "application/vnd.oasis.opendocument.presentation",
+                    "txt" | "text" => "text/plain",
+                    _ => {
+                        return Err(WorkspaceError::InvalidInput(format!(
+                            "Unsupported format '{}'. Supported: pdf, pptx, odp, txt",
+                            format
+                        )));
+                    }
+                };
+
+                let result = export_document(ctx, &id, &id, mime_type, &output, &format).await?;
+                Ok(written_output(&output, result))
+            }
+            SlidesCommands::Create { title } => {
+                CommandOutput::value(&slides::create_presentation(&client, &title).await?)
+            }
+            SlidesCommands::AddSlide { id, index, layout } => {
+                CommandOutput::value(&slides::add_slide(&client, &id, index, Some(&layout)).await?)
+            }
+            SlidesCommands::AddText { id, page_id, text, x, y, width, height } => {
+                let response = slides::add_text(&client, &id, &page_id, &text, x, y, width, height).await?;
+                CommandOutput::value(&response)
+            }
+        }
+    }
+}
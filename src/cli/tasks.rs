@@ -0,0 +1,119 @@
+//! Google Tasks subcommands
+
+use clap::Subcommand;
+
+use super::{CliContext, Command, CommandOutput};
+use crate::client::ApiClient;
+use crate::commands::tasks::types::MinimalTasks;
+use crate::commands::tasks::{create, list, update, TaskStatus};
+use crate::error::WorkspaceError;
+
+#[derive(Debug, Subcommand)]
+pub enum TasksCommands {
+    /// List task lists
+    Lists,
+    /// List tasks in a task list
+    List {
+        /// Task list ID
+        #[arg(long, default_value = "@default")]
+        list: String,
+        /// Maximum number of results (1-100)
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Show completed tasks
+        #[arg(long)]
+        show_completed: bool,
+        /// Return full task data (includes kind, etag, links, position, etc.)
+        #[arg(long)]
+        full: bool,
+    },
+    /// Create a task
+    Create {
+        /// Task title
+        title: String,
+        /// Task list ID
+        #[arg(long, default_value = "@default")]
+        list: String,
+        /// Due date (RFC3339)
+        #[arg(long)]
+        due: Option<String>,
+        /// Notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// Update a task
+    Update {
+        /// Task ID
+        id: String,
+        /// Task list ID
+        #[arg(long, default_value = "@default")]
+        list: String,
+        /// New title
+        #[arg(long)]
+        title: Option<String>,
+        /// Mark as completed
+        #[arg(long)]
+        complete: bool,
+    },
+    /// Delete a task
+    Delete {
+        /// Task ID
+        id: String,
+        /// Task list ID
+        #[arg(long, default_value = "@default")]
+        list: String,
+    },
+}
+
+impl Command for TasksCommands {
+    async fn run(self, ctx: &CliContext) -> Result<CommandOutput, WorkspaceError> {
+        let client = ctx.client(ApiClient::tasks);
+
+        match self {
+            TasksCommands::Lists => {
+                CommandOutput::value(&list::list_task_lists(&client).await?)
+            }
+            TasksCommands::List { list: task_list_id, limit, show_completed, full } => {
+                let params = list::ListTasksParams {
+                    task_list_id,
+                    max_results: limit.min(100),  // API max is 100
+                    show_completed,
+                    show_hidden: false,
+                    page_token: None,
+                };
+                let tasks = list::list_tasks(&client, params).await?;
+                if full {
+                    CommandOutput::value(&tasks)
+                } else {
+                    // Default: minimal task data (id, title, status, due, notes, completed)
+                    CommandOutput::value(&MinimalTasks::from_tasks(&tasks))
+                }
+            }
+            TasksCommands::Create { title, list, due, notes } => {
+                let params = create::CreateTaskParams {
+                    task_list_id: list,
+                    title,
+                    notes,
+                    due,
+                    parent: None,
+                };
+                CommandOutput::value(&create::create_task(&client, params).await?)
+            }
+            TasksCommands::Update { id, list, title, complete } => {
+                let params = update::UpdateTaskParams {
+                    task_list_id: list,
+                    task_id: id,
+                    title,
+                    status: if complete { Some(TaskStatus::Completed) } else { None },
+                    notes: None,
+                    due: None,
+                };
+                CommandOutput::value(&update::update_task(&client, params).await?)
+            }
+            TasksCommands::Delete { id, list } => {
+                update::delete_task(&client, &list, &id).await?;
+                Ok(CommandOutput::success("Task deleted"))
+            }
+        }
+    }
+}
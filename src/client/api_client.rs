@@ -1,9 +1,12 @@
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
 use std::time::Duration;
+use tracing::Instrument;
 
 use crate::auth::TokenManager;
 use crate::error::{WorkspaceError, ApiError};
+use crate::output::progress::{self, ProgressEvent};
+use super::cache::{CacheEvent, CacheMode, ResponseCache};
 use super::rate_limiter::{ApiRateLimiter, ConcurrencyPermit};
 use super::retry::{RetryConfig, Retryable, with_retry, RetryError, is_retryable_status, parse_retry_after};
 
@@ -25,6 +28,8 @@ pub struct ApiClient {
     rate_limiter: Option<ApiRateLimiter>,
     retry_config: RetryConfig,
     base_url: String,
+    service: &'static str,
+    cache: Option<ResponseCache>,
 }
 
 impl ApiClient {
@@ -44,6 +49,8 @@ impl ApiClient {
             rate_limiter: None,
             retry_config: RetryConfig::default(),
             base_url: String::new(),
+            service: "api",
+            cache: None,
         }
     }
 
@@ -65,10 +72,23 @@ impl ApiClient {
         self
     }
 
+    /// Set the service name used to key cache entries
+    pub fn with_service(mut self, service: &'static str) -> Self {
+        self.service = service;
+        self
+    }
+
+    /// Enable the on-disk response cache for GET requests
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Create a Gmail client
     pub fn gmail(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::GMAIL)
+            .with_service("gmail")
             .with_rate_limiter(ApiRateLimiter::gmail())
             .with_retry_config(RetryConfig::conservative())
     }
@@ -77,6 +97,7 @@ impl ApiClient {
     pub fn drive(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::DRIVE)
+            .with_service("drive")
             .with_rate_limiter(ApiRateLimiter::drive())
             .with_retry_config(RetryConfig::conservative())
     }
@@ -85,6 +106,7 @@ impl ApiClient {
     pub fn calendar(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::CALENDAR)
+            .with_service("calendar")
             .with_rate_limiter(ApiRateLimiter::calendar())
             .with_retry_config(RetryConfig::default())
     }
@@ -93,6 +115,7 @@ impl ApiClient {
     pub fn docs(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::DOCS)
+            .with_service("docs")
             .with_rate_limiter(ApiRateLimiter::docs())
             .with_retry_config(RetryConfig::aggressive())
     }
@@ -101,6 +124,7 @@ impl ApiClient {
     pub fn sheets(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::SHEETS)
+            .with_service("sheets")
             .with_rate_limiter(ApiRateLimiter::docs())
             .with_retry_config(RetryConfig::aggressive())
     }
@@ -109,6 +133,7 @@ impl ApiClient {
     pub fn slides(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::SLIDES)
+            .with_service("slides")
             .with_rate_limiter(ApiRateLimiter::docs())
             .with_retry_config(RetryConfig::aggressive())
     }
@@ -117,6 +142,7 @@ impl ApiClient {
     pub fn tasks(token_manager: std::sync::Arc<tokio::sync::RwLock<TokenManager>>) -> Self {
         Self::new(token_manager)
             .with_base_url(endpoints::TASKS)
+            .with_service("tasks")
             .with_rate_limiter(ApiRateLimiter::tasks())
             .with_retry_config(RetryConfig::default())
     }
@@ -140,7 +166,7 @@ impl ApiClient {
 
     /// Execute a GET request
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WorkspaceError> {
-        self.request_no_body(Method::GET, path, 1).await
+        self.get_url(path).await
     }
 
     /// Execute a GET request with query parameters
@@ -149,18 +175,34 @@ impl ApiClient {
         T: DeserializeOwned,
         Q: Serialize + ?Sized,
     {
+        let full_url = self.query_url(path, query)?;
+        self.get_url(&full_url).await
+    }
+
+    /// Execute a GET request with query parameters, bypassing the response cache
+    ///
+    /// For feeds such as Gmail history and Drive changes, whose answer for the
+    /// same URL changes as new events arrive.
+    pub async fn get_with_query_uncached<T, Q>(&self, path: &str, query: &Q) -> Result<T, WorkspaceError>
+    where
+        T: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        let full_url = self.query_url(path, query)?;
+        self.request_no_body(Method::GET, &full_url, 1).await
+    }
+
+    /// Full URL for a path with query parameters
+    fn query_url<Q: Serialize + ?Sized>(&self, path: &str, query: &Q) -> Result<String, WorkspaceError> {
         let query_string = serde_urlencoded::to_string(query)
             .map_err(|e| WorkspaceError::Config(e.to_string()))?;
 
-        let full_url = if query_string.is_empty() {
-            self.build_url(path)
-        } else {
-            let base_url = self.build_url(path);
-            let separator = if base_url.contains('?') { "&" } else { "?" };
-            format!("{}{}{}", base_url, separator, query_string)
-        };
-
-        self.request_no_body(Method::GET, &full_url, 1).await
+        let base_url = self.build_url(path);
+        if query_string.is_empty() {
+            return Ok(base_url);
+        }
+        let separator = if base_url.contains('?') { "&" } else { "?" };
+        Ok(format!("{}{}{}", base_url, separator, query_string))
     }
 
     /// Execute a POST request
@@ -193,9 +235,148 @@ impl ApiClient {
     /// Execute a DELETE request
     pub async fn delete(&self, path: &str) -> Result<(), WorkspaceError> {
         let _: serde_json::Value = self.request_no_body(Method::DELETE, path, 1).await?;
+        self.invalidate_cache();
         Ok(())
     }
 
+    /// Execute a GET request, going through the response cache when enabled
+    async fn get_url<T: DeserializeOwned>(&self, path: &str) -> Result<T, WorkspaceError> {
+        let Some(ref cache) = self.cache else {
+            return self.request_no_body(Method::GET, path, 1).await;
+        };
+
+        let url = self.build_url(path);
+        let span = self.request_span(&Method::GET, &url);
+
+        async {
+            let cached = cache.lookup(self.service, &url);
+
+            if let Some(ref entry) = cached {
+                if cache.mode() == CacheMode::Only || cache.is_fresh(self.service, entry) {
+                    cache.record(CacheEvent::Hit);
+                    span.record("cache", "hit");
+                    return Ok(serde_json::from_value(entry.body.clone())?);
+                }
+            }
+            if cache.mode() == CacheMode::Only {
+                return Err(WorkspaceError::NotFound(format!("No cached response for {}", url)));
+            }
+
+            let _permit = self.acquire_permit(1).await;
+            let etag = cached.as_ref().and_then(|e| e.etag.clone());
+
+            let make_request = || async {
+                let token = self.get_token().await?;
+
+                let mut builder = self.http.get(&url).bearer_auth(&token);
+                if let Some(ref tag) = etag {
+                    builder = builder.header(reqwest::header::IF_NONE_MATCH, tag);
+                }
+
+                let response = self.send(&span, &Method::GET, &url, builder).await?;
+                if response.status() == StatusCode::NOT_MODIFIED {
+                    return Ok(None);
+                }
+                let new_etag = response.headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from);
+                let body: serde_json::Value = self.handle_response(response).await?;
+                Ok(Some((new_etag, body)))
+            };
+
+            let result = match with_retry(self.retry_config.clone(), make_request).await {
+                Ok(value) => value,
+                Err(RetryError::NonRetryable(e)) => return Err(e),
+                Err(RetryError::MaxRetriesExceeded { last_error, .. }) => return Err(last_error),
+            };
+
+            match (result, cached) {
+                (Some((new_etag, body)), _) => {
+                    cache.record(CacheEvent::Miss);
+                    span.record("cache", "miss");
+                    cache.store(self.service, &url, new_etag, &body);
+                    Ok(serde_json::from_value(body)?)
+                }
+                (None, Some(entry)) => {
+                    cache.record(CacheEvent::Revalidated);
+                    span.record("cache", "revalidated");
+                    let body = entry.body.clone();
+                    cache.touch(self.service, entry);
+                    Ok(serde_json::from_value(body)?)
+                }
+                (None, None) => Err(WorkspaceError::Api(ApiError {
+                    code: StatusCode::NOT_MODIFIED.as_u16(),
+                    message: "Not modified, but no cached response is available".to_string(),
+                    domain: "cache".to_string(),
+                    retry_after: None,
+                })),
+            }
+        }
+        .instrument(span.clone())
+        .await
+    }
+
+    /// Span covering one logical API request, across all retry attempts
+    fn request_span(&self, method: &Method, url: &str) -> tracing::Span {
+        tracing::info_span!(
+            "api.request",
+            service = self.service,
+            method = %method,
+            path = %path_template(url),
+            status = tracing::field::Empty,
+            attempts = tracing::field::Empty,
+            cache = tracing::field::Empty,
+        )
+    }
+
+    /// Send a request, recording the status on the request span and reporting progress events
+    async fn send(
+        &self,
+        span: &tracing::Span,
+        method: &Method,
+        url: &str,
+        builder: RequestBuilder,
+    ) -> Result<Response, WorkspaceError> {
+        if !progress::enabled() {
+            let response = builder.send().await?;
+            span.record("status", response.status().as_u16());
+            return Ok(response);
+        }
+
+        progress::emit(ProgressEvent::RequestStart {
+            method: method.to_string(),
+            url: url.to_string(),
+        });
+        let started = std::time::Instant::now();
+        let response = builder.send().await?;
+        span.record("status", response.status().as_u16());
+        progress::emit(ProgressEvent::RequestEnd {
+            method: method.to_string(),
+            url: url.to_string(),
+            status: response.status().as_u16(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+        Ok(response)
+    }
+
+    /// Drop this service's cached responses after a successful write
+    ///
+    /// Called automatically for writes through this client; writes made with
+    /// a raw token (such as Drive uploads) call it themselves.
+    pub fn invalidate_cache(&self) {
+        if let Some(ref cache) = self.cache {
+            cache.invalidate_service(self.service);
+        }
+    }
+
+    /// Acquire a rate limit permit, if a limiter is configured
+    async fn acquire_permit(&self, cost: u32) -> Option<ConcurrencyPermit> {
+        let limiter = self.rate_limiter.as_ref()?;
+        let span = tracing::debug_span!("rate_limit.acquire", service = self.service, cost);
+        limiter.acquire(cost).instrument(span).await.ok().flatten()
+    }
+
     /// Execute a request without body (GET, DELETE)
     async fn request_no_body<T: DeserializeOwned>(
         &self,
@@ -203,35 +384,36 @@ impl ApiClient {
         path: &str,
         cost: u32,
     ) -> Result<T, WorkspaceError> {
-        // Acquire rate limit
-        let _permit: Option<ConcurrencyPermit> = if let Some(ref limiter) = self.rate_limiter {
-            limiter.acquire(cost).await.ok().flatten()
-        } else {
-            None
-        };
-
         let url = self.build_url(path);
+        let span = self.request_span(&method, &url);
 
-        // Create the request closure for retry
-        let make_request = || async {
-            // Get fresh token for each attempt (in case it expires during retries)
-            let token = self.get_token().await?;
+        async {
+            // Acquire rate limit
+            let _permit = self.acquire_permit(cost).await;
 
-            let builder = self.http.request(method.clone(), &url)
-                .bearer_auth(&token);
+            // Create the request closure for retry
+            let make_request = || async {
+                // Get fresh token for each attempt (in case it expires during retries)
+                let token = self.get_token().await?;
 
-            let response = builder.send().await?;
-            self.handle_response(response).await
-        };
+                let builder = self.http.request(method.clone(), &url)
+                    .bearer_auth(&token);
+
+                let response = self.send(&span, &method, &url, builder).await?;
+                self.handle_response(response).await
+            };
 
-        // Execute with retry
-        let result = with_retry(self.retry_config.clone(), make_request).await;
+            // Execute with retry
+            let result = with_retry(self.retry_config.clone(), make_request).await;
 
-        match result {
-            Ok(value) => Ok(value),
-            Err(RetryError::NonRetryable(e)) => Err(e),
-            Err(RetryError::MaxRetriesExceeded { last_error, .. }) => Err(last_error),
+            match result {
+                Ok(value) => Ok(value),
+                Err(RetryError::NonRetryable(e)) => Err(e),
+                Err(RetryError::MaxRetriesExceeded { last_error, .. }) => Err(last_error),
+            }
         }
+        .instrument(span.clone())
+        .await
     }
 
     /// Execute a request with body and rate limiting and retry
@@ -246,39 +428,43 @@ impl ApiClient {
         T: DeserializeOwned,
         B: Serialize + ?Sized,
     {
-        // Acquire rate limit
-        let _permit: Option<ConcurrencyPermit> = if let Some(ref limiter) = self.rate_limiter {
-            limiter.acquire(cost).await.ok().flatten()
-        } else {
-            None
-        };
-
         let url = self.build_url(path);
-
-        // Create the request closure for retry
-        let make_request = || async {
-            // Get fresh token for each attempt (in case it expires during retries)
-            let token = self.get_token().await?;
-
-            let mut builder = self.http.request(method.clone(), &url)
-                .bearer_auth(&token);
-
-            if let Some(b) = body {
-                builder = builder.json(b);
+        let span = self.request_span(&method, &url);
+
+        async {
+            // Acquire rate limit
+            let _permit = self.acquire_permit(cost).await;
+
+            // Create the request closure for retry
+            let make_request = || async {
+                // Get fresh token for each attempt (in case it expires during retries)
+                let token = self.get_token().await?;
+
+                let mut builder = self.http.request(method.clone(), &url)
+                    .bearer_auth(&token);
+
+                if let Some(b) = body {
+                    builder = builder.json(b);
+                }
+
+                let response = self.send(&span, &method, &url, builder).await?;
+                self.handle_response(response).await
+            };
+
+            // Execute with retry
+            let result = with_retry(self.retry_config.clone(), make_request).await;
+
+            match result {
+                Ok(value) => {
+                    self.invalidate_cache();
+                    Ok(value)
+                }
+                Err(RetryError::NonRetryable(e)) => Err(e),
+                Err(RetryError::MaxRetriesExceeded { last_error, .. }) => Err(last_error),
             }
-
-            let response = builder.send().await?;
-            self.handle_response(response).await
-        };
-
-        // Execute with retry
-        let result = with_retry(self.retry_config.clone(), make_request).await;
-
-        match result {
-            Ok(value) => Ok(value),
-            Err(RetryError::NonRetryable(e)) => Err(e),
-            Err(RetryError::MaxRetriesExceeded { last_error, .. }) => Err(last_error),
         }
+        .instrument(span.clone())
+        .await
     }
 
     /// Handle API response
@@ -286,7 +472,12 @@ impl ApiClient {
         let status = response.status();
 
         if status.is_success() {
-            response.json().await.map_err(WorkspaceError::from)
+            // Some endpoints (batchModify, batchDelete) return an empty body on success
+            let body = response.bytes().await.map_err(WorkspaceError::from)?;
+            if body.iter().all(u8::is_ascii_whitespace) {
+                return serde_json::from_value(serde_json::Value::Null).map_err(WorkspaceError::from);
+            }
+            serde_json::from_slice(&body).map_err(WorkspaceError::from)
         } else {
             let retry_after = response.headers()
                 .get("retry-after")
@@ -312,6 +503,38 @@ impl ApiClient {
     }
 }
 
+/// Reduce a request URL to a low-cardinality path template for spans
+///
+/// Drops the scheme, host and query string and replaces resource IDs with `{id}`,
+/// e.g. `/gmail/v1/users/me/messages/{id}`.
+fn path_template(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = without_scheme.find('/').map(|i| &without_scheme[i..]).unwrap_or("/");
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+
+    path.split('/')
+        .map(|segment| match segment.split_once(':') {
+            // Custom methods like `{id}:batchUpdate`
+            Some((head, verb)) if !verb.is_empty() && verb.chars().all(|c| c.is_ascii_alphabetic()) => {
+                format!("{}:{}", template_segment(head), verb)
+            }
+            _ => template_segment(segment),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn template_segment(segment: &str) -> String {
+    let looks_like_id = segment.len() >= 16
+        || segment.contains(['%', '@', '!'])
+        || (segment.len() >= 6 && segment.chars().any(|c| c.is_ascii_digit()));
+    if looks_like_id {
+        "{id}".to_string()
+    } else {
+        segment.to_string()
+    }
+}
+
 /// Implement Retryable for WorkspaceError
 impl Retryable for WorkspaceError {
     fn is_retryable(&self) -> bool {
@@ -329,3 +552,24 @@ impl Retryable for WorkspaceError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_template() {
+        assert_eq!(
+            path_template("https://gmail.googleapis.com/gmail/v1/users/me/messages/18c2f3a4b5d6e7f8?format=full"),
+            "/gmail/v1/users/me/messages/{id}"
+        );
+        assert_eq!(
+            path_template("https://docs.googleapis.com/v1/documents/1AbCdEfGhIjKlMnOpQrStUvWxYz0123456789:batchUpdate"),
+            "/v1/documents/{id}:batchUpdate"
+        );
+        assert_eq!(
+            path_template("https://www.googleapis.com/calendar/v3/calendars/user%40example.com/events"),
+            "/calendar/v3/calendars/{id}/events"
+        );
+    }
+}
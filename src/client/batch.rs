@@ -311,3 +311,19 @@ pub enum BatchError {
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
 }
+
+impl From<BatchError> for crate::error::WorkspaceError {
+    fn from(err: BatchError) -> Self {
+        use crate::error::{ApiError, WorkspaceError};
+        match err {
+            BatchError::Network(e) => WorkspaceError::Network(e),
+            BatchError::HttpError { status, message } => WorkspaceError::Api(ApiError {
+                code: status,
+                message,
+                domain: "batch".to_string(),
+                retry_after: None,
+            }),
+            other => WorkspaceError::InvalidInput(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::config::CacheConfig;
+
+/// How the response cache is used for a single invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve fresh entries, revalidate stale ones with If-None-Match
+    #[default]
+    Normal,
+    /// Skip the cache entirely (--no-cache)
+    Bypass,
+    /// Only serve from the cache, never touch the network (--cache-only)
+    Only,
+}
+
+/// A cached GET response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Full request URL (including query string and `fields`)
+    pub url: String,
+    /// ETag returned by the server, used for If-None-Match revalidation
+    pub etag: Option<String>,
+    /// Unix timestamp (seconds) when the entry was stored or last revalidated
+    pub stored_at: i64,
+    /// Decoded JSON response body
+    pub body: serde_json::Value,
+}
+
+/// Persistent hit/miss counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheCounters {
+    pub hits: u64,
+    pub misses: u64,
+    pub revalidated: u64,
+}
+
+/// Outcome of a cache lookup, recorded in the counters
+#[derive(Debug, Clone, Copy)]
+pub enum CacheEvent {
+    /// Served from a fresh entry without a request
+    Hit,
+    /// Fetched from the API and stored
+    Miss,
+    /// Server answered 304 Not Modified for a stale entry
+    Revalidated,
+}
+
+/// Per-service cache usage
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceStats {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+/// Summary returned by `workspace-cli cache stats`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub dir: PathBuf,
+    pub entries: u64,
+    pub bytes: u64,
+    pub services: BTreeMap<String, ServiceStats>,
+    pub hits: u64,
+    pub misses: u64,
+    pub revalidated: u64,
+}
+
+/// On-disk cache for GET responses
+///
+/// Entries live at `<dir>/<account>/<service>/<sha256(url)>.json`, so they are
+/// keyed by account, service and the full request URL (which carries any
+/// `fields` selector). Successful writes through an [`ApiClient`] drop the
+/// service's entries for that account.
+///
+/// [`ApiClient`]: super::ApiClient
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    root: PathBuf,
+    account: String,
+    mode: CacheMode,
+    default_ttl: u64,
+    ttls: HashMap<String, u64>,
+}
+
+impl ResponseCache {
+    pub fn new(root: impl Into<PathBuf>, account: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            account: account.into(),
+            mode: CacheMode::Normal,
+            default_ttl: 0,
+            ttls: HashMap::new(),
+        }
+    }
+
+    /// Build the cache for this invocation, or None when caching is off
+    ///
+    /// `--cache-only` always enables the cache, even if it is disabled in config.
+    pub fn from_config(config: &CacheConfig, account: &str, mode: CacheMode) -> Option<Self> {
+        if mode == CacheMode::Bypass || (!config.enabled && mode != CacheMode::Only) {
+            return None;
+        }
+        let root = config.dir.clone().or_else(crate::config::Config::cache_dir)?;
+
+        Some(Self {
+            root,
+            account: account.to_string(),
+            mode,
+            default_ttl: config.default_ttl_seconds,
+            ttls: config.ttl_seconds.clone(),
+        })
+    }
+
+    pub fn with_mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_ttl(mut self, service: &str, seconds: u64) -> Self {
+        self.ttls.insert(service.to_string(), seconds);
+        self
+    }
+
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// TTL in seconds for a service
+    pub fn ttl(&self, service: &str) -> u64 {
+        self.ttls.get(service).copied().unwrap_or(self.default_ttl)
+    }
+
+    fn service_dir(&self, service: &str) -> PathBuf {
+        self.root.join(sanitize_component(&self.account)).join(service)
+    }
+
+    fn entry_path(&self, service: &str, url: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(url.as_bytes());
+        let digest = hasher.finalize();
+        let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.service_dir(service).join(format!("{}.json", name))
+    }
+
+    /// Look up a cached entry (regardless of freshness)
+    pub fn lookup(&self, service: &str, url: &str) -> Option<CacheEntry> {
+        let content = std::fs::read(self.entry_path(service, url)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+        // Guard against hash collisions
+        (entry.url == url).then_some(entry)
+    }
+
+    /// Whether an entry is still within the service TTL
+    pub fn is_fresh(&self, service: &str, entry: &CacheEntry) -> bool {
+        let age = chrono::Utc::now().timestamp() - entry.stored_at;
+        age >= 0 && (age as u64) < self.ttl(service)
+    }
+
+    /// Store a response body
+    pub fn store(&self, service: &str, url: &str, etag: Option<String>, body: &serde_json::Value) {
+        let entry = CacheEntry {
+            url: url.to_string(),
+            etag,
+            stored_at: chrono::Utc::now().timestamp(),
+            body: body.clone(),
+        };
+        if let Err(e) = self.write_entry(service, &entry) {
+            tracing::debug!(error = %e, "Failed to write cache entry");
+        }
+    }
+
+    /// Mark an entry as freshly revalidated (after a 304)
+    pub fn touch(&self, service: &str, mut entry: CacheEntry) {
+        entry.stored_at = chrono::Utc::now().timestamp();
+        if let Err(e) = self.write_entry(service, &entry) {
+            tracing::debug!(error = %e, "Failed to refresh cache entry");
+        }
+    }
+
+    fn write_entry(&self, service: &str, entry: &CacheEntry) -> std::io::Result<()> {
+        let path = self.entry_path(service, &entry.url);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_vec(entry)?;
+        write_atomic(&path, &content)
+    }
+
+    /// Drop all entries for a service (called after successful writes)
+    pub fn invalidate_service(&self, service: &str) {
+        let dir = self.service_dir(service);
+        if dir.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                tracing::debug!(error = %e, "Failed to invalidate cache");
+            }
+        }
+    }
+
+    /// Record a lookup outcome in the persistent counters
+    pub fn record(&self, event: CacheEvent) {
+        let path = self.root.join("stats.json");
+        let mut counters: CacheCounters = std::fs::read(&path)
+            .ok()
+            .and_then(|c| serde_json::from_slice(&c).ok())
+            .unwrap_or_default();
+
+        match event {
+            CacheEvent::Hit => counters.hits += 1,
+            CacheEvent::Miss => counters.misses += 1,
+            CacheEvent::Revalidated => counters.revalidated += 1,
+        }
+
+        let result = std::fs::create_dir_all(&self.root)
+            .and_then(|_| serde_json::to_vec(&counters).map_err(std::io::Error::from))
+            .and_then(|content| write_atomic(&path, &content));
+        if let Err(e) = result {
+            tracing::debug!(error = %e, "Failed to update cache stats");
+        }
+    }
+}
+
+/// Collect cache usage statistics for a cache directory
+pub fn stats(root: &Path, enabled: bool) -> CacheStats {
+    let mut services: BTreeMap<String, ServiceStats> = BTreeMap::new();
+
+    if let Ok(accounts) = std::fs::read_dir(root) {
+        for account in accounts.flatten().filter(|e| e.path().is_dir()) {
+            let Ok(service_dirs) = std::fs::read_dir(account.path()) else { continue };
+            for service in service_dirs.flatten().filter(|e| e.path().is_dir()) {
+                let name = service.file_name().to_string_lossy().to_string();
+                let stats = services.entry(name).or_default();
+                let Ok(entries) = std::fs::read_dir(service.path()) else { continue };
+                for entry in entries.flatten() {
+                    if let Ok(meta) = entry.metadata() {
+                        stats.entries += 1;
+                        stats.bytes += meta.len();
+                    }
+                }
+            }
+        }
+    }
+
+    let counters: CacheCounters = std::fs::read(root.join("stats.json"))
+        .ok()
+        .and_then(|c| serde_json::from_slice(&c).ok())
+        .unwrap_or_default();
+
+    CacheStats {
+        enabled,
+        dir: root.to_path_buf(),
+        entries: services.values().map(|s| s.entries).sum(),
+        bytes: services.values().map(|s| s.bytes).sum(),
+        services,
+        hits: counters.hits,
+        misses: counters.misses,
+        revalidated: counters.revalidated,
+    }
+}
+
+/// Remove cached entries, optionally only for one account. Returns the number of entries removed.
+pub fn clear(root: &Path, account: Option<&str>) -> std::io::Result<u64> {
+    let targets: Vec<PathBuf> = match account {
+        Some(acc) => vec![root.join(sanitize_component(acc))],
+        None => match std::fs::read_dir(root) {
+            Ok(entries) => entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect(),
+            Err(_) => Vec::new(),
+        },
+    };
+
+    let mut removed = 0;
+    for dir in targets {
+        removed += count_files(&dir);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+    }
+
+    if account.is_none() {
+        let stats_path = root.join("stats.json");
+        if stats_path.exists() {
+            std::fs::remove_file(stats_path)?;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn count_files(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|e| if e.path().is_dir() { count_files(&e.path()) } else { 1 })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Keep account names usable as directory names
+fn sanitize_component(value: &str) -> String {
+    value.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '@' | '.' | '-' | '_') { c } else { '_' })
+        .collect()
+}
+
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache() -> ResponseCache {
+        let dir = std::env::temp_dir().join(format!("workspace-cli-cache-{}", uuid::Uuid::new_v4()));
+        ResponseCache::new(dir, "test@example.com").with_ttl("docs", 60)
+    }
+
+    #[test]
+    fn test_store_and_lookup() {
+        let cache = temp_cache();
+        let url = "https://docs.googleapis.com/v1/documents/abc?fields=title";
+        let body = serde_json::json!({"title": "Doc"});
+
+        assert!(cache.lookup("docs", url).is_none());
+        cache.store("docs", url, Some("\"etag-1\"".to_string()), &body);
+
+        let entry = cache.lookup("docs", url).expect("entry stored");
+        assert_eq!(entry.body, body);
+        assert_eq!(entry.etag.as_deref(), Some("\"etag-1\""));
+        assert!(cache.is_fresh("docs", &entry));
+        // No TTL configured for drive: entries are immediately stale
+        assert!(!cache.is_fresh("drive", &entry));
+
+        cache.invalidate_service("docs");
+        assert!(cache.lookup("docs", url).is_none());
+        let _ = std::fs::remove_dir_all(&cache.root);
+    }
+
+    #[test]
+    fn test_stats_and_clear() {
+        let cache = temp_cache();
+        cache.store("drive", "https://example.com/a", None, &serde_json::json!({}));
+        cache.store("drive", "https://example.com/b", None, &serde_json::json!({}));
+        cache.record(CacheEvent::Hit);
+
+        let summary = stats(&cache.root, true);
+        assert_eq!(summary.entries, 2);
+        assert_eq!(summary.services["drive"].entries, 2);
+        assert_eq!(summary.hits, 1);
+
+        assert_eq!(clear(&cache.root, None).unwrap(), 2);
+        assert_eq!(stats(&cache.root, true).entries, 0);
+        let _ = std::fs::remove_dir_all(&cache.root);
+    }
+}
@@ -1,9 +1,11 @@
 pub mod api_client;
 pub mod batch;
+pub mod cache;
 pub mod rate_limiter;
 pub mod retry;
 
 pub use api_client::{ApiClient, endpoints};
+pub use cache::{CacheMode, ResponseCache};
 pub use batch::{BatchClient, BatchRequest, BatchResponse, BatchError, batch_endpoints};
 pub use rate_limiter::{ApiRateLimiter, RateLimitConfig, gmail_costs};
 pub use retry::{RetryConfig, RetryState, Retryable, with_retry};
@@ -2,6 +2,8 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, Semaphore};
 
+use crate::output::progress::{self, ProgressEvent};
+
 /// Rate limiter configuration for a specific API
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
@@ -96,7 +98,9 @@ impl TokenBucket {
             let wait_secs = needed / self.config.refill_rate;
             drop(tokens); // Release lock while waiting
 
-            tokio::time::sleep(Duration::from_secs_f64(wait_secs.min(1.0))).await;
+            let wait = Duration::from_secs_f64(wait_secs.min(1.0));
+            progress::emit(ProgressEvent::RateLimitWait { wait_ms: wait.as_millis() as u64 });
+            tokio::time::sleep(wait).await;
         }
     }
 
@@ -1,5 +1,8 @@
 use std::time::Duration;
 use rand::Rng;
+use tracing::Instrument;
+
+use crate::output::progress::{self, ProgressEvent};
 
 /// Retry configuration
 #[derive(Debug, Clone)]
@@ -176,12 +179,18 @@ pub async fn with_retry<F, Fut, T, E>(
 where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = Result<T, E>>,
-    E: Retryable + std::fmt::Debug,
+    E: Retryable + std::fmt::Debug + std::fmt::Display,
 {
     let mut state = RetryState::new(config);
+    let caller = tracing::Span::current();
 
     loop {
-        match operation().await {
+        let attempt_span = tracing::debug_span!("retry.attempt", attempt = state.attempt() + 1);
+        let outcome = operation().instrument(attempt_span).await;
+        // Recorded on the caller's span (e.g. api.request) when it declares `attempts`
+        caller.record("attempts", state.attempt() + 1);
+
+        match outcome {
             Ok(result) => return Ok(result),
             Err(e) => {
                 if !e.is_retryable() {
@@ -209,7 +218,18 @@ where
                     backoff_ms = duration.as_millis() as u64,
                     "Retrying after backoff"
                 );
-                tokio::time::sleep(duration).await;
+                progress::emit(ProgressEvent::Retry {
+                    attempt: state.attempt(),
+                    backoff_ms: duration.as_millis() as u64,
+                    error: e.to_string(),
+                });
+                tokio::time::sleep(duration)
+                    .instrument(tracing::debug_span!(
+                        "retry.backoff",
+                        attempt = state.attempt(),
+                        backoff_ms = duration.as_millis() as u64,
+                    ))
+                    .await;
             }
         }
     }
@@ -1,6 +1,8 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use crate::client::batch::{BatchClient, BatchRequest, BatchError};
+use crate::output::progress::{self, ProgressEvent};
+use tracing::Instrument;
 
 /// Input format for a single batch request (agent-friendly JSON)
 #[derive(Debug, Deserialize)]
@@ -83,6 +85,24 @@ pub async fn execute_batch(
     service: &str,
     inputs: Vec<BatchRequestInput>,
     access_token: &str,
+) -> Result<BatchOutput, BatchError> {
+    let span = tracing::info_span!(
+        "batch.execute",
+        service,
+        requests = inputs.len(),
+        succeeded = tracing::field::Empty,
+        failed = tracing::field::Empty,
+    );
+    execute_batch_inner(service, inputs, access_token, &span)
+        .instrument(span.clone())
+        .await
+}
+
+async fn execute_batch_inner(
+    service: &str,
+    inputs: Vec<BatchRequestInput>,
+    access_token: &str,
+    span: &tracing::Span,
 ) -> Result<BatchOutput, BatchError> {
     // Convert inputs to BatchRequests
     let mut requests = Vec::new();
@@ -109,6 +129,12 @@ pub async fn execute_batch(
         )),
     };
 
+    progress::emit(ProgressEvent::BatchStart {
+        service: service.to_string(),
+        requests: requests.len(),
+    });
+    let started = std::time::Instant::now();
+
     // Execute batch (handles empty requests gracefully)
     let responses = client.execute(requests, access_token).await?;
 
@@ -142,6 +168,15 @@ pub async fn execute_batch(
     // Add parse errors
     errors.extend(parse_errors);
 
+    span.record("succeeded", results.len());
+    span.record("failed", errors.len());
+    progress::emit(ProgressEvent::BatchEnd {
+        service: service.to_string(),
+        succeeded: results.len(),
+        failed: errors.len(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+
     // Determine overall status
     let status = if errors.is_empty() {
         "success"
@@ -1,10 +1,17 @@
 use std::path::Path;
 use reqwest::Client;
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use crate::error::{WorkspaceError, ApiError};
+use crate::output::progress::TransferProgress;
 
+/// Whether an output path means standard output (`-`)
+pub fn is_stdout(output_path: &Path) -> bool {
+    output_path == Path::new("-")
+}
+
+/// Download a file's content to `output_path`, or to stdout when it is `-`
 pub async fn download_file(
     access_token: &str,
     file_id: &str,
@@ -17,7 +24,7 @@ pub async fn download_file(
         file_id
     );
 
-    let mut response = client
+    let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", access_token))
         .send()
@@ -34,21 +41,15 @@ pub async fn download_file(
         }));
     }
 
-    let mut file = File::create(output_path).await?;
-    let mut total_bytes = 0u64;
-
-    // Stream the response to avoid loading entire file into memory
-    while let Some(chunk) = response.chunk().await? {
-        file.write_all(&chunk).await?;
-        total_bytes += chunk.len() as u64;
+    let progress = TransferProgress::new("download", file_id, response.content_length());
+    if is_stdout(output_path) {
+        write_body(response, &mut tokio::io::stdout(), progress).await
+    } else {
+        write_body(response, &mut File::create(output_path).await?, progress).await
     }
-
-    file.flush().await?;
-
-    Ok(total_bytes)
 }
 
-/// Export Google Docs/Sheets/Slides to a specific format
+/// Export Google Docs/Sheets/Slides to a specific format, to a file or stdout (`-`)
 pub async fn export_file(
     access_token: &str,
     file_id: &str,
@@ -63,7 +64,7 @@ pub async fn export_file(
         urlencoding::encode(mime_type)
     );
 
-    let mut response = client
+    let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", access_token))
         .send()
@@ -80,16 +81,29 @@ pub async fn export_file(
         }));
     }
 
-    let mut file = File::create(output_path).await?;
-    let mut total_bytes = 0u64;
+    let progress = TransferProgress::new("export", file_id, response.content_length());
+    if is_stdout(output_path) {
+        write_body(response, &mut tokio::io::stdout(), progress).await
+    } else {
+        write_body(response, &mut File::create(output_path).await?, progress).await
+    }
+}
 
-    // Stream the response to avoid loading entire file into memory
+/// Stream a response body to a writer without holding it in memory
+async fn write_body<W: AsyncWrite + Unpin>(
+    mut response: reqwest::Response,
+    writer: &mut W,
+    mut progress: TransferProgress,
+) -> Result<u64, WorkspaceError> {
+    let mut total_bytes = 0u64;
     while let Some(chunk) = response.chunk().await? {
-        file.write_all(&chunk).await?;
+        writer.write_all(&chunk).await?;
         total_bytes += chunk.len() as u64;
+        progress.advance(chunk.len() as u64);
     }
 
-    file.flush().await?;
+    writer.flush().await?;
+    progress.finish();
 
     Ok(total_bytes)
 }
@@ -7,14 +7,18 @@ pub mod mkdir;
 pub mod operations;
 pub mod share;
 pub mod watch;
+pub mod tree;
+pub mod sync;
 
 // Re-export commonly used types and functions
 pub use types::{File, FileList, FileMetadata};
 pub use list::{ListParams, list_files, get_file};
-pub use upload::{UploadParams, upload_file};
-pub use download::{download_file, export_file};
+pub use upload::{UploadParams, upload_file, upload_bytes, upload_stream, update_file_content, conversion_target, resume_uploads, ResumedUpload, UploadSession};
+pub use download::{download_file, export_file, is_stdout};
 pub use delete::{delete_file, trash_file, untrash_file, empty_trash};
 pub use mkdir::create_folder;
 pub use operations::{move_file, copy_file, rename_file};
 pub use share::{Permission, PermissionList, list_permissions, share_with_user, share_with_anyone, share_with_domain, remove_permission};
-pub use watch::{Channel, WatchFileParams, WatchChangesParams, StartPageTokenResponse, ChangesResponse, Change, get_start_page_token, watch_changes, watch_file, stop_channel, list_changes};
+pub use watch::{Channel, WatchFileParams, WatchChangesParams, StartPageTokenResponse, ChangesResponse, Change, get_start_page_token, watch_changes, watch_file, stop_channel, list_changes, list_changes_with_fields};
+pub use tree::{upload_tree, download_tree, list_children, export_target, ExportFormats, TreeDownloadOptions, TreeEntry, TreeResult, TreeUploadOptions};
+pub use sync::{sync, ConflictPolicy, DriveSyncState, SyncMode, SyncOptions, SyncResult};
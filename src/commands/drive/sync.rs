@@ -0,0 +1,920 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::client::ApiClient;
+use crate::config::Config;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use crate::utils::glob::GlobFilter;
+use super::delete::trash_file;
+use super::download::download_file;
+use super::mkdir::create_folder;
+use super::operations::copy_file;
+use super::tree::{list_children, modified_millis, sanitize_name, unique_name, walk_local, FOLDER_MIME};
+use super::types::File;
+use super::upload::{md5_file, update_file_content, upload_file, UploadParams};
+use super::watch::{get_start_page_token, list_changes_with_fields, Change};
+
+const NATIVE_PREFIX: &str = "application/vnd.google-apps.";
+const CHANGE_FIELDS: &str = "nextPageToken,newStartPageToken,\
+    changes(fileId,removed,file(id,name,mimeType,parents,md5Checksum,size,modifiedTime,trashed))";
+
+/// Which side's changes a sync applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Local changes go to Drive
+    Push,
+    /// Drive changes come to the local directory
+    Pull,
+    Bidirectional,
+}
+
+impl SyncMode {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "push" => Some(Self::Push),
+            "pull" => Some(Self::Pull),
+            "bidirectional" | "both" => Some(Self::Bidirectional),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Push => "push",
+            Self::Pull => "pull",
+            Self::Bidirectional => "bidirectional",
+        }
+    }
+
+    fn pushes(&self) -> bool {
+        *self != Self::Pull
+    }
+
+    fn pulls(&self) -> bool {
+        *self != Self::Push
+    }
+}
+
+/// What to do when a file changed on both sides since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Leave both versions alone and list the conflict
+    Report,
+    /// The most recently modified version wins
+    Newer,
+    Local,
+    Remote,
+    /// Keep the remote version as a "(conflict ...)" copy on both sides
+    KeepBoth,
+}
+
+impl ConflictPolicy {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "report" => Some(Self::Report),
+            "newer" | "newer-wins" => Some(Self::Newer),
+            "local" => Some(Self::Local),
+            "remote" => Some(Self::Remote),
+            "keep-both" | "keepboth" => Some(Self::KeepBoth),
+            _ => None,
+        }
+    }
+}
+
+pub struct SyncOptions {
+    pub mode: SyncMode,
+    pub conflict: ConflictPolicy,
+    pub filter: GlobFilter,
+    pub dry_run: bool,
+    pub concurrency: usize,
+}
+
+/// Last known state of a remote file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFile {
+    pub id: String,
+    pub md5: Option<String>,
+    pub size: u64,
+    pub modified_time: Option<String>,
+}
+
+impl RemoteFile {
+    fn from_file(file: &File) -> Self {
+        Self {
+            id: file.id.clone(),
+            md5: file.md5_checksum.clone(),
+            size: file.size.as_deref().and_then(|s| s.parse().ok()).unwrap_or(0),
+            modified_time: file.modified_time.clone(),
+        }
+    }
+}
+
+/// The content both sides had when a path was last synced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedFile {
+    pub md5: Option<String>,
+    pub size: u64,
+    /// Local modification time, epoch milliseconds
+    pub local_modified: i64,
+    pub remote_modified: Option<String>,
+}
+
+/// Sync state of one local directory / Drive folder pair
+///
+/// Stored in `drive_sync/` in the config directory. `remote` is the last
+/// known remote tree, kept current from the changes feed; `synced` is the
+/// base both sides agreed on, which changes are detected against.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveSyncState {
+    pub folder_id: String,
+    pub local_dir: String,
+    /// Changes feed position the remote tree is current to
+    pub page_token: Option<String>,
+    /// Folder path to ID; "" is the synced folder itself
+    #[serde(default)]
+    pub folders: BTreeMap<String, String>,
+    #[serde(default)]
+    pub remote: BTreeMap<String, RemoteFile>,
+    #[serde(default)]
+    pub synced: BTreeMap<String, SyncedFile>,
+}
+
+impl DriveSyncState {
+    pub fn path(local_dir: &Path, folder_id: &str) -> Option<PathBuf> {
+        let key: String = Sha256::digest(format!("{}\n{}", folder_id, local_dir.display()).as_bytes())
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Config::config_dir().map(|dir| dir.join("drive_sync").join(format!("{}.json", key)))
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        std::fs::read_to_string(path).ok().and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Write to a temporary file and rename so an interrupted run keeps the old state
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+/// One transfer or deletion made (or planned) by a sync
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAction {
+    pub path: String,
+    /// upload, update, download, deleteLocal, deleteRemote or keepBoth
+    pub action: &'static str,
+    /// done, planned or failed
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub path: String,
+    /// bothModified or bothAdded
+    pub reason: &'static str,
+    /// The action taken, or None when left for the user
+    pub resolution: Option<&'static str>,
+}
+
+/// Result of `drive sync`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub local_dir: String,
+    pub folder_id: String,
+    pub mode: &'static str,
+    pub dry_run: bool,
+    /// The remote tree was listed in full (first sync, or folders were renamed)
+    pub full_scan: bool,
+    /// Remote changes affecting the folder since the last sync
+    pub remote_changes: usize,
+    pub unchanged: usize,
+    /// Changes on the side this mode doesn't apply, left for a later sync
+    pub pending: usize,
+    pub actions: Vec<SyncAction>,
+    pub conflicts: Vec<SyncConflict>,
+    /// Google Docs, Sheets, etc., which have no file content to sync, and
+    /// local entries that could not be read
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+    pub failed: usize,
+}
+
+/// A local file with its content hash
+#[derive(Debug, Clone)]
+pub struct LocalEntry {
+    pub full_path: PathBuf,
+    pub size: u64,
+    pub modified: i64,
+    pub md5: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Unchanged,
+    Changed,
+    Added,
+    Deleted,
+    Absent,
+}
+
+/// What a sync does with one path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Upload,
+    Update,
+    Download,
+    DeleteLocal,
+    DeleteRemote,
+    KeepBoth,
+    /// Same content on both sides: record it as synced
+    Adopt,
+    /// Gone on both sides: drop it from the state
+    Forget,
+    Conflict(&'static str),
+}
+
+impl Step {
+    fn name(&self) -> &'static str {
+        match self {
+            Step::Upload => "upload",
+            Step::Update => "update",
+            Step::Download => "download",
+            Step::DeleteLocal => "deleteLocal",
+            Step::DeleteRemote => "deleteRemote",
+            Step::KeepBoth => "keepBoth",
+            Step::Adopt => "adopt",
+            Step::Forget => "forget",
+            Step::Conflict(_) => "conflict",
+        }
+    }
+
+    fn allowed(&self, mode: SyncMode) -> bool {
+        match self {
+            Step::Upload | Step::Update | Step::DeleteRemote => mode.pushes(),
+            Step::Download | Step::DeleteLocal => mode.pulls(),
+            Step::KeepBoth => mode == SyncMode::Bidirectional,
+            Step::Adopt | Step::Forget | Step::Conflict(_) => true,
+        }
+    }
+}
+
+/// Sync a local directory with a Drive folder, transferring only what changed
+pub async fn sync(
+    client: &ApiClient,
+    access_token: &str,
+    local_dir: &Path,
+    folder_id: &str,
+    options: SyncOptions,
+) -> Result<SyncResult> {
+    std::fs::create_dir_all(local_dir)?;
+    let local_dir = local_dir.canonicalize()?;
+    let state_path = DriveSyncState::path(&local_dir, folder_id)
+        .ok_or_else(|| WorkspaceError::Config("No config directory to keep the sync state in".to_string()))?;
+    let mut state = DriveSyncState::load(&state_path)
+        .filter(|state| state.page_token.is_some())
+        .unwrap_or_else(|| DriveSyncState {
+            folder_id: folder_id.to_string(),
+            local_dir: local_dir.display().to_string(),
+            ..Default::default()
+        });
+
+    // Remote deltas from the changes feed; a full listing when there's no usable baseline
+    let mut skipped = Vec::new();
+    let mut remote_changes = 0;
+    let mut next_token = None;
+    if let Some(token) = state.page_token.clone() {
+        let (changes, token) = fetch_changes(client, &token).await?;
+        if let Some(count) = apply_changes(&mut state, &changes) {
+            remote_changes = count;
+            next_token = Some(token);
+        }
+    }
+    let full_scan = next_token.is_none();
+    if full_scan {
+        // Take the token first so changes made during the listing show up next time
+        next_token = Some(get_start_page_token(client).await?.start_page_token);
+        scan_remote(client, &mut state, folder_id, &mut skipped).await?;
+    }
+
+    let mut local_files = Vec::new();
+    let mut unreadable = Vec::new();
+    walk_local(&local_dir, "", &options.filter, &mut local_files, &mut unreadable)?;
+    let mut local = BTreeMap::new();
+    for file in local_files {
+        // Leftovers of an interrupted download are never synced
+        if is_partial(&file.path) {
+            continue;
+        }
+        // Hash only files whose size or mtime moved since the last sync
+        let md5 = match state.synced.get(&file.path) {
+            Some(synced) if synced.size == file.size && synced.local_modified == file.modified => synced.md5.clone(),
+            _ => match md5_file(&file.full_path) {
+                Ok(md5) => Some(md5),
+                Err(_) => {
+                    unreadable.push(file.path);
+                    continue;
+                }
+            },
+        };
+        local.insert(file.path, LocalEntry { full_path: file.full_path, size: file.size, modified: file.modified, md5 });
+    }
+    keep_unreadable(&mut local, &state, &local_dir, &unreadable);
+    skipped.extend(unreadable);
+    skipped.sort();
+
+    let steps = plan(&local, &state, &options.filter);
+    let mut result = SyncResult {
+        local_dir: local_dir.display().to_string(),
+        folder_id: folder_id.to_string(),
+        mode: options.mode.name(),
+        dry_run: options.dry_run,
+        full_scan,
+        remote_changes,
+        unchanged: local.len().saturating_sub(steps.iter().filter(|(path, _)| local.contains_key(path)).count()),
+        pending: 0,
+        actions: Vec::new(),
+        conflicts: Vec::new(),
+        skipped,
+        failed: 0,
+    };
+
+    let mut work = Vec::new();
+    for (path, step) in steps {
+        let step = match step {
+            Step::Conflict(reason) => {
+                let resolution = resolve(options.conflict, &local[&path], &state.remote[&path]).filter(|s| s.allowed(options.mode));
+                result.conflicts.push(SyncConflict { path: path.clone(), reason, resolution: resolution.map(|s| s.name()) });
+                match resolution {
+                    Some(step) => step,
+                    None => continue,
+                }
+            }
+            step if !step.allowed(options.mode) => {
+                result.pending += 1;
+                continue;
+            }
+            step => step,
+        };
+        work.push((path, step));
+    }
+
+    if options.dry_run {
+        result.actions = work.iter()
+            .filter(|(_, step)| !matches!(step, Step::Adopt | Step::Forget))
+            .map(|(path, step)| SyncAction { path: path.clone(), action: step.name(), status: "planned", error: None })
+            .collect();
+        return Ok(result);
+    }
+
+    // Folders for new uploads are created up front, parents first
+    let mut needed: Vec<String> = work.iter()
+        .filter(|(_, step)| *step == Step::Upload)
+        .filter_map(|(path, _)| path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+        .collect();
+    needed.sort();
+    needed.dedup();
+    for dir in needed {
+        ensure_folder(client, &mut state, &dir).await?;
+    }
+
+    let total = work.len();
+    let state_ref = &state;
+    let local_ref = &local;
+    let local_dir_ref = &local_dir;
+    let mut outcomes = stream::iter(work)
+        .map(|(path, step)| async move {
+            let outcome = execute(client, access_token, state_ref, local_ref, local_dir_ref, &path, step).await;
+            (path, step, outcome)
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut updates = Vec::new();
+    let mut current = 0;
+    while let Some((path, step, outcome)) = outcomes.next().await {
+        current += 1;
+        let mut action = SyncAction { path: path.clone(), action: step.name(), status: "done", error: None };
+        match outcome {
+            Ok(changes) => updates.extend(changes),
+            Err(e) => {
+                action.status = "failed";
+                action.error = Some(e.to_string());
+                result.failed += 1;
+            }
+        }
+        progress::emit(ProgressEvent::Item {
+            operation: "sync",
+            current,
+            total,
+            name: path,
+            status: action.status.to_string(),
+        });
+        if !matches!(step, Step::Adopt | Step::Forget) {
+            result.actions.push(action);
+        }
+    }
+    drop(outcomes);
+
+    for update in updates {
+        match update.remote {
+            Some(remote) => state.remote.insert(update.path.clone(), remote),
+            None => state.remote.remove(&update.path),
+        };
+        match update.synced {
+            Some(synced) => state.synced.insert(update.path, synced),
+            None => state.synced.remove(&update.path),
+        };
+    }
+    state.page_token = next_token;
+    state.save(&state_path)?;
+    // Uploads bypass the client, so cached listings are dropped here
+    client.invalidate_cache();
+
+    result.actions.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(result)
+}
+
+/// All changes since `token`, and the token to continue from next time
+async fn fetch_changes(client: &ApiClient, token: &str) -> Result<(Vec<Change>, String)> {
+    let mut changes = Vec::new();
+    let mut page_token = token.to_string();
+    loop {
+        let page = list_changes_with_fields(client, &page_token, CHANGE_FIELDS).await?;
+        changes.extend(page.changes);
+        match (page.next_page_token, page.new_start_page_token) {
+            (Some(next), _) => page_token = next,
+            (None, Some(new_start)) => return Ok((changes, new_start)),
+            (None, None) => return Ok((changes, page_token)),
+        }
+    }
+}
+
+/// Apply remote changes to the known remote tree
+///
+/// Returns how many changes touched the synced folder, or None when folders
+/// were renamed, moved or deleted and the tree has to be listed again.
+pub fn apply_changes(state: &mut DriveSyncState, changes: &[Change]) -> Option<usize> {
+    let mut folder_paths: HashMap<String, String> = state.folders.iter().map(|(path, id)| (id.clone(), path.clone())).collect();
+    let file_paths: HashMap<String, String> = state.remote.iter().map(|(path, file)| (file.id.clone(), path.clone())).collect();
+    let is_gone = |change: &Change| change.removed == Some(true) || change.file.as_ref().is_some_and(|f| f.trashed == Some(true));
+    let path_in = |folder_paths: &HashMap<String, String>, file: &File| {
+        file.parents.iter()
+            .find_map(|parent| folder_paths.get(parent))
+            .map(|dir| join(dir, &sanitize_name(&file.name)))
+    };
+
+    let mut relevant = 0;
+    let (mut folders, files): (Vec<&Change>, Vec<&Change>) = changes.iter().partition(|change| {
+        let id = change.file_id.as_deref().unwrap_or_default();
+        folder_paths.contains_key(id) || change.file.as_ref().is_some_and(|f| f.mime_type == FOLDER_MIME)
+    });
+
+    // New folders may be listed before their parent, so place them in passes
+    loop {
+        let mut placed = false;
+        let mut unplaced = Vec::new();
+        for change in folders {
+            let id = change.file_id.as_deref().unwrap_or_default();
+            let new_path = change.file.as_ref().filter(|_| !is_gone(change)).and_then(|f| path_in(&folder_paths, f));
+            match (folder_paths.get(id), new_path) {
+                (Some(old), Some(new)) if *old == new => {}
+                // Renamed, moved or deleted folder: paths below it changed
+                (Some(_), _) => return None,
+                (None, Some(new)) => {
+                    state.folders.insert(new.clone(), id.to_string());
+                    folder_paths.insert(id.to_string(), new);
+                    relevant += 1;
+                    placed = true;
+                }
+                (None, None) => unplaced.push(change),
+            }
+        }
+        folders = unplaced;
+        if !placed || folders.is_empty() {
+            break;
+        }
+    }
+
+    for change in files {
+        let Some(id) = change.file_id.as_deref() else { continue };
+        if change.file.as_ref().is_some_and(|f| f.mime_type.starts_with(NATIVE_PREFIX)) {
+            continue;
+        }
+        let old_path = file_paths.get(id);
+        let new_path = change.file.as_ref().filter(|_| !is_gone(change)).and_then(|f| path_in(&folder_paths, f));
+
+        if let Some(old) = old_path.filter(|old| Some(*old) != new_path.as_ref()) {
+            state.remote.remove(old);
+            relevant += 1;
+        }
+        if let (Some(new), Some(file)) = (new_path, change.file.as_ref()) {
+            let remote = RemoteFile::from_file(file);
+            if state.remote.get(&new) != Some(&remote) {
+                state.remote.insert(new, remote);
+                relevant += 1;
+            }
+        }
+    }
+
+    Some(relevant)
+}
+
+/// List the whole remote tree into the state
+async fn scan_remote(client: &ApiClient, state: &mut DriveSyncState, folder_id: &str, skipped: &mut Vec<String>) -> Result<()> {
+    state.folders = BTreeMap::from([(String::new(), folder_id.to_string())]);
+    state.remote.clear();
+
+    let mut queue = vec![(folder_id.to_string(), String::new())];
+    while let Some((id, prefix)) = queue.pop() {
+        let mut taken = HashSet::new();
+        for child in list_children(client, &id).await? {
+            let path = join(&prefix, &unique_name(&sanitize_name(&child.name), &mut taken));
+            if child.mime_type == FOLDER_MIME {
+                state.folders.insert(path.clone(), child.id.clone());
+                queue.push((child.id, path));
+            } else if child.mime_type.starts_with(NATIVE_PREFIX) {
+                skipped.push(path);
+            } else {
+                state.remote.insert(path, RemoteFile::from_file(&child));
+            }
+        }
+    }
+    skipped.sort();
+    Ok(())
+}
+
+fn local_side(local: Option<&LocalEntry>, synced: Option<&SyncedFile>) -> Side {
+    match (local, synced) {
+        (Some(local), Some(synced)) => {
+            let same = match (&local.md5, &synced.md5) {
+                (Some(a), Some(b)) => a == b,
+                _ => local.size == synced.size && local.modified == synced.local_modified,
+            };
+            if same { Side::Unchanged } else { Side::Changed }
+        }
+        (Some(_), None) => Side::Added,
+        (None, Some(_)) => Side::Deleted,
+        (None, None) => Side::Absent,
+    }
+}
+
+fn remote_side(remote: Option<&RemoteFile>, synced: Option<&SyncedFile>) -> Side {
+    match (remote, synced) {
+        (Some(remote), Some(synced)) => {
+            let same = match (&remote.md5, &synced.md5) {
+                (Some(a), Some(b)) => a == b,
+                _ => remote.size == synced.size && remote.modified_time == synced.remote_modified,
+            };
+            if same { Side::Unchanged } else { Side::Changed }
+        }
+        (Some(_), None) => Side::Added,
+        (None, Some(_)) => Side::Deleted,
+        (None, None) => Side::Absent,
+    }
+}
+
+/// Decide what to do with every path that differs from the last sync
+///
+/// A modification beats a deletion on the other side; changes on both
+/// sides are conflicts unless the content ended up identical.
+pub fn plan(local: &BTreeMap<String, LocalEntry>, state: &DriveSyncState, filter: &GlobFilter) -> Vec<(String, Step)> {
+    let paths: std::collections::BTreeSet<&String> = local.keys()
+        .chain(state.remote.keys().filter(|path| filter.matches_nested(path)))
+        .chain(state.synced.keys().filter(|path| filter.matches_nested(path)))
+        .collect();
+
+    let mut steps = Vec::new();
+    for path in paths {
+        let (l, r, s) = (local.get(path), state.remote.get(path), state.synced.get(path));
+        let step = match (local_side(l, s), remote_side(r, s)) {
+            (Side::Unchanged, Side::Unchanged) | (Side::Absent, Side::Absent) => continue,
+            (Side::Deleted, Side::Deleted) => Step::Forget,
+            (Side::Changed, Side::Unchanged) => Step::Update,
+            (Side::Added, Side::Absent) | (Side::Changed, Side::Deleted) => Step::Upload,
+            (Side::Unchanged, Side::Deleted) => Step::DeleteLocal,
+            (Side::Deleted, Side::Unchanged) => Step::DeleteRemote,
+            (Side::Unchanged | Side::Absent | Side::Deleted, Side::Changed | Side::Added) => Step::Download,
+            (local_side @ (Side::Changed | Side::Added), Side::Changed | Side::Added) => {
+                let same = l.and_then(|l| l.md5.as_ref()).is_some_and(|md5| r.and_then(|r| r.md5.as_ref()) == Some(md5));
+                match (same, local_side) {
+                    (true, _) => Step::Adopt,
+                    (false, Side::Added) => Step::Conflict("bothAdded"),
+                    (false, _) => Step::Conflict("bothModified"),
+                }
+            }
+            _ => continue,
+        };
+        steps.push((path.clone(), step));
+    }
+    steps
+}
+
+/// The step a conflict policy picks, or None to leave the conflict
+fn resolve(policy: ConflictPolicy, local: &LocalEntry, remote: &RemoteFile) -> Option<Step> {
+    match policy {
+        ConflictPolicy::Report => None,
+        ConflictPolicy::Local => Some(Step::Update),
+        ConflictPolicy::Remote => Some(Step::Download),
+        ConflictPolicy::KeepBoth => Some(Step::KeepBoth),
+        ConflictPolicy::Newer => {
+            let remote_modified = remote.modified_time.as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.timestamp_millis())
+                .unwrap_or(0);
+            Some(if local.modified >= remote_modified { Step::Update } else { Step::Download })
+        }
+    }
+}
+
+/// New remote and synced entries for a path after a step
+struct StateUpdate {
+    path: String,
+    remote: Option<RemoteFile>,
+    synced: Option<SyncedFile>,
+}
+
+async fn execute(
+    client: &ApiClient,
+    access_token: &str,
+    state: &DriveSyncState,
+    local: &BTreeMap<String, LocalEntry>,
+    local_dir: &Path,
+    path: &str,
+    step: Step,
+) -> Result<Vec<StateUpdate>> {
+    let target = local_dir.join(path);
+    let gone = || vec![StateUpdate { path: path.to_string(), remote: None, synced: None }];
+
+    match step {
+        Step::Upload | Step::Update => {
+            let entry = &local[path];
+            let file = match state.remote.get(path).filter(|_| step == Step::Update) {
+                Some(remote) => update_file_content(access_token, &remote.id, &entry.full_path, None).await?,
+                None => {
+                    let (dir, _) = path.rsplit_once('/').unwrap_or(("", path));
+                    let params = UploadParams {
+                        file_path: entry.full_path.to_string_lossy().into_owned(),
+                        name: None,
+                        parent_id: state.folders.get(dir).cloned(),
+                        mime_type: None,
+                        convert: false,
+                    };
+                    upload_file(access_token, params).await?
+                }
+            };
+            Ok(vec![uploaded(path, entry, &file.id)])
+        }
+        Step::Download => {
+            let remote = &state.remote[path];
+            Ok(vec![download(access_token, &remote.id, remote, path, &target).await?])
+        }
+        Step::DeleteLocal => {
+            match std::fs::remove_file(&target) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+            Ok(gone())
+        }
+        Step::DeleteRemote => {
+            trash_file(client, &state.remote[path].id).await?;
+            Ok(gone())
+        }
+        Step::KeepBoth => {
+            // Copy the remote version server-side, bring the copy down, then push local
+            let remote = &state.remote[path];
+            let entry = &local[path];
+            let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+            let copy_name = conflict_name(name);
+            let copy_path = join(dir, &copy_name);
+            let copy = copy_file(client, &remote.id, Some(&copy_name), None).await?;
+            let copied = RemoteFile { id: copy.id.clone(), ..remote.clone() };
+            let copy_update = download(access_token, &copy.id, &copied, &copy_path, &local_dir.join(&copy_path)).await?;
+            let file = update_file_content(access_token, &remote.id, &entry.full_path, None).await?;
+            Ok(vec![copy_update, uploaded(path, entry, &file.id)])
+        }
+        Step::Adopt => {
+            let entry = &local[path];
+            let remote = &state.remote[path];
+            Ok(vec![StateUpdate {
+                path: path.to_string(),
+                remote: Some(remote.clone()),
+                synced: Some(SyncedFile {
+                    md5: entry.md5.clone(),
+                    size: entry.size,
+                    local_modified: entry.modified,
+                    remote_modified: remote.modified_time.clone(),
+                }),
+            }])
+        }
+        Step::Forget | Step::Conflict(_) => Ok(gone()),
+    }
+}
+
+/// Treat synced files that could not be read this time as unchanged
+///
+/// Without this, an unreadable file (or every file below an unreadable
+/// directory) would look deleted locally and be trashed on Drive.
+fn keep_unreadable(local: &mut BTreeMap<String, LocalEntry>, state: &DriveSyncState, local_dir: &Path, unreadable: &[String]) {
+    for (path, synced) in &state.synced {
+        let hidden = unreadable.iter().any(|u| path == u || path.strip_prefix(u.as_str()).is_some_and(|rest| rest.starts_with('/')));
+        if hidden && !local.contains_key(path) {
+            local.insert(path.clone(), LocalEntry {
+                full_path: local_dir.join(path),
+                size: synced.size,
+                modified: synced.local_modified,
+                md5: synced.md5.clone(),
+            });
+        }
+    }
+}
+
+/// State after local content was uploaded to a file
+fn uploaded(path: &str, entry: &LocalEntry, file_id: &str) -> StateUpdate {
+    StateUpdate {
+        path: path.to_string(),
+        remote: Some(RemoteFile { id: file_id.to_string(), md5: entry.md5.clone(), size: entry.size, modified_time: None }),
+        synced: Some(SyncedFile { md5: entry.md5.clone(), size: entry.size, local_modified: entry.modified, remote_modified: None }),
+    }
+}
+
+/// Download a remote file over the local working copy
+///
+/// The content goes to a temporary file next to `target` that replaces it
+/// only once complete, so a failed download never leaves a truncated working
+/// copy that the next run would take for a local change.
+async fn download(access_token: &str, file_id: &str, remote: &RemoteFile, path: &str, target: &Path) -> Result<StateUpdate> {
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let partial = partial_path(target);
+    if let Err(e) = download_file(access_token, file_id, &partial).await {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, target)?;
+    let metadata = std::fs::metadata(target)?;
+    Ok(StateUpdate {
+        path: path.to_string(),
+        remote: Some(remote.clone()),
+        synced: Some(SyncedFile {
+            md5: remote.md5.clone(),
+            size: metadata.len(),
+            local_modified: modified_millis(&metadata),
+            remote_modified: remote.modified_time.clone(),
+        }),
+    })
+}
+
+/// `dir/report.pdf` -> `dir/.report.pdf.wscli-partial`
+fn partial_path(target: &Path) -> PathBuf {
+    let name = target.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    target.with_file_name(format!(".{}.wscli-partial", name))
+}
+
+fn is_partial(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.starts_with('.') && name.ends_with(".wscli-partial")
+}
+
+/// Find or create the remote folder for a directory path
+async fn ensure_folder(client: &ApiClient, state: &mut DriveSyncState, dir: &str) -> Result<String> {
+    if let Some(id) = state.folders.get(dir) {
+        return Ok(id.clone());
+    }
+    let (parent, name) = dir.rsplit_once('/').unwrap_or(("", dir));
+    let parent_id = Box::pin(ensure_folder(client, state, parent)).await?;
+    let folder = create_folder(client, name, Some(&parent_id)).await?;
+    state.folders.insert(dir.to_string(), folder.id.clone());
+    Ok(folder.id)
+}
+
+/// `report.pdf` -> `report (conflict 2026-10-18 1430).pdf`
+fn conflict_name(name: &str) -> String {
+    let stamp = chrono::Local::now().format("%Y-%m-%d %H%M");
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => format!("{} (conflict {}){}", &name[..dot], stamp, &name[dot..]),
+        _ => format!("{} (conflict {})", name, stamp),
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() { name.to_string() } else { format!("{}/{}", dir, name) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(id: &str, md5: &str) -> RemoteFile {
+        RemoteFile { id: id.to_string(), md5: Some(md5.to_string()), size: 1, modified_time: None }
+    }
+
+    fn synced(md5: &str) -> SyncedFile {
+        SyncedFile { md5: Some(md5.to_string()), size: 1, local_modified: 0, remote_modified: None }
+    }
+
+    fn local(md5: &str) -> LocalEntry {
+        LocalEntry { full_path: PathBuf::new(), size: 1, modified: 0, md5: Some(md5.to_string()) }
+    }
+
+    #[test]
+    fn test_partial_path() {
+        let partial = partial_path(Path::new("/sync/docs/report.pdf"));
+        assert_eq!(partial, Path::new("/sync/docs/.report.pdf.wscli-partial"));
+        assert!(is_partial("docs/.report.pdf.wscli-partial"));
+        assert!(!is_partial("docs/report.pdf"));
+        assert!(!is_partial("docs/notes.wscli-partial"));
+    }
+
+    #[test]
+    fn test_keep_unreadable() {
+        let mut state = DriveSyncState::default();
+        for path in ["a.txt", "locked/b.txt", "lockedness.txt"] {
+            state.synced.insert(path.to_string(), SyncedFile { md5: Some("m".to_string()), size: 1, local_modified: 1, remote_modified: None });
+        }
+        let mut local = BTreeMap::new();
+        keep_unreadable(&mut local, &state, Path::new("/sync"), &["locked".to_string()]);
+
+        let kept: Vec<_> = local.keys().map(String::as_str).collect();
+        assert_eq!(kept, vec!["locked/b.txt"]);
+        assert_eq!(local["locked/b.txt"].md5.as_deref(), Some("m"));
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut state = DriveSyncState::default();
+        for (path, md5) in [("same", "a"), ("edited", "a"), ("gone-local", "a"), ("gone-remote", "a"), ("both", "a"), ("both-same", "a")] {
+            state.synced.insert(path.to_string(), synced(md5));
+        }
+        for (path, md5) in [("same", "a"), ("edited", "a"), ("gone-local", "a"), ("both", "c"), ("both-same", "b"), ("new-remote", "n")] {
+            state.remote.insert(path.to_string(), remote(path, md5));
+        }
+        let local: BTreeMap<String, LocalEntry> = [("same", "a"), ("edited", "b"), ("gone-remote", "a"), ("both", "b"), ("both-same", "b"), ("new-local", "x")]
+            .into_iter()
+            .map(|(path, md5)| (path.to_string(), local(md5)))
+            .collect();
+
+        let steps: BTreeMap<String, Step> = plan(&local, &state, &GlobFilter::default()).into_iter().collect();
+        assert_eq!(steps.get("same"), None);
+        assert_eq!(steps["edited"], Step::Update);
+        assert_eq!(steps["gone-local"], Step::DeleteRemote);
+        assert_eq!(steps["gone-remote"], Step::DeleteLocal);
+        assert_eq!(steps["both"], Step::Conflict("bothModified"));
+        assert_eq!(steps["both-same"], Step::Adopt);
+        assert_eq!(steps["new-remote"], Step::Download);
+        assert_eq!(steps["new-local"], Step::Upload);
+        assert!(!Step::Download.allowed(SyncMode::Push) && Step::Upload.allowed(SyncMode::Push));
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let mut state = DriveSyncState::default();
+        state.folders.insert(String::new(), "root".to_string());
+        state.folders.insert("docs".to_string(), "f1".to_string());
+        state.remote.insert("docs/a.txt".to_string(), remote("a", "1"));
+        state.remote.insert("b.txt".to_string(), remote("b", "1"));
+
+        let change = |id: &str, file: serde_json::Value| -> Change {
+            serde_json::from_value(serde_json::json!({ "fileId": id, "removed": file.is_null(), "file": file })).unwrap()
+        };
+        let changes = vec![
+            // A file in a folder created in the same batch, listed first
+            change("c", serde_json::json!({"id": "c", "name": "c.txt", "mimeType": "text/plain", "parents": ["f2"], "md5Checksum": "3", "size": "5"})),
+            change("f2", serde_json::json!({"id": "f2", "name": "new", "mimeType": FOLDER_MIME, "parents": ["f1"]})),
+            change("a", serde_json::json!({"id": "a", "name": "a.txt", "mimeType": "text/plain", "parents": ["f1"], "md5Checksum": "2", "size": "1"})),
+            change("b", serde_json::Value::Null),
+            change("x", serde_json::json!({"id": "x", "name": "elsewhere.txt", "mimeType": "text/plain", "parents": ["other"]})),
+        ];
+
+        assert_eq!(apply_changes(&mut state, &changes), Some(4));
+        assert_eq!(state.remote["docs/a.txt"].md5.as_deref(), Some("2"));
+        assert_eq!(state.remote["docs/new/c.txt"].size, 5);
+        assert!(!state.remote.contains_key("b.txt"));
+
+        // Renaming a known folder needs a full listing
+        let rename = vec![change("f1", serde_json::json!({"id": "f1", "name": "renamed", "mimeType": FOLDER_MIME, "parents": ["root"]}))];
+        assert_eq!(apply_changes(&mut state, &rename), None);
+    }
+}
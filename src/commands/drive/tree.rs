@@ -0,0 +1,570 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use crate::utils::glob::GlobFilter;
+use super::download::{download_file, export_file};
+use super::list::get_file;
+use super::mkdir::create_folder;
+use super::types::{File, FileList};
+use super::upload::{upload_file, UploadParams};
+
+pub const FOLDER_MIME: &str = "application/vnd.google-apps.folder";
+const NATIVE_PREFIX: &str = "application/vnd.google-apps.";
+
+/// Formats Google-native files are exported to when downloading
+#[derive(Debug, Clone)]
+pub struct ExportFormats {
+    pub document: String,
+    pub spreadsheet: String,
+    pub presentation: String,
+    pub drawing: String,
+}
+
+impl Default for ExportFormats {
+    fn default() -> Self {
+        Self {
+            document: "docx".to_string(),
+            spreadsheet: "xlsx".to_string(),
+            presentation: "pptx".to_string(),
+            drawing: "png".to_string(),
+        }
+    }
+}
+
+impl ExportFormats {
+    /// Override defaults with `kind=format` specs, e.g. `docs=pdf`, `sheets=csv`
+    pub fn parse(specs: &[String]) -> Result<Self> {
+        let mut formats = Self::default();
+        for spec in specs {
+            let (kind, format) = spec.split_once('=').ok_or_else(|| WorkspaceError::InvalidInput(format!(
+                "Invalid export format '{}': use KIND=FORMAT, e.g. docs=pdf", spec
+            )))?;
+            let format = format.trim().to_lowercase();
+            let (slot, kind) = match kind.trim().to_lowercase().as_str() {
+                "doc" | "docs" | "document" => (&mut formats.document, "document"),
+                "sheet" | "sheets" | "spreadsheet" => (&mut formats.spreadsheet, "spreadsheet"),
+                "slide" | "slides" | "presentation" => (&mut formats.presentation, "presentation"),
+                "drawing" | "drawings" => (&mut formats.drawing, "drawing"),
+                other => return Err(WorkspaceError::InvalidInput(format!(
+                    "Unknown export kind '{}': use docs, sheets, slides or drawing", other
+                ))),
+            };
+            if export_mime(kind, &format).is_none() {
+                return Err(WorkspaceError::InvalidInput(format!("Can't export a {} as '{}'", kind, format)));
+            }
+            *slot = format;
+        }
+        Ok(formats)
+    }
+
+    /// Export MIME type and file extension for a Google-native file
+    ///
+    /// None for types that can't be exported (forms, sites, shortcuts, ...).
+    pub fn target(&self, mime_type: &str) -> Option<(&'static str, String)> {
+        let kind = mime_type.strip_prefix(NATIVE_PREFIX)?;
+        let format = match kind {
+            "document" => &self.document,
+            "spreadsheet" => &self.spreadsheet,
+            "presentation" => &self.presentation,
+            "drawing" => &self.drawing,
+            _ => return None,
+        };
+        export_mime(kind, format).map(|mime| (mime, format.clone()))
+    }
+}
+
+/// Export MIME type and extension for one Google-native file, in `format`
+/// or the default for its kind
+pub fn export_target(mime_type: &str, format: Option<&str>) -> Result<(&'static str, String)> {
+    let not_exportable = || WorkspaceError::InvalidInput(format!("{} files can't be downloaded or exported", mime_type));
+    let Some(format) = format else {
+        return ExportFormats::default().target(mime_type).ok_or_else(not_exportable);
+    };
+    let kind = mime_type.strip_prefix(NATIVE_PREFIX).ok_or_else(not_exportable)?;
+    let format = format.trim().to_lowercase();
+    match export_mime(kind, &format) {
+        Some(mime) => Ok((mime, format)),
+        None => Err(WorkspaceError::InvalidInput(format!("Can't export a {} as '{}'", kind, format))),
+    }
+}
+
+fn export_mime(kind: &str, format: &str) -> Option<&'static str> {
+    Some(match (kind, format) {
+        ("document", "docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        ("document", "odt") => "application/vnd.oasis.opendocument.text",
+        ("document", "rtf") => "application/rtf",
+        ("document", "txt") => "text/plain",
+        ("document", "md") => "text/markdown",
+        ("document", "html") => "text/html",
+        ("document", "epub") => "application/epub+zip",
+        ("spreadsheet", "xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ("spreadsheet", "ods") => "application/vnd.oasis.opendocument.spreadsheet",
+        ("spreadsheet", "csv") => "text/csv",
+        ("spreadsheet", "tsv") => "text/tab-separated-values",
+        ("presentation", "pptx") => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        ("presentation", "odp") => "application/vnd.oasis.opendocument.presentation",
+        ("presentation", "txt") => "text/plain",
+        ("drawing", "png") => "image/png",
+        ("drawing", "jpg") => "image/jpeg",
+        ("drawing", "svg") => "image/svg+xml",
+        (_, "pdf") => "application/pdf",
+        _ => return None,
+    })
+}
+
+/// One file or folder of a recursive transfer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeEntry {
+    /// Path relative to the transferred directory
+    pub path: String,
+    /// file or folder
+    pub kind: &'static str,
+    /// uploaded, downloaded, exported, created, skipped or failed
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TreeEntry {
+    fn folder(path: &str, id: Option<String>, status: &'static str) -> Self {
+        Self { path: path.to_string(), kind: "folder", status, id, mime_type: None, size: None, error: None }
+    }
+
+    fn file(path: &str, status: &'static str) -> Self {
+        Self { path: path.to_string(), kind: "file", status, id: None, mime_type: None, size: None, error: None }
+    }
+
+    fn failed(mut self, error: impl ToString) -> Self {
+        self.status = "failed";
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// Manifest of a recursive upload or download
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeResult {
+    pub local_dir: String,
+    pub folder_id: String,
+    pub folders: usize,
+    pub files: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub bytes: u64,
+    pub entries: Vec<TreeEntry>,
+}
+
+impl TreeResult {
+    fn new(local_dir: &Path, folder_id: &str, mut entries: Vec<TreeEntry>) -> Self {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let done = |kind: &str| entries.iter()
+            .filter(|e| e.kind == kind && e.status != "failed" && e.status != "skipped")
+            .count();
+        Self {
+            local_dir: local_dir.display().to_string(),
+            folder_id: folder_id.to_string(),
+            folders: done("folder"),
+            files: done("file"),
+            skipped: entries.iter().filter(|e| e.status == "skipped").count(),
+            failed: entries.iter().filter(|e| e.status == "failed").count(),
+            bytes: entries.iter().filter(|e| e.kind == "file" && e.status != "failed").filter_map(|e| e.size).sum(),
+            entries,
+        }
+    }
+}
+
+pub struct TreeUploadOptions {
+    pub parent_id: Option<String>,
+    /// Name of the new Drive folder (defaults to the directory name)
+    pub name: Option<String>,
+    pub filter: GlobFilter,
+    pub concurrency: usize,
+    /// Convert Office, CSV, Markdown and HTML files to Google-native files
+    pub convert: bool,
+}
+
+pub struct TreeDownloadOptions {
+    pub filter: GlobFilter,
+    pub formats: ExportFormats,
+    pub concurrency: usize,
+}
+
+/// A local file selected for transfer
+pub(super) struct LocalFile {
+    /// Path relative to the walked directory, `/`-separated
+    pub path: String,
+    pub full_path: PathBuf,
+    pub size: u64,
+    /// Modification time, epoch milliseconds
+    pub modified: i64,
+}
+
+/// Mirror a local directory into a new Drive folder
+///
+/// Folders are created first (only those leading to a selected file), then
+/// files are uploaded concurrently. Failures are recorded per entry.
+pub async fn upload_tree(
+    client: &ApiClient,
+    access_token: &str,
+    dir: &Path,
+    options: TreeUploadOptions,
+) -> Result<TreeResult> {
+    if !dir.is_dir() {
+        return Err(WorkspaceError::InvalidInput(format!("{} is not a directory", dir.display())));
+    }
+    let mut files = Vec::new();
+    let mut unreadable = Vec::new();
+    walk_local(dir, "", &options.filter, &mut files, &mut unreadable)?;
+
+    let root_name = match options.name {
+        Some(name) => name,
+        None => dir.canonicalize()?
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("upload")
+            .to_string(),
+    };
+    let root = create_folder(client, &root_name, options.parent_id.as_deref()).await?;
+
+    // Parents sort before their children, so each parent exists first
+    let folders: BTreeSet<String> = files.iter()
+        .flat_map(|f| f.path.match_indices('/').map(|(i, _)| f.path[..i].to_string()))
+        .collect();
+    let mut folder_ids: HashMap<String, String> = HashMap::from([(String::new(), root.id.clone())]);
+    let mut entries: Vec<TreeEntry> = unreadable.iter()
+        .map(|path| TreeEntry { error: Some("could not be read".to_string()), ..TreeEntry::file(path, "skipped") })
+        .collect();
+    for folder in folders {
+        let (parent, name) = folder.rsplit_once('/').unwrap_or(("", &folder));
+        let Some(parent_id) = folder_ids.get(parent).cloned() else {
+            entries.push(TreeEntry::folder(&folder, None, "failed").failed("parent folder was not created"));
+            continue;
+        };
+        match create_folder(client, name, Some(&parent_id)).await {
+            Ok(created) => {
+                entries.push(TreeEntry::folder(&folder, Some(created.id.clone()), "created"));
+                folder_ids.insert(folder, created.id);
+            }
+            Err(e) => entries.push(TreeEntry::folder(&folder, None, "failed").failed(e)),
+        }
+    }
+
+    let total = files.len();
+    let folder_ids = &folder_ids;
+    let convert = options.convert;
+    let mut uploads = stream::iter(files)
+        .map(|file| async move {
+            let mut entry = TreeEntry::file(&file.path, "uploaded");
+            entry.size = Some(file.size);
+            let parent = file.path.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+            let Some(parent_id) = folder_ids.get(parent) else {
+                return entry.failed("parent folder was not created");
+            };
+            let params = UploadParams {
+                file_path: file.full_path.to_string_lossy().into_owned(),
+                name: None,
+                parent_id: Some(parent_id.clone()),
+                mime_type: None,
+                convert,
+            };
+            match upload_file(access_token, params).await {
+                Ok(uploaded) => {
+                    entry.id = Some(uploaded.id);
+                    entry.mime_type = Some(uploaded.mime_type);
+                    entry
+                }
+                Err(e) => entry.failed(e),
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut current = 0;
+    while let Some(entry) = uploads.next().await {
+        current += 1;
+        progress::emit(ProgressEvent::Item {
+            operation: "upload",
+            current,
+            total,
+            name: entry.path.clone(),
+            status: entry.status.to_string(),
+        });
+        entries.push(entry);
+    }
+
+    entries.push(TreeEntry::folder("", Some(root.id.clone()), "created"));
+    // Uploads bypass the client, so cached listings are dropped here
+    client.invalidate_cache();
+    Ok(TreeResult::new(dir, &root.id, entries))
+}
+
+/// Collect selected files below `dir`; excluded directories are not entered
+///
+/// Entries that can't be read (dangling symlinks, unreadable directories)
+/// are added to `unreadable` instead of failing the walk; only `dir` itself
+/// must be readable.
+pub(super) fn walk_local(
+    dir: &Path,
+    prefix: &str,
+    filter: &GlobFilter,
+    files: &mut Vec<LocalFile>,
+    unreadable: &mut Vec<String>,
+) -> Result<()> {
+    let mut children: Vec<_> = std::fs::read_dir(dir)?.collect::<std::io::Result<_>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+
+    for child in children {
+        let name = child.file_name().to_string_lossy().into_owned();
+        let path = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+        let Ok(file_type) = child.file_type() else {
+            unreadable.push(path);
+            continue;
+        };
+        if file_type.is_dir() {
+            if !filter.is_excluded(&path) && walk_local(&child.path(), &path, filter, files, unreadable).is_err() {
+                unreadable.push(path);
+            }
+            continue;
+        }
+
+        // Follow symlinks to files, but not to directories (which could loop)
+        let Ok(metadata) = std::fs::metadata(child.path()) else {
+            if filter.matches(&path) {
+                unreadable.push(path);
+            }
+            continue;
+        };
+        if metadata.is_file() && filter.matches(&path) {
+            files.push(LocalFile { path, full_path: child.path(), size: metadata.len(), modified: modified_millis(&metadata) });
+        }
+    }
+    Ok(())
+}
+
+/// Modification time of file metadata as epoch milliseconds
+pub(super) fn modified_millis(metadata: &std::fs::Metadata) -> i64 {
+    metadata.modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// A remote file selected for download
+struct RemoteFile {
+    path: String,
+    file: File,
+    /// Export MIME type for Google-native files
+    export_mime: Option<&'static str>,
+}
+
+/// Download a Drive folder and everything below it into a local directory
+///
+/// Google-native files are exported using `options.formats`; types that
+/// can't be exported are listed as skipped. `dir` defaults to the folder name.
+pub async fn download_tree(
+    client: &ApiClient,
+    access_token: &str,
+    folder_id: &str,
+    dir: Option<&Path>,
+    options: TreeDownloadOptions,
+) -> Result<TreeResult> {
+    let root = get_file(client, folder_id, Some("id,name,mimeType")).await?;
+    if root.mime_type != FOLDER_MIME {
+        return Err(WorkspaceError::InvalidInput(format!(
+            "'{}' is a file, not a folder; download it without --recursive", root.name
+        )));
+    }
+    let dir = dir.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(sanitize_name(&root.name)));
+
+    let mut entries = Vec::new();
+    let mut files = Vec::new();
+    let mut folders = vec![(root.id.clone(), String::new())];
+    while let Some((id, prefix)) = folders.pop() {
+        std::fs::create_dir_all(dir.join(&prefix))?;
+        let mut taken = HashSet::new();
+
+        for child in list_children(client, &id).await? {
+            let is_folder = child.mime_type == FOLDER_MIME;
+            let export = options.formats.target(&child.mime_type);
+            let name = match &export {
+                Some((_, extension)) => format!("{}.{}", sanitize_name(&child.name), extension),
+                None => sanitize_name(&child.name),
+            };
+            let name = unique_name(&name, &mut taken);
+            let path = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+
+            if is_folder {
+                if !options.filter.is_excluded(&path) {
+                    entries.push(TreeEntry::folder(&path, Some(child.id.clone()), "created"));
+                    folders.push((child.id, path));
+                }
+                continue;
+            }
+            if !options.filter.matches(&path) {
+                continue;
+            }
+            if child.mime_type.starts_with(NATIVE_PREFIX) && export.is_none() {
+                let mut entry = TreeEntry::file(&path, "skipped");
+                entry.id = Some(child.id);
+                entry.mime_type = Some(child.mime_type);
+                entries.push(entry);
+                continue;
+            }
+            files.push(RemoteFile { path, file: child, export_mime: export.map(|(mime, _)| mime) });
+        }
+    }
+
+    let total = files.len();
+    let dir_ref = &dir;
+    let mut downloads = stream::iter(files)
+        .map(|remote| async move {
+            let target = dir_ref.join(&remote.path);
+            let mut entry = TreeEntry::file(&remote.path, "downloaded");
+            entry.id = Some(remote.file.id.clone());
+            entry.mime_type = Some(remote.file.mime_type.clone());
+            let result = match remote.export_mime {
+                Some(mime) => {
+                    entry.status = "exported";
+                    export_file(access_token, &remote.file.id, mime, &target).await
+                }
+                None => download_file(access_token, &remote.file.id, &target).await,
+            };
+            match result {
+                Ok(bytes) => {
+                    entry.size = Some(bytes);
+                    entry
+                }
+                Err(e) => entry.failed(e),
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut current = 0;
+    while let Some(entry) = downloads.next().await {
+        current += 1;
+        progress::emit(ProgressEvent::Item {
+            operation: "download",
+            current,
+            total,
+            name: entry.path.clone(),
+            status: entry.status.to_string(),
+        });
+        entries.push(entry);
+    }
+
+    Ok(TreeResult::new(&dir, &root.id, entries))
+}
+
+/// All non-trashed children of a folder
+///
+/// Listed uncached: transfers and sync act on the result, so it has to be current.
+pub async fn list_children(client: &ApiClient, folder_id: &str) -> Result<Vec<File>> {
+    let query = format!("'{}' in parents and trashed = false", folder_id);
+    let mut children = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut params = vec![
+            ("q", query.as_str()),
+            ("pageSize", "1000"),
+            ("orderBy", "name"),
+            ("fields", "nextPageToken,files(id,name,mimeType,size,md5Checksum,modifiedTime,parents)"),
+        ];
+        if let Some(ref token) = page_token {
+            params.push(("pageToken", token));
+        }
+        let page: FileList = client.get_with_query_uncached("/files", &params).await?;
+        children.extend(page.files);
+        page_token = page.next_page_token;
+        if page_token.is_none() {
+            return Ok(children);
+        }
+    }
+}
+
+/// Make a Drive name safe as a local file name
+pub(super) fn sanitize_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| match c {
+            '/' | '\\' | '\0' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match cleaned.trim() {
+        "" | "." | ".." => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Drive allows several items with the same name in a folder; number the repeats
+pub(super) fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    };
+    let mut candidate = name.to_string();
+    let mut counter = 1;
+    while !taken.insert(candidate.to_lowercase()) {
+        counter += 1;
+        candidate = format!("{} ({}){}", stem, counter, extension);
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_formats_and_names() {
+        let formats = ExportFormats::parse(&["docs=pdf".to_string(), "Sheets=CSV".to_string()]).unwrap();
+        assert_eq!(formats.target("application/vnd.google-apps.document"), Some(("application/pdf", "pdf".to_string())));
+        assert_eq!(formats.target("application/vnd.google-apps.spreadsheet").unwrap().0, "text/csv");
+        assert_eq!(formats.target("application/vnd.google-apps.presentation").unwrap().1, "pptx");
+        assert!(formats.target("application/vnd.google-apps.form").is_none());
+        assert!(formats.target("application/pdf").is_none());
+        assert!(ExportFormats::parse(&["slides=csv".to_string()]).is_err());
+        assert!(ExportFormats::parse(&["pdf".to_string()]).is_err());
+        assert_eq!(export_target("application/vnd.google-apps.document", None).unwrap().1, "docx");
+        assert_eq!(export_target("application/vnd.google-apps.spreadsheet", Some("CSV")).unwrap().0, "text/csv");
+        assert!(export_target("application/vnd.google-apps.form", None).is_err());
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name("Notes.docx", &mut taken), "Notes.docx");
+        assert_eq!(unique_name("notes.docx", &mut taken), "notes (2).docx");
+        assert_eq!(sanitize_name("Q1/Q2 plan"), "Q1_Q2 plan");
+        assert_eq!(sanitize_name(".."), "_");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_local_skips_dangling_symlinks() {
+        let dir = std::env::temp_dir().join(format!("workspace-cli-walk-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("docs/a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink(dir.join("missing.txt"), dir.join("docs/broken.txt")).unwrap();
+
+        let mut files = Vec::new();
+        let mut unreadable = Vec::new();
+        walk_local(&dir, "", &GlobFilter::new(vec![], vec![]), &mut files, &mut unreadable).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let paths: Vec<_> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["docs/a.txt"]);
+        assert_eq!(unreadable, vec!["docs/broken.txt"]);
+    }
+}
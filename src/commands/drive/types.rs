@@ -11,6 +11,8 @@ pub struct File {
     pub web_view_link: Option<String>,
     pub web_content_link: Option<String>,
     pub size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5_checksum: Option<String>,
     pub created_time: Option<String>,
     pub modified_time: Option<String>,
     pub trashed: Option<bool>,
@@ -1,34 +1,75 @@
-use std::path::Path;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt};
 
+use crate::config::Config;
 use crate::error::{WorkspaceError, ApiError};
+use crate::output::progress::TransferProgress;
+use super::tree::modified_millis;
 use super::types::{File as DriveFile, FileMetadata};
 
 const RESUMABLE_THRESHOLD: u64 = 5 * 1024 * 1024; // 5MB
+// Resumable chunks must be multiples of 256KB
+const CHUNK_SIZE: usize = 8 * 256 * 1024; // 2MB
+const MAX_RETRIES: u32 = 5;
+const SESSION_MAX_AGE_DAYS: i64 = 6;
+const UPLOAD_FIELDS: &str = "id,name,mimeType,parents,webViewLink,webContentLink,size,md5Checksum,createdTime,modifiedTime";
 
 pub struct UploadParams {
     pub file_path: String,
     pub name: Option<String>,
     pub parent_id: Option<String>,
     pub mime_type: Option<String>,
+    /// Convert Office, CSV, Markdown and HTML files to Docs, Sheets or Slides
+    pub convert: bool,
 }
 
+const GOOGLE_DOCUMENT: &str = "application/vnd.google-apps.document";
+const GOOGLE_SPREADSHEET: &str = "application/vnd.google-apps.spreadsheet";
+const GOOGLE_PRESENTATION: &str = "application/vnd.google-apps.presentation";
+
+/// Google-native type a file of `mime_type` can be converted to on upload
+pub fn conversion_target(mime_type: &str) -> Option<&'static str> {
+    Some(match mime_type {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/msword"
+        | "application/vnd.oasis.opendocument.text"
+        | "application/rtf"
+        | "text/rtf"
+        | "text/plain"
+        | "text/markdown"
+        | "text/html" => GOOGLE_DOCUMENT,
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/vnd.ms-excel"
+        | "application/vnd.oasis.opendocument.spreadsheet"
+        | "text/csv"
+        | "text/tab-separated-values" => GOOGLE_SPREADSHEET,
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+        | "application/vnd.ms-powerpoint"
+        | "application/vnd.oasis.opendocument.presentation" => GOOGLE_PRESENTATION,
+        _ => return None,
+    })
+}
+
+/// Upload a local file as a new file
+///
+/// With `convert`, files with a `conversion_target` become Google-native
+/// files named without their extension; other files upload unchanged.
 pub async fn upload_file(
     access_token: &str,
     params: UploadParams,
 ) -> Result<DriveFile, WorkspaceError> {
     let path = Path::new(&params.file_path);
-    let file_name = params.name.unwrap_or_else(|| {
-        path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unnamed")
-            .to_string()
-    });
 
     let metadata = std::fs::metadata(path)
-        .map_err(|e| WorkspaceError::Io(e))?;
+        .map_err(WorkspaceError::Io)?;
     let file_size = metadata.len();
 
     let mime_type = params.mime_type.unwrap_or_else(|| {
@@ -36,34 +77,89 @@ pub async fn upload_file(
             .first_or_octet_stream()
             .to_string()
     });
+    let target_mime = conversion_target(&mime_type).filter(|_| params.convert);
+
+    let file_name = params.name.unwrap_or_else(|| {
+        let name = if target_mime.is_some() { path.file_stem() } else { path.file_name() };
+        name.and_then(|n| n.to_str())
+            .unwrap_or("unnamed")
+            .to_string()
+    });
+    let metadata = FileMetadata {
+        name: file_name,
+        mime_type: Some(target_mime.unwrap_or(&mime_type).to_string()),
+        parents: params.parent_id.map(|p| vec![p]),
+    };
 
     if file_size > RESUMABLE_THRESHOLD {
-        resumable_upload(access_token, path, &file_name, &mime_type, params.parent_id).await
+        resumable_upload(access_token, path, &metadata, &mime_type).await
     } else {
-        simple_upload(access_token, path, &file_name, &mime_type, params.parent_id).await
+        simple_upload(access_token, path, &metadata, &mime_type).await
     }
 }
 
-async fn simple_upload(
+/// Upload in-memory content as a new file
+///
+/// Content up to 5MB goes in one multipart request; larger content uses a
+/// resumable session with a single PUT.
+pub async fn upload_bytes(
     access_token: &str,
-    path: &Path,
     name: &str,
     mime_type: &str,
+    content: Vec<u8>,
     parent_id: Option<String>,
 ) -> Result<DriveFile, WorkspaceError> {
+    let metadata = FileMetadata {
+        name: name.to_string(),
+        mime_type: Some(mime_type.to_string()),
+        parents: parent_id.map(|p| vec![p]),
+    };
+
+    if content.len() as u64 <= RESUMABLE_THRESHOLD {
+        return multipart_upload(access_token, &metadata, mime_type, content).await;
+    }
+
     let client = Client::new();
+    let upload_uri = start_resumable_session(&client, access_token, &metadata, mime_type, content.len() as u64).await?;
+    let mut progress = TransferProgress::new("upload", name, Some(content.len() as u64));
+    let size = content.len() as u64;
+    let hasher = Md5::new_with_prefix(&content);
+    let response = client
+        .put(&upload_uri)
+        .header("Content-Type", mime_type)
+        .header("Content-Length", size.to_string())
+        .body(content)
+        .send()
+        .await?;
+    let file = parse_upload_response(response).await?;
+    verify_content_md5(&file, hasher)?;
+    progress.advance(size);
+    progress.finish();
+    Ok(file)
+}
 
+async fn simple_upload(
+    access_token: &str,
+    path: &Path,
+    metadata: &FileMetadata,
+    mime_type: &str,
+) -> Result<DriveFile, WorkspaceError> {
     let mut file = File::open(path).await?;
     let mut content = Vec::new();
     file.read_to_end(&mut content).await?;
 
-    let metadata = FileMetadata {
-        name: name.to_string(),
-        mime_type: Some(mime_type.to_string()),
-        parents: parent_id.map(|p| vec![p]),
-    };
+    multipart_upload(access_token, metadata, mime_type, content).await
+}
 
-    let metadata_json = serde_json::to_string(&metadata)?;
+/// Metadata and content in one multipart/related request
+async fn multipart_upload(
+    access_token: &str,
+    metadata: &FileMetadata,
+    mime_type: &str,
+    content: Vec<u8>,
+) -> Result<DriveFile, WorkspaceError> {
+    let client = Client::new();
+    let metadata_json = serde_json::to_string(metadata)?;
 
     // Multipart upload
     let boundary = "workspace_cli_boundary";
@@ -81,14 +177,26 @@ async fn simple_upload(
     body.extend_from_slice(&content);
     body.extend_from_slice(format!("\r\n--{}--", boundary).as_bytes());
 
+    let hasher = Md5::new_with_prefix(&content);
+    let mut progress = TransferProgress::new("upload", &metadata.name, Some(content.len() as u64));
+    let url = format!("https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&fields={}", UPLOAD_FIELDS);
     let response = client
-        .post("https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart")
+        .post(&url)
         .header("Authorization", format!("Bearer {}", access_token))
         .header("Content-Type", format!("multipart/related; boundary={}", boundary))
         .body(body)
         .send()
         .await?;
 
+    let file = parse_upload_response(response).await?;
+    verify_content_md5(&file, hasher)?;
+    progress.advance(content.len() as u64);
+    progress.finish();
+    Ok(file)
+}
+
+/// Parse the file from a final upload response, or turn it into an API error
+async fn parse_upload_response(response: reqwest::Response) -> Result<DriveFile, WorkspaceError> {
     if !response.status().is_success() {
         let status = response.status().as_u16();
         let text = response.text().await.unwrap_or_default();
@@ -99,39 +207,38 @@ async fn simple_upload(
             retry_after: None,
         }));
     }
-
     response.json().await.map_err(WorkspaceError::from)
 }
 
-async fn resumable_upload(
+/// Start a resumable upload session and return its upload URI
+async fn start_resumable_session(
+    client: &Client,
     access_token: &str,
-    path: &Path,
-    name: &str,
+    metadata: &FileMetadata,
     mime_type: &str,
-    parent_id: Option<String>,
-) -> Result<DriveFile, WorkspaceError> {
-    let client = Client::new();
-
-    let file_size = std::fs::metadata(path)
-        .map_err(|e| WorkspaceError::Io(e))?
-        .len();
-
-    let metadata = FileMetadata {
-        name: name.to_string(),
-        mime_type: Some(mime_type.to_string()),
-        parents: parent_id.map(|p| vec![p]),
-    };
+    size: u64,
+) -> Result<String, WorkspaceError> {
+    let url = format!("https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields={}", UPLOAD_FIELDS);
+    open_session(client.post(&url), access_token, metadata, mime_type, Some(size)).await
+}
 
-    // Step 1: Initiate resumable upload
-    let init_response = client
-        .post("https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable")
+/// Send a session-initiating request (new file or content update) and return the upload URI
+async fn open_session(
+    request: reqwest::RequestBuilder,
+    access_token: &str,
+    metadata: &impl serde::Serialize,
+    mime_type: &str,
+    size: Option<u64>,
+) -> Result<String, WorkspaceError> {
+    let mut request = request
         .header("Authorization", format!("Bearer {}", access_token))
         .header("Content-Type", "application/json")
-        .header("X-Upload-Content-Type", mime_type)
-        .header("X-Upload-Content-Length", file_size.to_string())
-        .json(&metadata)
-        .send()
-        .await?;
+        .header("X-Upload-Content-Type", mime_type);
+    // Streams of unknown length declare the total with their last chunk
+    if let Some(size) = size {
+        request = request.header("X-Upload-Content-Length", size.to_string());
+    }
+    let init_response = request.json(metadata).send().await?;
 
     if !init_response.status().is_success() {
         let status = init_response.status().as_u16();
@@ -144,60 +251,639 @@ async fn resumable_upload(
         }));
     }
 
-    let upload_uri = init_response
+    init_response
         .headers()
         .get("location")
         .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| WorkspaceError::Config("No upload URI in response".to_string()))?
-        .to_string();
+        .map(String::from)
+        .ok_or_else(|| WorkspaceError::Config("No upload URI in response".to_string()))
+}
+
+/// `metadata` names the new file and, when converting, its Google-native type;
+/// `mime_type` is the type of the content sent
+async fn resumable_upload(
+    access_token: &str,
+    path: &Path,
+    metadata: &FileMetadata,
+    mime_type: &str,
+) -> Result<DriveFile, WorkspaceError> {
+    let client = Client::new();
+
+    let file_size = std::fs::metadata(path)
+        .map_err(WorkspaceError::Io)?
+        .len();
+
+    let parent = metadata.parents.as_ref().and_then(|p| p.first()).map_or("root", String::as_str);
+    let target = format!(
+        "create:{}/{}:{}",
+        parent,
+        metadata.name,
+        metadata.mime_type.as_deref().unwrap_or(mime_type)
+    );
+
+    let url = format!("https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields={}", UPLOAD_FIELDS);
+    let open = open_session(client.post(&url), access_token, metadata, mime_type, Some(file_size));
+    resumable_transfer(&client, &target, path, &metadata.name, mime_type, file_size, open).await
+}
+
+/// Replace the content of an existing file with a local file
+pub async fn update_file_content(
+    access_token: &str,
+    file_id: &str,
+    file_path: &Path,
+    mime_type: Option<&str>,
+) -> Result<DriveFile, WorkspaceError> {
+    let client = Client::new();
+    let file_size = std::fs::metadata(file_path)
+        .map_err(WorkspaceError::Io)?
+        .len();
+    let mime_type = mime_type.map(str::to_string).unwrap_or_else(|| {
+        mime_guess::from_path(file_path).first_or_octet_stream().to_string()
+    });
+    let name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or(file_id).to_string();
+
+    let url = format!(
+        "https://www.googleapis.com/upload/drive/v3/files/{}?uploadType=resumable&fields={}",
+        file_id, UPLOAD_FIELDS
+    );
+    let metadata = serde_json::json!({});
+    let open = open_session(client.patch(&url), access_token, &metadata, &mime_type, Some(file_size));
+    resumable_transfer(&client, &format!("update:{}", file_id), file_path, &name, &mime_type, file_size, open).await
+}
+
+/// A resumable upload session, saved so an interrupted upload can continue after a restart
+///
+/// Sessions live in `drive_uploads/` in the config directory, one file per
+/// local file and destination, and are removed once the upload completes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSession {
+    pub key: String,
+    pub upload_uri: String,
+    pub file_path: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    /// Local modification time when the session started, epoch milliseconds
+    pub modified: i64,
+    /// Bytes the server had committed at the last save
+    pub offset: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UploadSession {
+    fn dir() -> Option<PathBuf> {
+        Config::config_dir().map(|dir| dir.join("drive_uploads"))
+    }
+
+    /// Sessions are keyed by destination and local file, so an edited file starts over
+    fn key(target: &str, path: &Path, size: u64, modified: i64) -> String {
+        Sha256::digest(format!("{}\n{}\n{}\n{}", target, path.display(), size, modified).as_bytes())
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn load(key: &str) -> Option<Self> {
+        let path = Self::dir()?.join(format!("{}.json", key));
+        std::fs::read_to_string(path).ok().and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// All saved sessions, oldest first
+    pub fn list() -> Vec<Self> {
+        let Some(entries) = Self::dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<Self> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        sessions.sort_by_key(|session| session.created_at);
+        sessions
+    }
+
+    fn save(&self) -> Result<(), WorkspaceError> {
+        let Some(dir) = Self::dir() else { return Ok(()) };
+        std::fs::create_dir_all(&dir)?;
+        // Write to a temporary file and rename so a crash mid-write keeps the last offset
+        let path = dir.join(format!("{}.json", self.key));
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn remove(&self) {
+        if let Some(dir) = Self::dir() {
+            let _ = std::fs::remove_file(dir.join(format!("{}.json", self.key)));
+        }
+    }
+
+    /// Drive keeps resumable sessions for a week
+    fn is_expired(&self) -> bool {
+        Utc::now() - self.created_at > chrono::Duration::days(SESSION_MAX_AGE_DAYS)
+    }
+
+    /// Whether the local file is still the one the session started with
+    fn matches_local(&self) -> bool {
+        std::fs::metadata(&self.file_path)
+            .is_ok_and(|metadata| metadata.len() == self.size && modified_millis(&metadata) == self.modified)
+    }
+}
+
+/// Where a resumable session stands on the server
+enum SessionStatus {
+    Complete(Box<DriveFile>),
+    /// Bytes committed so far
+    Incomplete(u64),
+    /// The session is gone (expired or cancelled)
+    Expired,
+}
+
+/// Ask the server how much of a session it has, without sending data
+async fn query_status(client: &Client, upload_uri: &str, size: Option<u64>) -> Result<SessionStatus, WorkspaceError> {
+    let total = size.map_or_else(|| "*".to_string(), |size| size.to_string());
+    let response = client
+        .put(upload_uri)
+        .header("Content-Length", "0")
+        .header("Content-Range", format!("bytes */{}", total))
+        .send()
+        .await?;
+    match response.status().as_u16() {
+        200 | 201 => Ok(SessionStatus::Complete(Box::new(response.json().await?))),
+        308 => Ok(SessionStatus::Incomplete(committed_offset(&response))),
+        404 | 410 => Ok(SessionStatus::Expired),
+        status => Err(upload_error(status, response.text().await.unwrap_or_default())),
+    }
+}
+
+/// Bytes committed according to a 308 response's `Range: bytes=0-N` header
+fn committed_offset(response: &reqwest::Response) -> u64 {
+    response.headers()
+        .get("range")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|range| range.rsplit('-').next())
+        .and_then(|end| end.parse::<u64>().ok())
+        .map_or(0, |end| end + 1)
+}
+
+/// Upload through a resumable session, continuing a saved one for the same file if there is one
+async fn resumable_transfer(
+    client: &Client,
+    target: &str,
+    path: &Path,
+    name: &str,
+    mime_type: &str,
+    size: u64,
+    open: impl std::future::Future<Output = Result<String, WorkspaceError>>,
+) -> Result<DriveFile, WorkspaceError> {
+    let full_path = path.canonicalize()?;
+    let modified = modified_millis(&std::fs::metadata(&full_path)?);
+    let key = UploadSession::key(target, &full_path, size, modified);
+
+    let saved = UploadSession::load(&key).filter(|session| !session.is_expired() && session.matches_local());
+    let resumed = match saved {
+        Some(mut session) => match query_status(client, &session.upload_uri, Some(size)).await? {
+            SessionStatus::Complete(file) => {
+                session.remove();
+                verify_md5(&file, &full_path)?;
+                return Ok(*file);
+            }
+            SessionStatus::Incomplete(offset) => {
+                session.offset = offset;
+                Some(session)
+            }
+            SessionStatus::Expired => None,
+        },
+        None => None,
+    };
+
+    let mut session = match resumed {
+        Some(session) => session,
+        None => {
+            let session = UploadSession {
+                key,
+                upload_uri: open.await?,
+                file_path: full_path.display().to_string(),
+                name: name.to_string(),
+                mime_type: mime_type.to_string(),
+                size,
+                modified,
+                offset: 0,
+                created_at: Utc::now(),
+            };
+            session.save()?;
+            session
+        }
+    };
+
+    let file = send_chunks(client, &mut session).await?;
+    session.remove();
+    verify_md5(&file, &full_path)?;
+    Ok(file)
+}
+
+/// Upload everything `reader` produces as a new file, without knowing its size up front
+///
+/// Content goes through a resumable session one chunk at a time, so memory
+/// use stays at one chunk however long the stream is. A chunk is retried
+/// like in `send_chunks`, but a stream can't be replayed, so the session is
+/// not saved for resuming after a restart. The md5 of what was read is
+/// checked against Drive's. `convert` works as in `upload_file`.
+pub async fn upload_stream<R: AsyncRead + Unpin>(
+    access_token: &str,
+    mut reader: R,
+    name: &str,
+    mime_type: &str,
+    parent_id: Option<String>,
+    convert: bool,
+) -> Result<DriveFile, WorkspaceError> {
+    let client = Client::new();
+    let metadata = FileMetadata {
+        name: name.to_string(),
+        mime_type: Some(conversion_target(mime_type).filter(|_| convert).unwrap_or(mime_type).to_string()),
+        parents: parent_id.map(|p| vec![p]),
+    };
+    let url = format!("https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields={}", UPLOAD_FIELDS);
+    let upload_uri = open_session(client.post(&url), access_token, &metadata, mime_type, None).await?;
 
-    // Step 2: Upload the file content in chunks
-    const CHUNK_SIZE: usize = 256 * 1024; // 256KB chunks
-    let mut file = File::open(path).await?;
     let mut buffer = vec![0u8; CHUNK_SIZE];
-    let mut uploaded = 0u64;
+    let mut hasher = Md5::new();
+    let mut offset = 0u64;
+    let mut progress = TransferProgress::new("upload", name, None);
 
     loop {
-        let bytes_read = file.read(&mut buffer).await?;
+        let bytes_read = read_chunk(&mut reader, &mut buffer).await?;
+        hasher.update(&buffer[..bytes_read]);
+        // A short read is the end of the stream, which fixes the total
+        let total = (bytes_read < CHUNK_SIZE).then_some(offset + bytes_read as u64);
+        let chunk = &buffer[..bytes_read];
+        let mut sent = 0;
+        let mut failures = 0;
+
+        while sent < chunk.len() || total.is_some() {
+            let start = offset + sent as u64;
+            let remaining = chunk.len() - sent;
+            let total_text = total.map_or_else(|| "*".to_string(), |total| total.to_string());
+            let content_range = if remaining == 0 {
+                format!("bytes */{}", total_text)
+            } else {
+                format!("bytes {}-{}/{}", start, start + remaining as u64 - 1, total_text)
+            };
+
+            let response = client
+                .put(&upload_uri)
+                .header("Content-Type", mime_type)
+                .header("Content-Length", remaining.to_string())
+                .header("Content-Range", content_range)
+                .body(chunk[sent..].to_vec())
+                .send()
+                .await;
+
+            let error = match response {
+                Ok(response) if response.status().is_success() => {
+                    progress.advance(remaining as u64);
+                    progress.finish();
+                    let file: DriveFile = response.json().await?;
+                    verify_content_md5(&file, hasher)?;
+                    return Ok(file);
+                }
+                Ok(response) if response.status().as_u16() == 308 => {
+                    let committed = committed_offset(&response).saturating_sub(offset) as usize;
+                    if committed > sent {
+                        progress.advance((committed - sent) as u64);
+                        sent = committed;
+                        failures = 0;
+                        continue;
+                    }
+                    upload_error(308, "No bytes committed".to_string())
+                }
+                Ok(response) if response.status().is_server_error() || response.status().as_u16() == 429 => {
+                    let status = response.status().as_u16();
+                    upload_error(status, response.text().await.unwrap_or_default())
+                }
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    return Err(upload_error(status, response.text().await.unwrap_or_default()));
+                }
+                Err(e) => WorkspaceError::from(e),
+            };
+
+            failures += 1;
+            if failures > MAX_RETRIES {
+                return Err(error);
+            }
+            tokio::time::sleep(Duration::from_secs(1 << failures.min(5))).await;
+
+            match query_status(&client, &upload_uri, total).await {
+                Ok(SessionStatus::Complete(file)) => {
+                    progress.finish();
+                    verify_content_md5(&file, hasher)?;
+                    return Ok(*file);
+                }
+                // Earlier chunks are gone from memory, so the server must have all of them
+                Ok(SessionStatus::Incomplete(committed)) if committed < offset => {
+                    return Err(upload_error(308, format!("Upload session lost data before byte {}", offset)));
+                }
+                Ok(SessionStatus::Incomplete(committed)) => {
+                    let committed = ((committed - offset) as usize).min(chunk.len());
+                    progress.advance(committed.saturating_sub(sent) as u64);
+                    sent = committed;
+                }
+                Ok(SessionStatus::Expired) => {
+                    return Err(upload_error(410, "Upload session expired".to_string()));
+                }
+                Err(_) => {}
+            }
+        }
+        offset += bytes_read as u64;
+    }
+}
+
+/// Check the uploaded file's md5Checksum against the hash of the bytes sent
+///
+/// Google-native (converted) files have no md5Checksum and are not checked.
+fn verify_content_md5(file: &DriveFile, hasher: Md5) -> Result<(), WorkspaceError> {
+    let Some(remote) = file.md5_checksum.as_deref() else { return Ok(()) };
+    let local: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    if local != remote {
+        return Err(WorkspaceError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Checksum mismatch after uploading {}: sent md5 {}, Drive md5 {}", file.name, local, remote),
+        )));
+    }
+    Ok(())
+}
+
+/// Result of finishing one interrupted upload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumedUpload {
+    pub file_path: String,
+    pub name: String,
+    /// done, expired (the session is gone), changed (the local file was modified) or failed
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<DriveFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Finish every saved upload session
+///
+/// Sessions that can no longer be continued are discarded; re-run the
+/// upload to start those over.
+pub async fn resume_uploads() -> Vec<ResumedUpload> {
+    let client = Client::new();
+    let mut results = Vec::new();
+    for mut session in UploadSession::list() {
+        let mut result = ResumedUpload {
+            file_path: session.file_path.clone(),
+            name: session.name.clone(),
+            status: "done",
+            file: None,
+            error: None,
+        };
+        if !session.matches_local() {
+            session.remove();
+            result.status = "changed";
+            results.push(result);
+            continue;
+        }
+        let outcome = match query_status(&client, &session.upload_uri, Some(session.size)).await {
+            Ok(SessionStatus::Complete(file)) => Ok(Some(*file)),
+            Ok(SessionStatus::Incomplete(offset)) => {
+                session.offset = offset;
+                send_chunks(&client, &mut session).await.map(Some)
+            }
+            Ok(SessionStatus::Expired) => Ok(None),
+            Err(e) => Err(e),
+        };
+        match outcome.and_then(|file| {
+            if let Some(file) = &file {
+                verify_md5(file, Path::new(&session.file_path))?;
+            }
+            Ok(file)
+        }) {
+            Ok(Some(file)) => {
+                session.remove();
+                result.file = Some(file);
+            }
+            Ok(None) => {
+                session.remove();
+                result.status = "expired";
+            }
+            Err(e) => {
+                result.status = "failed";
+                result.error = Some(e.to_string());
+            }
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Upload the rest of a session's file in chunks
+///
+/// Each chunk is retried on 5xx responses, network errors and 308 responses
+/// that committed less than was sent; after a failure the server is asked
+/// for its committed offset and the upload continues from there.
+async fn send_chunks(client: &Client, session: &mut UploadSession) -> Result<DriveFile, WorkspaceError> {
+    let size = session.size;
+    if size == 0 {
+        let response = client.put(&session.upload_uri).header("Content-Length", "0").send().await?;
+        return parse_upload_response(response).await;
+    }
+
+    let mut file = File::open(&session.file_path).await?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut progress = TransferProgress::new("upload", &session.name, Some(size));
+    progress.advance(session.offset);
+    let mut failures = 0;
+
+    loop {
+        file.seek(SeekFrom::Start(session.offset)).await?;
+        let bytes_read = read_chunk(&mut file, &mut buffer).await?;
         if bytes_read == 0 {
-            break;
+            return Err(WorkspaceError::Config("Upload completed but no response received".to_string()));
         }
 
-        let chunk_end = uploaded + bytes_read as u64 - 1;
-        let content_range = format!("bytes {}-{}/{}", uploaded, chunk_end, file_size);
+        let chunk_end = session.offset + bytes_read as u64 - 1;
+        let content_range = format!("bytes {}-{}/{}", session.offset, chunk_end, size);
 
         let response = client
-            .put(&upload_uri)
-            .header("Content-Type", mime_type)
+            .put(&session.upload_uri)
+            .header("Content-Type", &session.mime_type)
             .header("Content-Length", bytes_read.to_string())
             .header("Content-Range", content_range)
             .body(buffer[..bytes_read].to_vec())
             .send()
-            .await?;
-
-        uploaded += bytes_read as u64;
+            .await;
+
+        let error = match response {
+            Ok(response) if response.status().is_success() => {
+                progress.advance(size - session.offset);
+                progress.finish();
+                return response.json().await.map_err(WorkspaceError::from);
+            }
+            // 308 Resume Incomplete: continue from what the server committed
+            Ok(response) if response.status().as_u16() == 308 => {
+                let committed = committed_offset(&response);
+                if committed > session.offset {
+                    progress.advance(committed - session.offset);
+                    session.offset = committed;
+                    session.save()?;
+                    failures = 0;
+                    continue;
+                }
+                upload_error(308, "No bytes committed".to_string())
+            }
+            Ok(response) if response.status().is_server_error() || response.status().as_u16() == 429 => {
+                let status = response.status().as_u16();
+                upload_error(status, response.text().await.unwrap_or_default())
+            }
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if matches!(status, 404 | 410) {
+                    session.remove();
+                }
+                return Err(upload_error(status, response.text().await.unwrap_or_default()));
+            }
+            Err(e) => WorkspaceError::from(e),
+        };
+
+        failures += 1;
+        if failures > MAX_RETRIES {
+            return Err(error);
+        }
+        tokio::time::sleep(Duration::from_secs(1 << failures.min(5))).await;
+
+        // Find out what the server kept before sending again
+        match query_status(client, &session.upload_uri, Some(size)).await {
+            Ok(SessionStatus::Complete(file)) => {
+                progress.finish();
+                return Ok(*file);
+            }
+            Ok(SessionStatus::Incomplete(committed)) => {
+                progress.advance(committed.saturating_sub(session.offset));
+                session.offset = committed;
+                session.save()?;
+            }
+            Ok(SessionStatus::Expired) => {
+                session.remove();
+                return Err(upload_error(410, "Upload session expired".to_string()));
+            }
+            // Retry the chunk from the last known offset
+            Err(_) => {}
+        }
+    }
+}
 
-        // 308 Resume Incomplete means continue uploading
-        if response.status().as_u16() == 308 {
-            continue;
+/// Fill `buffer` as far as the file allows; only the last chunk may be short
+async fn read_chunk<R: AsyncRead + Unpin>(reader: &mut R, buffer: &mut [u8]) -> Result<usize, WorkspaceError> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = reader.read(&mut buffer[filled..]).await?;
+        if read == 0 {
+            break;
         }
+        filled += read;
+    }
+    Ok(filled)
+}
+
+/// Check the uploaded file's md5Checksum against the local file
+fn verify_md5(file: &DriveFile, path: &Path) -> Result<(), WorkspaceError> {
+    let Some(remote) = file.md5_checksum.as_deref() else { return Ok(()) };
+    let local = md5_file(path)?;
+    if local != remote {
+        return Err(WorkspaceError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Checksum mismatch after uploading {}: local md5 {}, Drive md5 {}", path.display(), local, remote),
+        )));
+    }
+    Ok(())
+}
 
-        // Check for success (200 or 201)
-        if response.status().is_success() {
-            return response.json().await.map_err(WorkspaceError::from);
+/// Hex MD5 of a file, as Drive reports in md5Checksum
+pub(super) fn md5_file(path: &Path) -> Result<String, WorkspaceError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = std::io::Read::read(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
         }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
 
-        // Handle error
-        let status = response.status().as_u16();
-        let text = response.text().await.unwrap_or_default();
-        return Err(WorkspaceError::Api(ApiError {
-            code: status,
-            message: text,
-            domain: "drive".to_string(),
-            retry_after: None,
-        }));
+fn upload_error(code: u16, message: String) -> WorkspaceError {
+    WorkspaceError::Api(ApiError {
+        code,
+        message,
+        domain: "drive".to_string(),
+        retry_after: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_target() {
+        assert_eq!(conversion_target("text/csv"), Some(GOOGLE_SPREADSHEET));
+        assert_eq!(conversion_target("text/markdown"), Some(GOOGLE_DOCUMENT));
+        assert_eq!(conversion_target("application/vnd.ms-powerpoint"), Some(GOOGLE_PRESENTATION));
+        assert_eq!(conversion_target("application/pdf"), None);
     }
 
-    // If we get here, the upload completed but didn't get a final response
-    Err(WorkspaceError::Config("Upload completed but no response received".to_string()))
+    #[test]
+    fn test_upload_session() {
+        let path = Path::new("/tmp/video.mp4");
+        let key = UploadSession::key("create:root/video.mp4", path, 100, 1);
+        assert_eq!(key, UploadSession::key("create:root/video.mp4", path, 100, 1));
+        assert_ne!(key, UploadSession::key("create:root/video.mp4", path, 100, 2));
+        assert_ne!(key, UploadSession::key("update:abc", path, 100, 1));
+
+        let mut session = UploadSession {
+            key,
+            upload_uri: "https://example.com/upload".to_string(),
+            file_path: path.display().to_string(),
+            name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            size: 100,
+            modified: 1,
+            offset: 0,
+            created_at: Utc::now(),
+        };
+        assert!(!session.is_expired());
+        session.created_at -= chrono::Duration::days(SESSION_MAX_AGE_DAYS + 1);
+        assert!(session.is_expired());
+    }
+
+    #[test]
+    fn test_verify_content_md5() {
+        let file = |md5: Option<&str>| -> DriveFile {
+            serde_json::from_value(serde_json::json!({
+                "id": "f1",
+                "name": "notes.txt",
+                "mimeType": "text/plain",
+                "md5Checksum": md5,
+            }))
+            .unwrap()
+        };
+
+        // md5("hello")
+        let uploaded = file(Some("5d41402abc4b2a76b9719d911017c592"));
+        assert!(verify_content_md5(&uploaded, Md5::new_with_prefix(b"hello")).is_ok());
+        assert!(verify_content_md5(&uploaded, Md5::new_with_prefix(b"hellO")).is_err());
+        assert!(verify_content_md5(&file(None), Md5::new_with_prefix(b"hello")).is_ok());
+    }
 }
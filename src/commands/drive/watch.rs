@@ -60,7 +60,8 @@ pub struct StartPageTokenResponse {
 
 /// Get the starting page token for watching changes
 pub async fn get_start_page_token(client: &ApiClient) -> Result<StartPageTokenResponse> {
-    client.get("/changes/startPageToken").await
+    let no_query: [(&str, &str); 0] = [];
+    client.get_with_query_uncached("/changes/startPageToken", &no_query).await
 }
 
 /// Watch for changes to the user's Drive
@@ -142,6 +143,12 @@ pub struct Change {
 
 /// List changes since a page token
 pub async fn list_changes(client: &ApiClient, page_token: &str) -> Result<ChangesResponse> {
-    let path = format!("/changes?pageToken={}", urlencoding::encode(page_token));
-    client.get(&path).await
+    client.get_with_query_uncached("/changes", &[("pageToken", page_token)]).await
+}
+
+/// List changes since a page token, returning only the given `fields`
+pub async fn list_changes_with_fields(client: &ApiClient, page_token: &str, fields: &str) -> Result<ChangesResponse> {
+    let query = [("pageToken", page_token), ("pageSize", "1000"), ("fields", fields)];
+    // A page token returns more once new changes arrive, so never serve it from the cache
+    client.get_with_query_uncached("/changes", &query).await
 }
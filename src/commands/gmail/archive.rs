@@ -0,0 +1,495 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use futures::stream::{self, StreamExt};
+
+use crate::client::ApiClient;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use crate::utils::base64::{decode_base64url, encode_base64url};
+use crate::utils::charset::decode_encoded_words;
+use super::bulk::collect_message_ids;
+use super::get::get_raw_message;
+use super::labels::{list_labels, LabelResolver};
+use super::types::MessageRef;
+
+/// Header carrying label names through an export/import round trip (as in Google Takeout)
+const LABELS_HEADER: &str = "X-Gmail-Labels";
+
+/// Labels that cannot be set on imported messages
+const UNIMPORTABLE_LABELS: &[&str] = &["DRAFT", "CHAT"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// One mboxrd file
+    Mbox,
+    /// A directory of `<id>.eml` files plus `index.json`
+    Eml,
+}
+
+impl ArchiveFormat {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "mbox" => Some(Self::Mbox),
+            "eml" => Some(Self::Eml),
+            _ => None,
+        }
+    }
+
+    /// `.mbox` paths are mbox files, anything else is an EML directory
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("mbox") => Self::Mbox,
+            _ => Self::Eml,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mbox => "mbox",
+            Self::Eml => "eml",
+        }
+    }
+}
+
+pub struct ExportOptions {
+    pub query: String,
+    pub out: PathBuf,
+    pub format: ArchiveFormat,
+    /// Stop after this many messages
+    pub max: Option<usize>,
+    /// Messages fetched in parallel
+    pub concurrency: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub query: String,
+    pub format: String,
+    pub out: String,
+    pub exported: usize,
+    pub failed: usize,
+    pub bytes: u64,
+    /// More messages matched than --max allowed
+    pub capped: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Entry in the `index.json` of an EML export
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub id: String,
+    pub thread_id: String,
+    pub file: String,
+    pub labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+/// Export messages matching a query as mbox or EML files
+///
+/// Messages are fetched in `raw` format, `concurrency` at a time, and written
+/// in search order. Label names are stored in an `X-Gmail-Labels` header so
+/// `import` can restore them.
+pub async fn export(client: &ApiClient, options: &ExportOptions) -> Result<ExportResult> {
+    let (ids, capped) = collect_message_ids(client, &options.query, options.max.unwrap_or(usize::MAX)).await?;
+
+    // System labels keep their IDs (INBOX, STARRED); user labels are exported by name
+    let label_names: HashMap<String, String> = list_labels(client).await?.labels.into_iter()
+        .map(|l| {
+            let name = if l.r#type == "system" { l.id.clone() } else { l.name };
+            (l.id, name)
+        })
+        .collect();
+
+    let mut mbox = match options.format {
+        ArchiveFormat::Mbox => {
+            if let Some(dir) = options.out.parent().filter(|d| !d.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)?;
+            }
+            Some(std::io::BufWriter::new(std::fs::File::create(&options.out)?))
+        }
+        ArchiveFormat::Eml => {
+            std::fs::create_dir_all(&options.out)?;
+            None
+        }
+    };
+
+    let mut result = ExportResult {
+        query: options.query.clone(),
+        format: options.format.as_str().to_string(),
+        out: options.out.display().to_string(),
+        exported: 0,
+        failed: 0,
+        bytes: 0,
+        capped,
+        errors: Vec::new(),
+    };
+    let mut index = Vec::new();
+
+    let mut fetches = stream::iter(&ids)
+        .map(|id| async move { (id, get_raw_message(client, id).await) })
+        .buffered(options.concurrency.max(1));
+
+    let mut current = 0;
+    while let Some((id, fetched)) = fetches.next().await {
+        current += 1;
+        let decoded = fetched.and_then(|m| {
+            decode_base64url(&m.raw)
+                .map(|source| (m, source))
+                .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid raw message: {}", e)))
+        });
+        let (message, source) = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                result.failed += 1;
+                result.errors.push(format!("{}: {}", id, e));
+                continue;
+            }
+        };
+
+        let labels: Vec<String> = message.label_ids.iter()
+            .map(|id| label_names.get(id).cloned().unwrap_or_else(|| id.clone()))
+            .collect();
+        let data = add_header(&source, LABELS_HEADER, &labels.join(","));
+        let date = message.internal_date.as_deref()
+            .and_then(|ms| ms.parse::<i64>().ok())
+            .and_then(chrono::DateTime::from_timestamp_millis);
+
+        match mbox {
+            Some(ref mut writer) => {
+                let entry = mbox_entry(&data, date);
+                writer.write_all(&entry)?;
+                result.bytes += entry.len() as u64;
+            }
+            None => {
+                let file = format!("{}.eml", message.id);
+                std::fs::write(options.out.join(&file), &data)?;
+                result.bytes += data.len() as u64;
+                index.push(IndexEntry {
+                    id: message.id.clone(),
+                    thread_id: message.thread_id.clone(),
+                    file,
+                    labels,
+                    date: date.map(|d| d.to_rfc3339()),
+                    subject: header_value(&data, "Subject").map(|v| decode_encoded_words(&v)),
+                    from: header_value(&data, "From").map(|v| decode_encoded_words(&v)),
+                });
+            }
+        }
+
+        result.exported += 1;
+        progress::emit(ProgressEvent::Item {
+            operation: "export",
+            current,
+            total: ids.len(),
+            name: message.id,
+            status: "exported".to_string(),
+        });
+    }
+
+    match mbox {
+        Some(mut writer) => writer.flush()?,
+        None => std::fs::write(options.out.join("index.json"), serde_json::to_string_pretty(&index)?)?,
+    }
+
+    Ok(result)
+}
+
+pub struct ImportOptions {
+    /// Extra labels (names or IDs) applied to every message
+    pub labels: Vec<String>,
+    /// Create missing labels instead of skipping them
+    pub create_labels: bool,
+    /// Restore labels from `X-Gmail-Labels` headers
+    pub archive_labels: bool,
+    /// Use messages.insert (no spam scanning or inbox classification) instead of messages.import
+    pub insert: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedMessage {
+    /// File name, or `<file>#<n>` for the n-th message of an mbox
+    pub source: String,
+    pub id: String,
+    pub thread_id: String,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub imported: usize,
+    pub failed: usize,
+    pub messages: Vec<ImportedMessage>,
+    /// Archive labels that do not exist in the mailbox (pass --create-labels to create them)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown_labels: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Import an `.eml` file, an mbox file or a directory of `.eml` files
+///
+/// Dates come from each message's Date header. Labels are restored from
+/// `X-Gmail-Labels` (written by `export` and Google Takeout) plus `labels`.
+pub async fn import(client: &ApiClient, path: &Path, options: &ImportOptions) -> Result<ImportResult> {
+    let messages = read_archive(path)?;
+
+    let mut resolver = LabelResolver::load(client).await?.with_create_missing(options.create_labels);
+    let extra_labels = resolver.resolve_all(client, &options.labels).await?;
+
+    let endpoint = if options.insert {
+        "/users/me/messages/insert?internalDateSource=dateHeader"
+    } else {
+        "/users/me/messages/import?internalDateSource=dateHeader&neverMarkSpam=true"
+    };
+
+    let mut result = ImportResult::default();
+    let total = messages.len();
+    for (current, (source, data)) in messages.into_iter().enumerate() {
+        let (archive_labels, data) = take_header(&data, LABELS_HEADER);
+
+        let mut label_ids = extra_labels.clone();
+        if options.archive_labels {
+            for name in archive_labels.iter().flat_map(|v| v.split(',')).map(str::trim) {
+                if name.is_empty() || UNIMPORTABLE_LABELS.contains(&name) {
+                    continue;
+                }
+                match resolver.resolve(client, name).await {
+                    Ok(id) if !label_ids.contains(&id) => label_ids.push(id),
+                    Ok(_) => {}
+                    Err(WorkspaceError::NotFound(_)) => {
+                        if !result.unknown_labels.iter().any(|l| l == name) {
+                            result.unknown_labels.push(name.to_string());
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        let request = serde_json::json!({
+            "raw": encode_base64url(&data),
+            "labelIds": label_ids,
+        });
+        let status = match client.post::<MessageRef, _>(endpoint, &request).await {
+            Ok(message) => {
+                result.imported += 1;
+                result.messages.push(ImportedMessage {
+                    source: source.clone(),
+                    id: message.id,
+                    thread_id: message.thread_id,
+                });
+                "imported"
+            }
+            Err(e) => {
+                result.failed += 1;
+                result.errors.push(format!("{}: {}", source, e));
+                "failed"
+            }
+        };
+
+        progress::emit(ProgressEvent::Item {
+            operation: "import",
+            current: current + 1,
+            total,
+            name: source,
+            status: status.to_string(),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Read messages from an `.eml` file, an mbox file or a directory of `.eml` files
+fn read_archive(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("eml")))
+            .collect();
+        files.sort();
+        return files.into_iter()
+            .map(|file| {
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                Ok((name, std::fs::read(&file)?))
+            })
+            .collect();
+    }
+
+    let data = std::fs::read(path).map_err(|e| {
+        WorkspaceError::InvalidInput(format!("Failed to read '{}': {}", path.display(), e))
+    })?;
+    if data.starts_with(b"From ") {
+        Ok(split_mbox(&data).into_iter()
+            .enumerate()
+            .map(|(i, message)| (format!("{}#{}", name, i + 1), message))
+            .collect())
+    } else {
+        Ok(vec![(name, data)])
+    }
+}
+
+/// Format one message as an mboxrd entry
+///
+/// Lines matching `>*From ` get one more `>` so they cannot be mistaken for
+/// a message separator; line endings become LF.
+pub fn mbox_entry(message: &[u8], date: Option<chrono::DateTime<chrono::Utc>>) -> Vec<u8> {
+    let date = date.unwrap_or_else(chrono::Utc::now);
+    let mut entry = format!("From MAILER-DAEMON {}\n", date.format("%a %b %e %H:%M:%S %Y")).into_bytes();
+
+    let mut lines: Vec<&[u8]> = message.split(|b| *b == b'\n').collect();
+    if lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    for line in lines {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let quotes = line.iter().take_while(|b| **b == b'>').count();
+        if line[quotes..].starts_with(b"From ") {
+            entry.push(b'>');
+        }
+        entry.extend_from_slice(line);
+        entry.push(b'\n');
+    }
+    entry.push(b'\n');
+    entry
+}
+
+/// Split an mboxrd file into messages (CRLF line endings), undoing `>From ` quoting
+pub fn split_mbox(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<&[u8]>> = None;
+    let mut previous_blank = true;
+
+    for line in data.split(|b| *b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if previous_blank && line.starts_with(b"From ") {
+            if let Some(lines) = current.take() {
+                messages.push(join_message(lines));
+            }
+            current = Some(Vec::new());
+            previous_blank = false;
+            continue;
+        }
+        previous_blank = line.is_empty();
+
+        if let Some(ref mut lines) = current {
+            let quotes = line.iter().take_while(|b| **b == b'>').count();
+            if quotes > 0 && line[quotes..].starts_with(b"From ") {
+                lines.push(&line[1..]);
+            } else {
+                lines.push(line);
+            }
+        }
+    }
+    if let Some(lines) = current {
+        messages.push(join_message(lines));
+    }
+    messages
+}
+
+fn join_message(mut lines: Vec<&[u8]>) -> Vec<u8> {
+    // Drop the blank separator line(s) before the next "From " line
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    let mut message = lines.join(&b"\r\n"[..]);
+    message.extend_from_slice(b"\r\n");
+    message
+}
+
+/// Prepend a header to a message, matching its line endings
+fn add_header(message: &[u8], name: &str, value: &str) -> Vec<u8> {
+    let eol: &[u8] = if message.windows(2).any(|w| w == b"\r\n") { b"\r\n" } else { b"\n" };
+    let (_, message) = take_header(message, name);
+    let mut data = format!("{}: {}", name, value).into_bytes();
+    data.extend_from_slice(eol);
+    data.extend_from_slice(&message);
+    data
+}
+
+/// Remove a header (with continuation lines) and return its unfolded value
+fn take_header(message: &[u8], name: &str) -> (Option<String>, Vec<u8>) {
+    let mut value: Option<String> = None;
+    let mut output = Vec::with_capacity(message.len());
+    let mut in_headers = true;
+    let mut skipping = false;
+
+    for line in message.split_inclusive(|b| *b == b'\n') {
+        if in_headers {
+            let content = line.strip_suffix(b"\n").unwrap_or(line);
+            let content = content.strip_suffix(b"\r").unwrap_or(content);
+            if content.is_empty() {
+                in_headers = false;
+            } else if skipping && (content[0] == b' ' || content[0] == b'\t') {
+                if let Some(ref mut v) = value {
+                    v.push(' ');
+                    v.push_str(String::from_utf8_lossy(content).trim());
+                }
+                continue;
+            } else {
+                skipping = false;
+                let text = String::from_utf8_lossy(content);
+                if let Some((header, rest)) = text.split_once(':') {
+                    if header.trim().eq_ignore_ascii_case(name) {
+                        if value.is_none() {
+                            value = Some(rest.trim().to_string());
+                        }
+                        skipping = true;
+                        continue;
+                    }
+                }
+            }
+        }
+        output.extend_from_slice(line);
+    }
+
+    (value, output)
+}
+
+/// Unfolded value of a header in an RFC 2822 message
+fn header_value(message: &[u8], name: &str) -> Option<String> {
+    take_header(message, name).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mbox_round_trip_quotes_from_lines() {
+        let first = b"Subject: One\r\n\r\nFrom here on\r\n>From quoted\r\n".to_vec();
+        let second = b"Subject: Two\r\n\r\nBody\r\n".to_vec();
+
+        let mut mbox = mbox_entry(&first, None);
+        mbox.extend(mbox_entry(&second, None));
+        assert!(mbox.windows(14).any(|w| w == b"\n>From here on"));
+        assert!(mbox.windows(15).any(|w| w == b"\n>>From quoted\n"));
+
+        let messages = split_mbox(&mbox);
+        assert_eq!(messages, vec![first, second]);
+    }
+
+    #[test]
+    fn test_labels_header_round_trip() {
+        let message = b"From: a@example.com\r\nSubject: Hi\r\n\r\nX-Gmail-Labels: not a header\r\n";
+        let with_labels = add_header(message, LABELS_HEADER, "INBOX,Work/Clients");
+        assert_eq!(header_value(&with_labels, "subject").as_deref(), Some("Hi"));
+
+        let (labels, original) = take_header(&with_labels, LABELS_HEADER);
+        assert_eq!(labels.as_deref(), Some("INBOX,Work/Clients"));
+        assert_eq!(original, message.to_vec());
+    }
+}
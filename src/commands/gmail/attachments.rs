@@ -0,0 +1,365 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use futures::stream::{self, StreamExt};
+use sha2::{Digest, Sha256};
+
+use crate::client::ApiClient;
+use crate::commands::drive::upload_bytes;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use super::bulk::collect_message_ids;
+use super::get::{download_attachment, get_header, get_message, list_attachments, AttachmentInfo};
+use super::types::Message;
+
+/// Placeholders accepted in a filename template
+pub const TEMPLATE_FIELDS: &[&str] = &["date", "from", "subject", "filename", "messageId", "hash"];
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Where pulled attachments go
+pub enum PullTarget {
+    /// Write files into a local directory (created if needed)
+    Dir(PathBuf),
+    /// Upload files into a Drive folder; the manifest is written to `manifest_dir` if set
+    Drive {
+        folder_id: String,
+        access_token: String,
+        manifest_dir: Option<PathBuf>,
+    },
+}
+
+/// Options for [`pull`]
+pub struct PullOptions {
+    pub query: String,
+    pub target: PullTarget,
+    /// File name template, e.g. `{date}_{from}_{filename}`
+    pub template: String,
+    /// Stop after this many messages
+    pub max: Option<usize>,
+    /// Messages fetched in parallel
+    pub concurrency: usize,
+}
+
+/// One stored file and the messages it was attached to
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// File name in the directory or Drive folder
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_file_id: Option<String>,
+    pub sha256: String,
+    pub size: usize,
+    pub mime_type: String,
+    /// Original attachment file name
+    pub filename: String,
+    /// Every message carrying this content
+    pub message_ids: Vec<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    files: Vec<ManifestEntry>,
+}
+
+/// Result of [`pull`]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullResult {
+    pub query: String,
+    pub messages: usize,
+    /// More messages matched than `max` allowed
+    pub capped: bool,
+    /// Attachments seen across all messages
+    pub attachments: usize,
+    /// New files written or uploaded
+    pub saved: usize,
+    /// Attachments skipped because the same content was already stored
+    pub duplicates: usize,
+    pub failed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<String>,
+    pub files: Vec<ManifestEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// An attachment downloaded along with the message fields used for naming
+struct Fetched {
+    message_id: String,
+    fields: HashMap<&'static str, String>,
+    info: AttachmentInfo,
+    content: Result<Vec<u8>>,
+}
+
+/// Download every attachment of the messages matching `query`
+///
+/// Messages are fetched `concurrency` at a time (requests still go through
+/// the Gmail rate limiter) and their attachments stored in search order.
+/// Content is deduplicated by SHA-256, including against an existing
+/// manifest in the target directory, so re-running a pull only stores new
+/// files. Name clashes get a ` (2)`, ` (3)`, ... suffix.
+pub async fn pull(client: &ApiClient, options: &PullOptions) -> Result<PullResult> {
+    validate_template(&options.template)?;
+
+    let manifest_dir = match options.target {
+        PullTarget::Dir(ref dir) => Some(dir.clone()),
+        PullTarget::Drive { ref manifest_dir, .. } => manifest_dir.clone(),
+    };
+    if let Some(ref dir) = manifest_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    let manifest_path = manifest_dir.as_ref().map(|d| d.join(MANIFEST_FILE));
+    let mut manifest: Manifest = match manifest_path {
+        Some(ref path) if path.exists() => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        _ => Manifest::default(),
+    };
+
+    let (ids, capped) = collect_message_ids(client, &options.query, options.max.unwrap_or(usize::MAX)).await?;
+    let mut by_hash: HashMap<String, usize> = manifest.files.iter()
+        .enumerate()
+        .map(|(index, entry)| (entry.sha256.clone(), index))
+        .collect();
+    let mut names: HashSet<String> = manifest.files.iter().map(|e| e.file.to_lowercase()).collect();
+
+    let mut result = PullResult {
+        query: options.query.clone(),
+        messages: ids.len(),
+        capped,
+        attachments: 0,
+        saved: 0,
+        duplicates: 0,
+        failed: 0,
+        manifest: manifest_path.as_ref().map(|p| p.display().to_string()),
+        files: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let mut fetches = stream::iter(&ids)
+        .map(|id| fetch_attachments(client, id))
+        .buffered(options.concurrency.max(1));
+
+    let mut current = 0;
+    while let Some(fetched) = fetches.next().await {
+        current += 1;
+        let fetched = match fetched {
+            Ok(fetched) => fetched,
+            Err((id, e)) => {
+                result.failed += 1;
+                result.errors.push(format!("{}: {}", id, e));
+                progress::emit(ProgressEvent::Item {
+                    operation: "pull",
+                    current,
+                    total: ids.len(),
+                    name: id,
+                    status: "failed".to_string(),
+                });
+                continue;
+            }
+        };
+
+        for attachment in fetched {
+            result.attachments += 1;
+            let content = match attachment.content {
+                Ok(content) => content,
+                Err(e) => {
+                    result.failed += 1;
+                    result.errors.push(format!("{} {}: {}", attachment.message_id, attachment.info.filename, e));
+                    continue;
+                }
+            };
+
+            let hash = sha256_hex(&content);
+            if let Some(&index) = by_hash.get(&hash) {
+                let entry = &mut manifest.files[index];
+                if !entry.message_ids.contains(&attachment.message_id) {
+                    entry.message_ids.push(attachment.message_id.clone());
+                }
+                result.duplicates += 1;
+                continue;
+            }
+
+            let mut fields = attachment.fields;
+            fields.insert("filename", attachment.info.filename.clone());
+            fields.insert("hash", hash[..12].to_string());
+            let local_dir = match options.target {
+                PullTarget::Dir(ref dir) => Some(dir.as_path()),
+                PullTarget::Drive { .. } => None,
+            };
+            let file = unique_name(&render_name(&options.template, &fields), &mut names, local_dir);
+
+            let size = content.len();
+            let stored = match options.target {
+                PullTarget::Dir(ref dir) => std::fs::write(dir.join(&file), &content)
+                    .map(|_| None)
+                    .map_err(WorkspaceError::from),
+                PullTarget::Drive { ref folder_id, ref access_token, .. } => {
+                    upload_bytes(access_token, &file, &attachment.info.mime_type, content, Some(folder_id.clone()))
+                        .await
+                        .map(|f| Some(f.id))
+                }
+            };
+            let drive_file_id = match stored {
+                Ok(id) => id,
+                Err(e) => {
+                    result.failed += 1;
+                    result.errors.push(format!("{} {}: {}", attachment.message_id, file, e));
+                    continue;
+                }
+            };
+
+            let entry = ManifestEntry {
+                file: file.clone(),
+                drive_file_id,
+                sha256: hash.clone(),
+                size,
+                mime_type: attachment.info.mime_type,
+                filename: attachment.info.filename,
+                message_ids: vec![attachment.message_id],
+            };
+            by_hash.insert(hash, manifest.files.len());
+            manifest.files.push(entry.clone());
+            result.files.push(entry);
+            result.saved += 1;
+        }
+
+        progress::emit(ProgressEvent::Item {
+            operation: "pull",
+            current,
+            total: ids.len(),
+            name: ids[current - 1].clone(),
+            status: "done".to_string(),
+        });
+    }
+
+    if let Some(ref path) = manifest_path {
+        std::fs::write(path, serde_json::to_string_pretty(&manifest)?)?;
+    }
+    Ok(result)
+}
+
+/// Fetch a message and download all of its attachments
+async fn fetch_attachments(client: &ApiClient, id: &str) -> std::result::Result<Vec<Fetched>, (String, WorkspaceError)> {
+    let message = get_message(client, id, "full").await.map_err(|e| (id.to_string(), e))?;
+    let fields = message_fields(&message);
+
+    let mut fetched = Vec::new();
+    for info in list_attachments(&message) {
+        let content = download_attachment(client, &message.id, &info.attachment_id).await;
+        fetched.push(Fetched {
+            message_id: message.id.clone(),
+            fields: fields.clone(),
+            info,
+            content,
+        });
+    }
+    Ok(fetched)
+}
+
+/// Template fields taken from the message: date, from, subject, messageId
+fn message_fields(message: &Message) -> HashMap<&'static str, String> {
+    let date = message.internal_date.as_deref()
+        .and_then(|ms| ms.parse::<i64>().ok())
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let from = get_header(message, "From")
+        .map(|from| match (from.find('<'), from.rfind('>')) {
+            (Some(start), Some(end)) if start < end => from[start + 1..end].to_string(),
+            _ => from,
+        })
+        .unwrap_or_default();
+
+    HashMap::from([
+        ("date", date),
+        ("from", from),
+        ("subject", get_header(message, "Subject").unwrap_or_default()),
+        ("messageId", message.id.clone()),
+    ])
+}
+
+fn validate_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else { break };
+        let name = &rest[start + 1..start + end];
+        if !TEMPLATE_FIELDS.contains(&name) {
+            return Err(WorkspaceError::InvalidInput(format!(
+                "Unknown template field '{{{}}}' (available: {})",
+                name,
+                TEMPLATE_FIELDS.iter().map(|f| format!("{{{}}}", f)).collect::<Vec<_>>().join(", ")
+            )));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// Fill in a filename template; each value is made safe for use in a file name
+fn render_name(template: &str, fields: &HashMap<&'static str, String>) -> String {
+    let mut name = template.to_string();
+    for field in TEMPLATE_FIELDS {
+        if let Some(value) = fields.get(field) {
+            name = name.replace(&format!("{{{}}}", field), &sanitize_filename(value));
+        }
+    }
+    let name = sanitize_filename(&name);
+    if name.is_empty() { "attachment".to_string() } else { name }
+}
+
+fn sanitize_filename(value: &str) -> String {
+    let cleaned: String = value.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    cleaned.trim().trim_start_matches('.').chars().take(200).collect()
+}
+
+/// Pick a name not used yet in this pull, the manifest or the directory
+fn unique_name(name: &str, taken: &mut HashSet<String>, dir: Option<&Path>) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    };
+
+    let mut candidate = name.to_string();
+    let mut counter = 1;
+    while taken.contains(&candidate.to_lowercase()) || dir.is_some_and(|d| d.join(&candidate).exists()) {
+        counter += 1;
+        candidate = format!("{} ({}){}", stem, counter, extension);
+    }
+    taken.insert(candidate.to_lowercase());
+    candidate
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_name_and_uniqueness() {
+        let fields = HashMap::from([
+            ("date", "2026-03-01".to_string()),
+            ("from", "invoices@vendor.com".to_string()),
+            ("filename", "March/Invoice.pdf".to_string()),
+        ]);
+        let name = render_name("{date}_{from}_{filename}", &fields);
+        assert_eq!(name, "2026-03-01_invoices@vendor.com_March_Invoice.pdf");
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name("a.pdf", &mut taken, None), "a.pdf");
+        assert_eq!(unique_name("A.pdf", &mut taken, None), "A (2).pdf");
+        assert_eq!(unique_name("a.pdf", &mut taken, None), "a (3).pdf");
+
+        assert!(validate_template("{date}-{filename}").is_ok());
+        assert!(validate_template("{sender}").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::client::ApiClient;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use super::delete::batch_delete;
+use super::labels::ModifyLabelsRequest;
+use super::list::{list_messages, ListParams};
+
+/// Maximum number of IDs accepted by batchModify and batchDelete
+pub const BATCH_LIMIT: usize = 1000;
+
+/// Largest page size accepted by messages.list
+const LIST_PAGE_SIZE: u32 = 500;
+
+/// Operation applied to every message matching a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    ModifyLabels { add: Vec<String>, remove: Vec<String> },
+    Archive,
+    MarkRead,
+    Trash,
+    Delete,
+}
+
+impl BulkAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkAction::ModifyLabels { .. } => "modify-labels",
+            BulkAction::Archive => "archive",
+            BulkAction::MarkRead => "mark-read",
+            BulkAction::Trash => "trash",
+            BulkAction::Delete => "delete",
+        }
+    }
+
+    /// Label changes for a batchModify call, or None for batchDelete
+    pub fn label_changes(&self) -> Option<ModifyLabelsRequest> {
+        let (add, remove) = match self {
+            BulkAction::ModifyLabels { add, remove } => (add.clone(), remove.clone()),
+            BulkAction::Archive => (vec![], vec!["INBOX".to_string()]),
+            BulkAction::MarkRead => (vec![], vec!["UNREAD".to_string()]),
+            BulkAction::Trash => (vec!["TRASH".to_string()], vec![]),
+            BulkAction::Delete => return None,
+        };
+        Some(ModifyLabelsRequest {
+            add_label_ids: add,
+            remove_label_ids: remove,
+        })
+    }
+}
+
+/// Result of a bulk operation
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkResult {
+    pub action: String,
+    pub query: String,
+    /// Messages selected (at most `max`)
+    pub matched: usize,
+    /// More messages matched than `--max` allowed; only the first `matched` were selected
+    pub capped: bool,
+    pub dry_run: bool,
+    /// Messages changed (0 for a dry run)
+    pub affected: usize,
+    /// Labels a dry run with --create-labels would create
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels_to_create: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Collect IDs of messages matching `query`, stopping after `max`
+///
+/// Returns the IDs and whether more messages matched than were collected.
+pub async fn collect_message_ids(client: &ApiClient, query: &str, max: usize) -> Result<(Vec<String>, bool)> {
+    let mut ids = Vec::new();
+    let mut page_token = None;
+    let mut page = 0;
+
+    loop {
+        let params = ListParams {
+            query: Some(query.to_string()),
+            max_results: LIST_PAGE_SIZE,
+            label_ids: None,
+            page_token,
+        };
+        let response = list_messages(client, params).await?;
+        page += 1;
+        progress::emit(ProgressEvent::Page {
+            page,
+            items: response.messages.len(),
+            has_more: response.next_page_token.is_some(),
+        });
+
+        ids.extend(response.messages.into_iter().map(|m| m.id));
+        if ids.len() > max {
+            ids.truncate(max);
+            return Ok((ids, true));
+        }
+
+        page_token = response.next_page_token;
+        if page_token.is_none() {
+            return Ok((ids, false));
+        }
+        if ids.len() == max {
+            // Another page exists, so the cap was hit
+            return Ok((ids, true));
+        }
+    }
+}
+
+/// Request body for messages.batchModify
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchModifyRequest<'a> {
+    ids: &'a [String],
+    #[serde(flatten)]
+    changes: &'a ModifyLabelsRequest,
+}
+
+/// Apply label changes to up to 1000 messages
+pub async fn batch_modify(client: &ApiClient, message_ids: &[String], changes: &ModifyLabelsRequest) -> Result<()> {
+    let request = BatchModifyRequest { ids: message_ids, changes };
+    let _: serde_json::Value = client.post("/users/me/messages/batchModify", &request).await?;
+    Ok(())
+}
+
+/// Apply `action` to every message matching `query`, up to `max` messages
+///
+/// Messages are processed in chunks of [`BATCH_LIMIT`]. A failing chunk is
+/// recorded in `errors` and the remaining chunks still run.
+pub async fn bulk(
+    client: &ApiClient,
+    action: &BulkAction,
+    query: &str,
+    max: usize,
+    dry_run: bool,
+) -> Result<BulkResult> {
+    if query.trim().is_empty() {
+        return Err(WorkspaceError::InvalidInput(
+            "A non-empty --query is required for bulk operations".to_string(),
+        ));
+    }
+    let changes = action.label_changes();
+    if let Some(ref changes) = changes {
+        if changes.add_label_ids.is_empty() && changes.remove_label_ids.is_empty() {
+            return Err(WorkspaceError::InvalidInput(
+                "modify-labels needs --add-labels or --remove-labels".to_string(),
+            ));
+        }
+    }
+
+    let (ids, capped) = collect_message_ids(client, query, max).await?;
+    let mut result = BulkResult {
+        action: action.name().to_string(),
+        query: query.to_string(),
+        matched: ids.len(),
+        capped,
+        dry_run,
+        affected: 0,
+        labels_to_create: Vec::new(),
+        errors: Vec::new(),
+    };
+    if dry_run {
+        return Ok(result);
+    }
+
+    for chunk in ids.chunks(BATCH_LIMIT) {
+        let outcome = match changes {
+            Some(ref changes) => batch_modify(client, chunk, changes).await,
+            None => batch_delete(client, chunk).await,
+        };
+        match outcome {
+            Ok(()) => result.affected += chunk.len(),
+            // Every later chunk would fail the same way; surface the login hint instead
+            Err(e) if is_permission_error(&e) => return Err(e),
+            Err(e) => result.errors.push(e.to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
+/// A 403 for the whole call, such as batchDelete without the full mail scope
+fn is_permission_error(err: &WorkspaceError) -> bool {
+    matches!(err, WorkspaceError::Api(api) if api.code == 403
+        && !api.message.to_lowercase().contains("rate limit")
+        && !api.message.to_lowercase().contains("quota"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_label_changes() {
+        let archive = BulkAction::Archive.label_changes().unwrap();
+        assert_eq!(archive.remove_label_ids, vec!["INBOX"]);
+        assert!(archive.add_label_ids.is_empty());
+
+        let trash = BulkAction::Trash.label_changes().unwrap();
+        assert_eq!(trash.add_label_ids, vec!["TRASH"]);
+
+        let request = serde_json::to_value(BulkAction::MarkRead.label_changes().unwrap()).unwrap();
+        assert_eq!(request["removeLabelIds"], serde_json::json!(["UNREAD"]));
+    }
+
+    #[test]
+    fn test_delete_uses_batch_delete() {
+        assert!(BulkAction::Delete.label_changes().is_none());
+
+        let api_error = |code: u16, message: &str| WorkspaceError::Api(crate::error::ApiError {
+            code,
+            message: message.to_string(),
+            domain: "gmail".to_string(),
+            retry_after: None,
+        });
+        assert!(is_permission_error(&api_error(403, "Request had insufficient authentication scopes.")));
+        assert!(!is_permission_error(&api_error(403, "User-rate limit exceeded")));
+        assert!(!is_permission_error(&api_error(500, "Backend error")));
+    }
+}
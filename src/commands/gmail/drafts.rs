@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use crate::client::ApiClient;
+use crate::error::Result;
+use crate::utils::base64::encode_base64url_string;
+use super::get::{decode_part_body, download_attachment, find_text_part, get_header, get_raw_header, minimal_message};
+use super::list::enrich_messages;
+use super::send::{build_raw_email, Attachment, ComposeParams, InlineImage};
+use super::types::{Draft, DraftDetail, DraftList, DraftRef, DraftSummary, ListDraftsResponse, Message, MessagePart, MessageRef, MessageSummary};
+
+/// List drafts with Subject/From/Date of each draft message
+pub async fn list_drafts(
+    client: &ApiClient,
+    query: Option<&str>,
+    max_results: u32,
+    access_token: &str,
+) -> Result<DraftList> {
+    let mut query_params = vec![("maxResults", max_results.to_string())];
+    if let Some(q) = query {
+        query_params.push(("q", q.to_string()));
+    }
+
+    let response: ListDraftsResponse = client.get_with_query("/users/me/drafts", &query_params).await?;
+    let refs: Vec<MessageRef> = response.drafts.iter().map(|d| d.message.clone()).collect();
+    let summaries = enrich_messages(refs, access_token).await?;
+
+    Ok(DraftList {
+        drafts: pair_drafts(response.drafts, summaries),
+        next_page_token: response.next_page_token,
+    })
+}
+
+/// Match each draft to its message summary by message ID
+///
+/// Batch responses are not guaranteed to keep request order, and failed
+/// parts are missing; such drafts keep only their message and thread IDs.
+fn pair_drafts(drafts: Vec<DraftRef>, summaries: Vec<MessageSummary>) -> Vec<DraftSummary> {
+    let mut by_id: HashMap<String, MessageSummary> = summaries.into_iter()
+        .map(|summary| (summary.id.clone(), summary))
+        .collect();
+
+    drafts.into_iter()
+        .map(|draft| {
+            let message = by_id.remove(&draft.message.id).unwrap_or(MessageSummary {
+                id: draft.message.id,
+                thread_id: draft.message.thread_id,
+                subject: None,
+                from: None,
+                date: None,
+                snippet: None,
+            });
+            DraftSummary { id: draft.id, message }
+        })
+        .collect()
+}
+
+/// Get a draft with its message in `full` format
+pub async fn get_draft(client: &ApiClient, id: &str) -> Result<Draft> {
+    let query = [("format", "full")];
+    client.get_with_query(&format!("/users/me/drafts/{}", urlencoding::encode(id)), &query).await
+}
+
+/// Get a draft decoded like `get_message_minimal`, plus its Cc/Bcc
+pub async fn get_draft_minimal(client: &ApiClient, id: &str) -> Result<DraftDetail> {
+    let draft = get_draft(client, id).await?;
+    Ok(DraftDetail {
+        id: draft.id,
+        cc: get_header(&draft.message, "Cc"),
+        bcc: get_header(&draft.message, "Bcc"),
+        message: minimal_message(draft.message),
+    })
+}
+
+/// Changes to apply to a draft; unset fields keep their current value
+#[derive(Default)]
+pub struct DraftUpdate {
+    pub to: Option<String>,
+    pub cc: Option<String>,
+    pub bcc: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub is_html: bool,
+    /// Attachments added to the existing ones
+    pub attachments: Vec<Attachment>,
+    /// Drop the draft's current attachments
+    pub clear_attachments: bool,
+}
+
+/// Replace a draft's content
+///
+/// The message is rebuilt from the current draft with `update` applied.
+/// From, In-Reply-To, References and the thread ID are preserved so reply
+/// drafts stay in their conversation, and existing attachments are carried
+/// over unless `clear_attachments` is set. Images embedded in the HTML body
+/// keep their Content-ID.
+pub async fn update_draft(client: &ApiClient, id: &str, update: DraftUpdate) -> Result<serde_json::Value> {
+    let draft = get_draft(client, id).await?;
+
+    let mut attachments = Vec::new();
+    let mut inline_images = Vec::new();
+    for part in draft_parts(&draft.message) {
+        if part.cid.is_none() && update.clear_attachments {
+            continue;
+        }
+        let content = download_attachment(client, &draft.message.id, &part.attachment_id).await?;
+        match part.cid {
+            Some(cid) => inline_images.push(InlineImage { cid, filename: part.filename, content, mime_type: part.mime_type }),
+            None => attachments.push(Attachment { filename: part.filename, content, mime_type: part.mime_type }),
+        }
+    }
+
+    let params = compose_from_draft(&draft.message, update, attachments, inline_images);
+    let request = serde_json::json!({
+        "id": draft.id,
+        "message": {
+            "raw": encode_base64url_string(&build_raw_email(&params)),
+            "threadId": draft.message.thread_id,
+        }
+    });
+    client.put(&format!("/users/me/drafts/{}", urlencoding::encode(id)), &request).await
+}
+
+/// Send an existing draft
+pub async fn send_draft(client: &ApiClient, id: &str) -> Result<Message> {
+    let request = serde_json::json!({ "id": id });
+    client.post("/users/me/drafts/send", &request).await
+}
+
+/// Delete a draft permanently
+pub async fn delete_draft(client: &ApiClient, id: &str) -> Result<()> {
+    client.delete(&format!("/users/me/drafts/{}", urlencoding::encode(id))).await
+}
+
+/// A stored part of a draft: an attachment, or an image embedded in the HTML body
+struct DraftPart {
+    attachment_id: String,
+    filename: String,
+    mime_type: String,
+    /// Content-ID (without angle brackets) for embedded images
+    cid: Option<String>,
+}
+
+/// Parts of a draft stored as attachments, with embedded images told apart
+fn draft_parts(message: &Message) -> Vec<DraftPart> {
+    let mut parts = Vec::new();
+    if let Some(ref payload) = message.payload {
+        collect_draft_parts(&payload.parts, false, &mut parts);
+    }
+    parts
+}
+
+fn collect_draft_parts(parts: &[MessagePart], in_related: bool, out: &mut Vec<DraftPart>) {
+    for part in parts {
+        let header = |name: &str| part.headers.iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.trim().to_string());
+
+        if let Some(attachment_id) = part.body.as_ref().and_then(|b| b.attachment_id.clone()) {
+            let filename = part.filename.clone().filter(|f| !f.is_empty()).unwrap_or_else(|| "unnamed".to_string());
+            let is_attachment = header("Content-Disposition")
+                .is_some_and(|d| d.to_ascii_lowercase().starts_with("attachment"));
+            // Embedded images carry a Content-ID, or at least sit inside multipart/related
+            let cid = match header("Content-ID") {
+                Some(id) => Some(id.trim_start_matches('<').trim_end_matches('>').to_string()),
+                None if in_related && !is_attachment => Some(header("X-Attachment-Id").unwrap_or_else(|| filename.clone())),
+                None => None,
+            };
+            out.push(DraftPart {
+                attachment_id,
+                filename,
+                mime_type: part.mime_type.clone().unwrap_or_else(|| "application/octet-stream".to_string()),
+                cid,
+            });
+        }
+
+        let related = part.mime_type.as_deref().is_some_and(|m| m.eq_ignore_ascii_case("multipart/related"));
+        collect_draft_parts(&part.parts, related, out);
+    }
+}
+
+/// Merge the current draft message with requested changes
+///
+/// Embedded images are kept only while the HTML body still refers to them.
+fn compose_from_draft(
+    message: &Message,
+    update: DraftUpdate,
+    existing_attachments: Vec<Attachment>,
+    inline_images: Vec<InlineImage>,
+) -> ComposeParams {
+    // Address headers are copied verbatim; the Subject is re-encoded on build
+    let header = |name: &str| get_raw_header(message, name);
+
+    let (body, is_html) = match update.body {
+        Some(body) => (body, update.is_html),
+        None => {
+            let (body, was_html) = draft_body(message).unwrap_or_default();
+            (body, was_html || update.is_html)
+        }
+    };
+
+    let mut attachments = existing_attachments;
+    attachments.extend(update.attachments);
+    let inline_images = inline_images.into_iter()
+        .filter(|image| is_html && body.contains(&format!("cid:{}", image.cid)))
+        .collect();
+
+    ComposeParams {
+        to: update.to.or_else(|| header("To")).unwrap_or_default(),
+        subject: update.subject.or_else(|| get_header(message, "Subject")).unwrap_or_default(),
+        body,
+        from: header("From"),
+        cc: update.cc.or_else(|| header("Cc")),
+        bcc: update.bcc.or_else(|| header("Bcc")),
+        in_reply_to: header("In-Reply-To"),
+        references: header("References"),
+        thread_id: Some(message.thread_id.clone()),
+        is_html,
+        text_body: None,
+        inline_images,
+        attachments,
+    }
+}
+
+/// Decoded body of a draft and whether it is HTML
+fn draft_body(message: &Message) -> Option<(String, bool)> {
+    let payload = message.payload.as_ref()?;
+    if let Some(data) = payload.body.as_ref().and_then(|b| b.data.as_deref()).filter(|d| !d.is_empty()) {
+        let is_html = payload.mime_type.as_deref() == Some("text/html");
+        return decode_part_body(data, &payload.headers).map(|body| (body, is_html));
+    }
+
+    // Prefer HTML: the plain-text alternative is regenerated when the draft is rebuilt
+    find_text_part(&payload.parts, "text/html").map(|body| (body, true))
+        .or_else(|| find_text_part(&payload.parts, "text/plain").map(|body| (body, false)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compose_from_draft_keeps_threading_headers() {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "id": "m1",
+            "threadId": "t1",
+            "payload": {
+                "mimeType": "text/html",
+                "headers": [
+                    {"name": "To", "value": "alice@example.com"},
+                    {"name": "Subject", "value": "Re: Budget"},
+                    {"name": "In-Reply-To", "value": "<abc@mail>"},
+                    {"name": "References", "value": "<root@mail> <abc@mail>"}
+                ],
+                "body": {"size": 11, "data": encode_base64url_string("<p>Old</p>")}
+            }
+        }))
+        .unwrap();
+
+        let update = DraftUpdate {
+            subject: Some("Re: Budget v2".to_string()),
+            ..Default::default()
+        };
+        let params = compose_from_draft(&message, update, Vec::new(), Vec::new());
+
+        assert_eq!(params.to, "alice@example.com");
+        assert_eq!(params.subject, "Re: Budget v2");
+        assert_eq!(params.in_reply_to.as_deref(), Some("<abc@mail>"));
+        assert_eq!(params.references.as_deref(), Some("<root@mail> <abc@mail>"));
+        assert_eq!(params.thread_id.as_deref(), Some("t1"));
+        assert_eq!(params.body, "<p>Old</p>");
+        assert!(params.is_html);
+    }
+
+    #[test]
+    fn test_draft_parts_keep_inline_images() {
+        let html = "<p>Logo: <img src=\"cid:logo\"></p>";
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "id": "m1",
+            "threadId": "t1",
+            "payload": {
+                "mimeType": "multipart/mixed",
+                "headers": [{"name": "Subject", "value": "Launch"}],
+                "parts": [
+                    {
+                        "mimeType": "multipart/alternative",
+                        "parts": [
+                            {"mimeType": "text/plain", "body": {"size": 5, "data": encode_base64url_string("Logo:")}},
+                            {
+                                "mimeType": "multipart/related",
+                                "parts": [
+                                    {"mimeType": "text/html", "body": {"size": 30, "data": encode_base64url_string(html)}},
+                                    {
+                                        "mimeType": "image/png",
+                                        "filename": "logo.png",
+                                        "headers": [
+                                            {"name": "Content-ID", "value": "<logo>"},
+                                            {"name": "Content-Disposition", "value": "inline; filename=\"logo.png\""}
+                                        ],
+                                        "body": {"size": 10, "attachmentId": "a1"}
+                                    },
+                                    {
+                                        "mimeType": "image/gif",
+                                        "filename": "spacer.gif",
+                                        "body": {"size": 10, "attachmentId": "a2"}
+                                    }
+                                ]
+                            }
+                        ]
+                    },
+                    {
+                        "mimeType": "application/pdf",
+                        "filename": "deck.pdf",
+                        "headers": [{"name": "Content-Disposition", "value": "attachment; filename=\"deck.pdf\""}],
+                        "body": {"size": 10, "attachmentId": "a3"}
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let parts = draft_parts(&message);
+        let cids: Vec<_> = parts.iter().map(|p| (p.attachment_id.as_str(), p.cid.as_deref())).collect();
+        assert_eq!(cids, vec![("a1", Some("logo")), ("a2", Some("spacer.gif")), ("a3", None)]);
+
+        let image = |cid: &str| InlineImage {
+            cid: cid.to_string(),
+            filename: format!("{}.png", cid),
+            content: vec![1, 2, 3],
+            mime_type: "image/png".to_string(),
+        };
+        let update = DraftUpdate { subject: Some("Launch v2".to_string()), ..Default::default() };
+        let params = compose_from_draft(&message, update, Vec::new(), vec![image("logo"), image("spacer.gif")]);
+
+        assert!(params.is_html);
+        assert_eq!(params.body, html);
+        let kept: Vec<_> = params.inline_images.iter().map(|i| i.cid.as_str()).collect();
+        assert_eq!(kept, vec!["logo"]);
+        assert!(params.attachments.is_empty());
+    }
+
+    #[test]
+    fn test_pair_drafts_matches_by_message_id() {
+        let draft = |id: &str, message: &str| DraftRef {
+            id: id.to_string(),
+            message: MessageRef { id: message.to_string(), thread_id: format!("t-{}", message) },
+        };
+        let summary = |id: &str, subject: &str| MessageSummary {
+            id: id.to_string(),
+            thread_id: format!("t-{}", id),
+            subject: Some(subject.to_string()),
+            from: None,
+            date: None,
+            snippet: None,
+        };
+
+        let drafts = vec![draft("d1", "m1"), draft("d2", "m2"), draft("d3", "m3")];
+        let summaries = vec![summary("m3", "Third"), summary("m1", "First")];
+        let paired = pair_drafts(drafts, summaries);
+
+        assert_eq!(paired.len(), 3);
+        assert_eq!((paired[0].id.as_str(), paired[0].message.subject.as_deref()), ("d1", Some("First")));
+        assert_eq!((paired[1].id.as_str(), paired[1].message.subject.as_deref()), ("d2", None));
+        assert_eq!(paired[1].message.thread_id, "t-m2");
+        assert_eq!((paired[2].id.as_str(), paired[2].message.subject.as_deref()), ("d3", Some("Third")));
+    }
+}
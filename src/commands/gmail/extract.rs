@@ -0,0 +1,794 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::commands::calendar::types::EventDateTime;
+use crate::error::{ApiError, Result, WorkspaceError};
+use crate::utils::charset::{content_type_param, decode_charset};
+use super::get::{decode_part_body, download_attachment, extract_body, find_text_part, get_message, get_raw_header, minimal_message};
+use super::send::{send_message, ComposeParams};
+use super::types::{Header, Message, MessagePart, MinimalMessage};
+
+/// Timeout for the one-click unsubscribe POST to the sender's server
+const UNSUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Redirects followed by the one-click unsubscribe POST
+const UNSUBSCRIBE_MAX_REDIRECTS: usize = 3;
+
+/// URL fragments of well-known open-tracking endpoints
+const TRACKER_PATTERNS: &[&str] = &[
+    "/track/open",
+    "/wf/open",
+    "/e/o/",
+    "/open.php",
+    "/open.aspx",
+    "/o.gif",
+    "/pixel",
+    "mailtrack.io",
+    "list-manage.com/track",
+    "mandrillapp.com/track",
+    "sendgrid.net/wf",
+    "hubspotlinks.com",
+    "beacon",
+];
+
+/// A message with the structured fields agents usually parse out of it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedMessage {
+    #[serde(flatten)]
+    pub message: MinimalMessage,
+    pub links: Vec<Link>,
+    pub unsubscribe: Option<Unsubscribe>,
+    pub calendar_events: Vec<CalendarInvite>,
+    pub authentication: Option<AuthenticationResults>,
+    pub tracking_pixels: Vec<TrackingPixel>,
+}
+
+/// A link in the message body
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    pub url: String,
+    /// Anchor text; absent for bare URLs in plain text
+    pub text: Option<String>,
+}
+
+/// Unsubscribe options from `List-Unsubscribe` / `List-Unsubscribe-Post`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unsubscribe {
+    pub http: Option<String>,
+    pub mailto: Option<String>,
+    /// RFC 8058 one-click: a POST to `http` unsubscribes without a browser
+    pub one_click: bool,
+}
+
+/// An event from a `text/calendar` part or `.ics` attachment
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarInvite {
+    /// iTIP method of the calendar: REQUEST, CANCEL, REPLY, ...
+    pub method: Option<String>,
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start: Option<EventDateTime>,
+    pub end: Option<EventDateTime>,
+    pub status: Option<String>,
+    pub sequence: Option<u32>,
+    pub organizer: Option<InviteAttendee>,
+    pub attendees: Vec<InviteAttendee>,
+    pub recurrence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteAttendee {
+    pub email: String,
+    pub name: Option<String>,
+    /// Participation status: NEEDS-ACTION, ACCEPTED, DECLINED, TENTATIVE
+    pub status: Option<String>,
+    pub role: Option<String>,
+}
+
+/// Sender authentication verdicts from the receiving server's `Authentication-Results`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationResults {
+    /// Server that performed the checks, e.g. mx.google.com
+    pub authserv_id: String,
+    pub spf: Option<AuthCheck>,
+    pub dkim: Vec<AuthCheck>,
+    pub dmarc: Option<AuthCheck>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthCheck {
+    /// pass, fail, softfail, neutral, none, temperror, permerror
+    pub result: String,
+    /// Domain the check was made for
+    pub domain: Option<String>,
+}
+
+/// An image that looks like an open-tracking pixel
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingPixel {
+    pub url: String,
+    /// tinyImage, hidden or knownTracker
+    pub reason: &'static str,
+}
+
+/// Outcome of `gmail unsubscribe`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsubscribeResult {
+    pub message_id: String,
+    /// oneClick or mailto
+    pub method: &'static str,
+    pub target: String,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_message_id: Option<String>,
+}
+
+/// Fetch a message and extract links, unsubscribe info, invites,
+/// authentication results and tracking pixels
+pub async fn extract_message(client: &ApiClient, id: &str) -> Result<ExtractedMessage> {
+    let message = get_message(client, id, "full").await?;
+
+    let mut calendars = Vec::new();
+    if let Some(payload) = &message.payload {
+        let root = MessagePart {
+            headers: payload.headers.clone(),
+            mime_type: payload.mime_type.clone(),
+            body: payload.body.clone(),
+            parts: payload.parts.clone(),
+            filename: None,
+        };
+        for part in calendar_parts(&root) {
+            let body = part.body.as_ref();
+            if let Some(data) = body.and_then(|b| b.data.as_deref()).filter(|d| !d.is_empty()) {
+                calendars.extend(decode_part_body(data, &part.headers));
+            } else if let Some(attachment_id) = body.and_then(|b| b.attachment_id.as_deref()) {
+                let bytes = download_attachment(client, id, attachment_id).await?;
+                let charset = header(&part.headers, "Content-Type").and_then(|v| content_type_param(v, "charset"));
+                calendars.push(decode_charset(&bytes, charset.as_deref()));
+            }
+        }
+    }
+
+    Ok(extract(message, &calendars))
+}
+
+/// Build the extracted view of a full-format message
+///
+/// `calendars` holds the text of its calendar parts, which may have to be
+/// downloaded separately.
+pub fn extract(message: Message, calendars: &[String]) -> ExtractedMessage {
+    let html = message.payload.as_ref().and_then(|payload| {
+        match payload.body.as_ref().and_then(|b| b.data.as_deref()).filter(|d| !d.is_empty()) {
+            Some(data) if payload.mime_type.as_deref() == Some("text/html") => decode_part_body(data, &payload.headers),
+            Some(_) => None,
+            None => find_text_part(&payload.parts, "text/html"),
+        }
+    });
+
+    let (links, tracking_pixels) = match &html {
+        Some(html) => (html_links(html), tracking_pixels(html)),
+        None => {
+            let text = extract_body(&message).unwrap_or_default();
+            (text_links(&text), Vec::new())
+        }
+    };
+
+    let unsubscribe = get_raw_header(&message, "List-Unsubscribe").and_then(|value| {
+        parse_list_unsubscribe(&value, get_raw_header(&message, "List-Unsubscribe-Post").as_deref())
+    });
+
+    // The topmost header is the one added by Gmail's own servers
+    let authentication = get_raw_header(&message, "Authentication-Results")
+        .map(|value| parse_authentication_results(&value));
+
+    let mut calendar_events: Vec<CalendarInvite> = Vec::new();
+    for event in calendars.iter().flat_map(|text| parse_calendar(text)) {
+        // Invites usually carry the same event inline and as invite.ics
+        let duplicate = event.uid.is_some() && calendar_events.iter().any(|e| e.uid == event.uid && e.sequence == event.sequence);
+        if !duplicate {
+            calendar_events.push(event);
+        }
+    }
+
+    ExtractedMessage {
+        message: minimal_message(message),
+        links,
+        unsubscribe,
+        calendar_events,
+        authentication,
+        tracking_pixels,
+    }
+}
+
+/// Unsubscribe from the list a message came from
+///
+/// Uses the RFC 8058 one-click POST when the sender supports it, otherwise
+/// sends the `mailto:` unsubscribe request. Web-only unsubscribe links need
+/// a browser and are reported as an error with the URL.
+pub async fn unsubscribe(client: &ApiClient, id: &str, dry_run: bool) -> Result<UnsubscribeResult> {
+    let message = get_message(client, id, "metadata").await?;
+    let options = get_raw_header(&message, "List-Unsubscribe")
+        .and_then(|value| parse_list_unsubscribe(&value, get_raw_header(&message, "List-Unsubscribe-Post").as_deref()))
+        .ok_or_else(|| WorkspaceError::NotFound(format!("Message {} has no List-Unsubscribe header", id)))?;
+
+    let mut result = UnsubscribeResult {
+        message_id: id.to_string(),
+        method: "oneClick",
+        target: String::new(),
+        dry_run,
+        http_status: None,
+        sent_message_id: None,
+    };
+
+    match (&options.http, &options.mailto) {
+        (Some(url), _) if options.one_click => {
+            result.target = url.clone();
+            if !dry_run {
+                // RFC 8058: no cookies or credentials, just the fixed form body
+                let http = reqwest::Client::builder()
+                    .timeout(UNSUBSCRIBE_TIMEOUT)
+                    .redirect(reqwest::redirect::Policy::limited(UNSUBSCRIBE_MAX_REDIRECTS))
+                    .build()?;
+                let response = http
+                    .post(url)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body("List-Unsubscribe=One-Click")
+                    .send()
+                    .await?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(WorkspaceError::Api(ApiError {
+                        code: status.as_u16(),
+                        message: format!("One-click unsubscribe failed: {}", response.text().await.unwrap_or_default()),
+                        domain: "unsubscribe".to_string(),
+                        retry_after: None,
+                    }));
+                }
+                result.http_status = Some(status.as_u16());
+            }
+        }
+        (_, Some(mailto)) => {
+            let params = mailto_params(mailto)?;
+            result.method = "mailto";
+            result.target = params.to.clone();
+            if !dry_run {
+                let sent = send_message(client, params).await?;
+                result.sent_message_id = Some(sent.id);
+            }
+        }
+        (Some(url), None) => {
+            return Err(WorkspaceError::InvalidInput(format!(
+                "The sender only offers an unsubscribe web page; open it in a browser: {}", url
+            )));
+        }
+        (None, None) => unreachable!("parse_list_unsubscribe returns None without options"),
+    }
+
+    Ok(result)
+}
+
+/// Parse `List-Unsubscribe` (`<mailto:...>, <https://...>`) and `List-Unsubscribe-Post`
+pub fn parse_list_unsubscribe(value: &str, post: Option<&str>) -> Option<Unsubscribe> {
+    let mut http = None;
+    let mut mailto = None;
+    for uri in value.split('<').skip(1).filter_map(|s| s.split_once('>')).map(|(uri, _)| uri.trim()) {
+        let lower = uri.to_ascii_lowercase();
+        if lower.starts_with("mailto:") {
+            mailto.get_or_insert_with(|| uri.to_string());
+        } else if lower.starts_with("https://") || lower.starts_with("http://") {
+            http.get_or_insert_with(|| uri.to_string());
+        }
+    }
+    if http.is_none() && mailto.is_none() {
+        return None;
+    }
+
+    // One-click requires HTTPS and the exact List-Unsubscribe-Post value
+    let one_click = http.as_deref().is_some_and(|u| u.to_ascii_lowercase().starts_with("https://"))
+        && post.is_some_and(|p| p.trim().eq_ignore_ascii_case("List-Unsubscribe=One-Click"));
+    Some(Unsubscribe { http, mailto, one_click })
+}
+
+/// Message to send for a `mailto:` unsubscribe URI, honouring `subject` and `body`
+fn mailto_params(uri: &str) -> Result<ComposeParams> {
+    let rest = &uri["mailto:".len()..];
+    let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let to = urlencoding::decode(address).map(|a| a.into_owned()).unwrap_or_else(|_| address.to_string());
+    if to.trim().is_empty() {
+        return Err(WorkspaceError::InvalidInput(format!("Invalid unsubscribe address '{}'", uri)));
+    }
+
+    let mut subject = "unsubscribe".to_string();
+    let mut body = "unsubscribe".to_string();
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        let value = urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_else(|_| value.to_string());
+        if key.eq_ignore_ascii_case("subject") {
+            subject = value;
+        } else if key.eq_ignore_ascii_case("body") {
+            body = value;
+        }
+    }
+
+    Ok(ComposeParams {
+        to,
+        subject,
+        body,
+        from: None,
+        cc: None,
+        bcc: None,
+        in_reply_to: None,
+        references: None,
+        thread_id: None,
+        is_html: false,
+        text_body: None,
+        inline_images: Vec::new(),
+        attachments: Vec::new(),
+    })
+}
+
+/// Parse an `Authentication-Results` header (RFC 8601)
+pub fn parse_authentication_results(value: &str) -> AuthenticationResults {
+    let value = strip_comments(value);
+    let mut segments = value.split(';').map(str::trim);
+    let mut results = AuthenticationResults {
+        authserv_id: segments.next().unwrap_or_default().split_whitespace().next().unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+
+    for segment in segments {
+        let mut tokens = segment.split_whitespace();
+        let Some((method, result)) = tokens.next().and_then(|t| t.split_once('=')) else {
+            continue;
+        };
+        let properties: Vec<(&str, &str)> = tokens.filter_map(|t| t.split_once('=')).collect();
+        let property = |name: &str| properties.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim_matches('"'));
+
+        let check = |domain: Option<&str>| AuthCheck {
+            result: result.to_ascii_lowercase(),
+            domain: domain.map(|d| d.rsplit('@').next().unwrap_or(d).to_string()).filter(|d| !d.is_empty()),
+        };
+        match method.to_ascii_lowercase().as_str() {
+            "spf" => results.spf = Some(check(property("smtp.mailfrom").or_else(|| property("smtp.helo")))),
+            "dkim" => results.dkim.push(check(property("header.d").or_else(|| property("header.i")))),
+            "dmarc" => results.dmarc = Some(check(property("header.from"))),
+            _ => {}
+        }
+    }
+    results
+}
+
+/// Remove `(comments)`, which may themselves contain `;` or `=`
+fn strip_comments(value: &str) -> String {
+    let mut depth = 0usize;
+    value.chars()
+        .filter(|&c| match c {
+            '(' => { depth += 1; false }
+            ')' => { depth = depth.saturating_sub(1); false }
+            _ => depth == 0,
+        })
+        .collect()
+}
+
+/// Calendar parts anywhere in the MIME tree: text/calendar, application/ics or *.ics
+fn calendar_parts(part: &MessagePart) -> Vec<&MessagePart> {
+    let mime = part.mime_type.as_deref().unwrap_or("").to_ascii_lowercase();
+    let is_ics = part.filename.as_deref().is_some_and(|f| f.to_ascii_lowercase().ends_with(".ics"));
+    if mime == "text/calendar" || mime == "application/ics" || is_ics {
+        return vec![part];
+    }
+    part.parts.iter().flat_map(calendar_parts).collect()
+}
+
+fn header<'a>(headers: &'a [Header], name: &str) -> Option<&'a str> {
+    headers.iter().find(|h| h.name.eq_ignore_ascii_case(name)).map(|h| h.value.as_str())
+}
+
+/// Parse the VEVENTs of an iCalendar (RFC 5545) document
+pub fn parse_calendar(text: &str) -> Vec<CalendarInvite> {
+    // Unfold continuation lines, which start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut method = None;
+    let mut events = Vec::new();
+    let mut current: Option<CalendarInvite> = None;
+    let mut depth = 0usize;
+
+    for line in &lines {
+        let Some((name_and_params, value)) = split_property(line) else {
+            continue;
+        };
+        let mut params = name_and_params.split(';');
+        let name = params.next().unwrap_or_default().to_ascii_uppercase();
+        let param = |key: &str| name_and_params.split(';').skip(1)
+            .filter_map(|p| p.split_once('='))
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.trim_matches('"').to_string());
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", Some(_)) => depth += 1,
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(CalendarInvite { method: method.clone(), ..Default::default() });
+            }
+            ("END", Some(_)) if depth > 0 => depth -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => events.extend(current.take()),
+            ("METHOD", None) => method = Some(value.to_string()),
+            // Properties of nested components such as VALARM belong to them
+            (_, Some(_)) if depth > 0 => {}
+            ("UID", Some(event)) => event.uid = Some(value.to_string()),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(value)),
+            ("DESCRIPTION", Some(event)) => event.description = Some(unescape_text(value)),
+            ("LOCATION", Some(event)) => event.location = Some(unescape_text(value)),
+            ("STATUS", Some(event)) => event.status = Some(value.to_string()),
+            ("SEQUENCE", Some(event)) => event.sequence = value.trim().parse().ok(),
+            ("RRULE" | "RDATE" | "EXDATE", Some(event)) => event.recurrence.push(format!("{}:{}", name_and_params, value)),
+            ("DTSTART", Some(event)) => event.start = Some(parse_ical_time(value, param("TZID"))),
+            ("DTEND", Some(event)) => event.end = Some(parse_ical_time(value, param("TZID"))),
+            ("ORGANIZER" | "ATTENDEE", Some(event)) => {
+                let person = InviteAttendee {
+                    email: strip_mailto(value),
+                    name: param("CN"),
+                    status: param("PARTSTAT"),
+                    role: param("ROLE"),
+                };
+                if name == "ORGANIZER" {
+                    event.organizer = Some(person);
+                } else {
+                    event.attendees.push(person);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Split `NAME;PARAM="a:b":value` at the first colon outside quotes
+fn split_property(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..i], &line[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn strip_mailto(value: &str) -> String {
+    let value = value.trim();
+    match value.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("mailto:") => value[7..].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+/// Convert an iCalendar DATE or DATE-TIME to the Calendar API's start/end shape
+fn parse_ical_time(value: &str, tzid: Option<String>) -> EventDateTime {
+    let value = value.trim();
+    let digits = |range: std::ops::Range<usize>| value.get(range).unwrap_or("00");
+
+    if value.len() == 8 {
+        return EventDateTime {
+            date: Some(format!("{}-{}-{}", digits(0..4), digits(4..6), digits(6..8))),
+            date_time: None,
+            time_zone: tzid,
+        };
+    }
+
+    let utc = value.ends_with('Z');
+    let date_time = format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        digits(0..4), digits(4..6), digits(6..8), digits(9..11), digits(11..13), digits(13..15),
+        if utc { "Z" } else { "" }
+    );
+    EventDateTime {
+        date: None,
+        date_time: Some(date_time),
+        time_zone: if utc { Some("UTC".to_string()) } else { tzid },
+    }
+}
+
+/// Links of `<a href>` tags with their anchor text
+pub fn html_links(html: &str) -> Vec<Link> {
+    let lower = html.to_ascii_lowercase();
+    let mut links: Vec<Link> = Vec::new();
+
+    for (end, attrs) in find_tags(html, &lower, "a") {
+        let Some(url) = attribute(attrs, "href").filter(|u| is_link(u)) else {
+            continue;
+        };
+        let close = lower[end..].find("</a").map(|i| end + i).unwrap_or(end);
+        let text = collapse_whitespace(&decode_entities(&strip_tags(&html[end..close])));
+        let link = Link { url, text: (!text.is_empty()).then_some(text) };
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// Bare http(s) URLs in plain text
+pub fn text_links(text: &str) -> Vec<Link> {
+    let mut links: Vec<Link> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = ["http://", "https://"].iter().filter_map(|scheme| rest.find(scheme)).min() {
+        let candidate = &rest[start..];
+        let end = candidate.find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"')).unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '\'']);
+        let link = Link { url: url.to_string(), text: None };
+        if url.len() > "https://".len() && !links.contains(&link) {
+            links.push(link);
+        }
+        rest = &candidate[end..];
+    }
+    links
+}
+
+/// Images that look like open-tracking pixels
+pub fn tracking_pixels(html: &str) -> Vec<TrackingPixel> {
+    let lower = html.to_ascii_lowercase();
+    let mut pixels: Vec<TrackingPixel> = Vec::new();
+
+    for (_, attrs) in find_tags(html, &lower, "img") {
+        let Some(url) = attribute(attrs, "src").filter(|u| u.starts_with("http://") || u.starts_with("https://")) else {
+            continue;
+        };
+        let size = |name: &str| attribute(attrs, name)
+            .and_then(|v| v.trim().trim_end_matches("px").parse::<u32>().ok());
+        let style = attribute(attrs, "style").unwrap_or_default().to_ascii_lowercase().replace(char::is_whitespace, "");
+        let url_lower = url.to_ascii_lowercase();
+
+        let reason = if size("width").is_some_and(|w| w <= 1) || size("height").is_some_and(|h| h <= 1)
+            || style.contains("width:1px") || style.contains("height:1px") || style.contains("width:0")
+        {
+            "tinyImage"
+        } else if style.contains("display:none") || style.contains("visibility:hidden") {
+            "hidden"
+        } else if TRACKER_PATTERNS.iter().any(|p| url_lower.contains(p)) {
+            "knownTracker"
+        } else {
+            continue;
+        };
+
+        if !pixels.iter().any(|p| p.url == url) {
+            pixels.push(TrackingPixel { url, reason });
+        }
+    }
+    pixels
+}
+
+fn is_link(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("mailto:")
+}
+
+/// Start tags named `name`: the byte offset just past each tag and its attribute text
+///
+/// `lower` is the ASCII-lowercased `html`, so offsets are shared.
+fn find_tags<'a>(html: &'a str, lower: &str, name: &str) -> Vec<(usize, &'a str)> {
+    let open = format!("<{}", name);
+    let mut tags = Vec::new();
+    let mut offset = 0;
+
+    while let Some(found) = lower[offset..].find(&open) {
+        let start = offset + found + open.len();
+        offset = start;
+        if !lower[start..].starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/') {
+            continue;
+        }
+
+        // Find the closing '>' outside quoted attribute values
+        let mut quote = None;
+        let end = html[start..].char_indices().find(|&(_, c)| match (quote, c) {
+            (None, '"' | '\'') => { quote = Some(c); false }
+            (Some(q), c) if c == q => { quote = None; false }
+            (None, '>') => true,
+            _ => false,
+        });
+        let Some((end, _)) = end else { break };
+        tags.push((start + end + 1, &html[start..start + end]));
+        offset = start + end + 1;
+    }
+    tags
+}
+
+/// Value of an attribute in a tag's attribute text, entities decoded
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_end = rest.find(|c: char| c.is_ascii_whitespace() || c == '=').unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let close = after[1..].find(q).map(|i| i + 1).unwrap_or(after.len());
+                        (&after[1..close], after.get(close + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(|c: char| c.is_ascii_whitespace()).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                value
+            }
+            None => "",
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value.trim()));
+        }
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
+    }
+    output
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode the HTML entities that show up in URLs and link text
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').filter(|&i| i <= 10).map(|i| &rest[1..i + 1]);
+        let decoded = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity.strip_prefix('#').and_then(|n| match n.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => n.parse().ok(),
+            }).and_then(char::from_u32),
+        });
+        match (entity, decoded) {
+            (Some(entity), Some(c)) => {
+                output.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_html_and_headers() {
+        let html = r##"<p>Hi <a href="https://example.com/a?x=1&amp;y=2" class=btn>Read
+            <b>more</b></a> or <A HREF='mailto:help@example.com'>email us</A> <a href="#top">top</a></p>
+            <img src="https://example.com/logo.png" width="120">
+            <img width="1" height="1" src="https://t.example.com/open?id=42" alt="">
+            <img src="https://mailtrack.io/trace/mail/abc.png">"##;
+
+        let links = html_links(html);
+        assert_eq!(links, vec![
+            Link { url: "https://example.com/a?x=1&y=2".into(), text: Some("Read more".into()) },
+            Link { url: "mailto:help@example.com".into(), text: Some("email us".into()) },
+        ]);
+        let pixels = tracking_pixels(html);
+        assert_eq!(pixels.iter().map(|p| p.reason).collect::<Vec<_>>(), vec!["tinyImage", "knownTracker"]);
+        assert_eq!(text_links("See https://example.com/x. Or (http://a.io/b)")[1].url, "http://a.io/b");
+
+        let unsubscribe = parse_list_unsubscribe(
+            "<mailto:unsub@lists.example.com?subject=unsubscribe>, <https://lists.example.com/u/123>",
+            Some("List-Unsubscribe=One-Click"),
+        ).unwrap();
+        assert!(unsubscribe.one_click);
+        assert_eq!(unsubscribe.http.as_deref(), Some("https://lists.example.com/u/123"));
+        assert_eq!(mailto_params(unsubscribe.mailto.as_deref().unwrap()).unwrap().to, "unsub@lists.example.com");
+
+        let auth = parse_authentication_results(
+            "mx.google.com; dkim=pass header.i=@example.com header.s=s1 header.b=abc; \
+             spf=softfail (google.com: domain of bounce@mail.example.com does not designate 1.2.3.4; x) \
+             smtp.mailfrom=bounce@mail.example.com; dmarc=pass (p=NONE sp=NONE dis=NONE) header.from=example.com",
+        );
+        assert_eq!(auth.authserv_id, "mx.google.com");
+        assert_eq!(auth.spf, Some(AuthCheck { result: "softfail".into(), domain: Some("mail.example.com".into()) }));
+        assert_eq!(auth.dkim[0].domain.as_deref(), Some("example.com"));
+        assert_eq!(auth.dmarc.unwrap().result, "pass");
+    }
+
+    #[test]
+    fn test_parse_calendar() {
+        let ics = "BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nBEGIN:VEVENT\r\nUID:abc@google.com\r\n\
+            DTSTART;TZID=Europe/Berlin:20261020T100000\r\nDTEND:20261020T090000Z\r\n\
+            SUMMARY:Planning\\, Q4\r\nDESCRIPTION:Line one\\nline\r\n  two\r\nSEQUENCE:1\r\n\
+            ORGANIZER;CN=Ann:mailto:ann@example.com\r\n\
+            ATTENDEE;CN=\"Bob: B\";PARTSTAT=NEEDS-ACTION;ROLE=REQ-PARTICIPANT:mailto:bob@example.com\r\n\
+            BEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        let events = parse_calendar(ics);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.method.as_deref(), Some("REQUEST"));
+        assert_eq!(event.summary.as_deref(), Some("Planning, Q4"));
+        assert_eq!(event.description.as_deref(), Some("Line one\nline two"));
+        let start = event.start.as_ref().unwrap();
+        assert_eq!(start.date_time.as_deref(), Some("2026-10-20T10:00:00"));
+        assert_eq!(start.time_zone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(event.end.as_ref().unwrap().date_time.as_deref(), Some("2026-10-20T09:00:00Z"));
+        assert_eq!(event.organizer.as_ref().unwrap().email, "ann@example.com");
+        assert_eq!(event.attendees[0].name.as_deref(), Some("Bob: B"));
+        assert_eq!(event.attendees[0].status.as_deref(), Some("NEEDS-ACTION"));
+    }
+}
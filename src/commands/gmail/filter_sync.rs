@@ -0,0 +1,430 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::error::{Result, WorkspaceError};
+use super::filters::{create_filter, delete_filter, list_filters, Filter, FilterAction, FilterCriteria};
+use super::labels::{find_label, list_labels, Label, LabelResolver};
+
+/// A filter as kept in a filters file
+///
+/// Labels are referenced by name rather than ID so the same file can be
+/// applied to any account.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSpec {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove_labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward: Option<String>,
+    #[serde(default)]
+    pub criteria: FilterCriteria,
+}
+
+/// Contents of a filters file (`[[filter]]` tables in TOML)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FiltersFile {
+    #[serde(default, rename = "filter")]
+    pub filters: Vec<FilterSpec>,
+}
+
+/// Changes needed to make the live filters match a filters file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterPlan {
+    pub unchanged: usize,
+    pub create: Vec<FilterSpec>,
+    pub delete: Vec<PlannedDelete>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedDelete {
+    pub id: String,
+    #[serde(flatten)]
+    pub filter: FilterSpec,
+}
+
+/// Options for [`apply`]
+pub struct ApplyOptions {
+    /// Only compute the plan
+    pub dry_run: bool,
+    /// Delete live filters that are not in the file
+    pub delete_unmanaged: bool,
+    /// Create labels referenced by the file that do not exist yet
+    pub create_labels: bool,
+}
+
+/// Result of [`apply`]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyResult {
+    pub dry_run: bool,
+    #[serde(flatten)]
+    pub plan: FilterPlan,
+    pub created: usize,
+    pub deleted: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Export file formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FiltersFormat {
+    Json,
+    Toml,
+    /// Gmail's mailFilters.xml, as used by Settings > Filters > Export
+    Xml,
+}
+
+impl FiltersFormat {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "xml" => Some(Self::Xml),
+            _ => None,
+        }
+    }
+}
+
+/// Read the live filters as a filters file, with label IDs replaced by names
+pub async fn export_filters(client: &ApiClient) -> Result<FiltersFile> {
+    let labels = list_labels(client).await?.labels;
+    let filters = list_filters(client).await?.filter;
+    Ok(FiltersFile {
+        filters: filters.iter().map(|f| spec_from_filter(f, &labels)).collect(),
+    })
+}
+
+/// Render a filters file; JSON is left to the regular output formatter
+pub fn render_filters(file: &FiltersFile, format: FiltersFormat) -> Result<String> {
+    match format {
+        FiltersFormat::Json => Ok(serde_json::to_string_pretty(file)?),
+        FiltersFormat::Toml => toml::to_string_pretty(file)
+            .map_err(|e| WorkspaceError::InvalidInput(format!("Failed to render TOML: {}", e))),
+        FiltersFormat::Xml => Ok(to_mail_filters_xml(file)),
+    }
+}
+
+/// Load a filters file: JSON for `.json`, TOML otherwise
+pub fn load_filters_file(path: &Path) -> Result<FiltersFile> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        WorkspaceError::InvalidInput(format!("Failed to read '{}': {}", path.display(), e))
+    })?;
+    let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    if is_json {
+        serde_json::from_str(&content)
+            .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid filters file '{}': {}", path.display(), e)))
+    } else {
+        toml::from_str(&content)
+            .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid filters file '{}': {}", path.display(), e)))
+    }
+}
+
+/// Make the live filters match `desired`
+///
+/// Gmail filters cannot be updated in place, so a changed filter is a create
+/// plus a delete. New filters are created first; if any creation fails,
+/// deletions are skipped so no rule is lost.
+pub async fn apply(client: &ApiClient, desired: &[FilterSpec], options: &ApplyOptions) -> Result<ApplyResult> {
+    let labels = list_labels(client).await?.labels;
+    let live = list_filters(client).await?.filter;
+    let plan = plan_changes(desired, &live, &labels, options.delete_unmanaged);
+
+    let mut result = ApplyResult {
+        dry_run: options.dry_run,
+        plan,
+        created: 0,
+        deleted: 0,
+        errors: Vec::new(),
+    };
+    if options.dry_run {
+        return Ok(result);
+    }
+
+    let mut resolver = LabelResolver::load(client).await?.with_create_missing(options.create_labels);
+    for spec in &result.plan.create {
+        let outcome = async {
+            let filter = Filter {
+                id: None,
+                criteria: spec.criteria.clone(),
+                action: FilterAction {
+                    add_label_ids: resolver.resolve_all(client, &spec.add_labels).await?,
+                    remove_label_ids: resolver.resolve_all(client, &spec.remove_labels).await?,
+                    forward: spec.forward.clone(),
+                },
+            };
+            create_filter(client, &filter).await
+        }
+        .await;
+        match outcome {
+            Ok(_) => result.created += 1,
+            Err(e) => result.errors.push(format!("create {}: {}", describe(spec), e)),
+        }
+    }
+
+    if !result.errors.is_empty() && !result.plan.delete.is_empty() {
+        result.errors.push(format!(
+            "Skipped {} deletion(s) because some filters could not be created",
+            result.plan.delete.len()
+        ));
+        return Ok(result);
+    }
+
+    for planned in &result.plan.delete {
+        match delete_filter(client, &planned.id).await {
+            Ok(()) => result.deleted += 1,
+            Err(e) => result.errors.push(format!("delete {}: {}", planned.id, e)),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Match desired filters against live ones by criteria and action
+///
+/// Each live filter matches at most one desired filter, so duplicates in
+/// the file are created again. Live filters left over are deleted when
+/// `delete_unmanaged` is set.
+pub fn plan_changes(desired: &[FilterSpec], live: &[Filter], labels: &[Label], delete_unmanaged: bool) -> FilterPlan {
+    let mut remaining: Vec<(String, FilterSpec)> = live.iter()
+        .map(|f| (f.id.clone().unwrap_or_default(), spec_from_filter(f, labels)))
+        .collect();
+    let mut plan = FilterPlan {
+        unchanged: 0,
+        create: Vec::new(),
+        delete: Vec::new(),
+    };
+
+    for spec in desired {
+        let wanted = normalize(spec, labels);
+        match remaining.iter().position(|(_, existing)| normalize(existing, labels) == wanted) {
+            Some(index) => {
+                remaining.remove(index);
+                plan.unchanged += 1;
+            }
+            None => plan.create.push(spec.clone()),
+        }
+    }
+
+    if delete_unmanaged {
+        plan.delete = remaining.into_iter()
+            .map(|(id, filter)| PlannedDelete { id, filter })
+            .collect();
+    }
+    plan
+}
+
+fn spec_from_filter(filter: &Filter, labels: &[Label]) -> FilterSpec {
+    let name = |id: &String| labels.iter()
+        .find(|l| &l.id == id)
+        .map(|l| l.name.clone())
+        .unwrap_or_else(|| id.clone());
+
+    FilterSpec {
+        add_labels: filter.action.add_label_ids.iter().map(name).collect(),
+        remove_labels: filter.action.remove_label_ids.iter().map(name).collect(),
+        forward: filter.action.forward.clone(),
+        criteria: filter.criteria.clone(),
+    }
+}
+
+/// Canonical form for comparison: label names resolved and sorted, blank
+/// strings and `false` flags treated as unset
+fn normalize(spec: &FilterSpec, labels: &[Label]) -> FilterSpec {
+    let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(String::from);
+    let flag = |value: Option<bool>| value.filter(|v| *v);
+    let label_set = |keys: &[String]| {
+        let mut names: Vec<String> = keys.iter()
+            .map(|key| find_label(labels, key).map(|l| l.name.clone()).unwrap_or_else(|| key.trim().to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+
+    let criteria = &spec.criteria;
+    FilterSpec {
+        add_labels: label_set(&spec.add_labels),
+        remove_labels: label_set(&spec.remove_labels),
+        forward: text(&spec.forward).map(|f| f.to_lowercase()),
+        criteria: FilterCriteria {
+            from: text(&criteria.from),
+            to: text(&criteria.to),
+            subject: text(&criteria.subject),
+            query: text(&criteria.query),
+            negated_query: text(&criteria.negated_query),
+            has_attachment: flag(criteria.has_attachment),
+            exclude_chats: flag(criteria.exclude_chats),
+            size: criteria.size,
+            size_comparison: criteria.size.and(text(&criteria.size_comparison)),
+        },
+    }
+}
+
+/// Short human description of a filter for error messages
+fn describe(spec: &FilterSpec) -> String {
+    let c = &spec.criteria;
+    let parts: Vec<String> = [
+        ("from", &c.from),
+        ("to", &c.to),
+        ("subject", &c.subject),
+        ("query", &c.query),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}:{}", name, v)))
+    .collect();
+    if parts.is_empty() {
+        "filter".to_string()
+    } else {
+        format!("filter [{}]", parts.join(" "))
+    }
+}
+
+/// Render filters in Gmail's mailFilters.xml format
+///
+/// System labels map to the matching `should*` properties and categories to
+/// `smartLabelToApply`; other added labels become `label` properties.
+pub fn to_mail_filters_xml(file: &FiltersFile) -> String {
+    let mut xml = String::from(
+        "<?xml version='1.0' encoding='UTF-8'?>\n\
+         <feed xmlns='http://www.w3.org/2005/Atom' xmlns:apps='http://schemas.google.com/apps/2006'>\n\
+         \t<title>Mail Filters</title>\n",
+    );
+
+    for spec in &file.filters {
+        let mut properties: Vec<(&str, String)> = Vec::new();
+        let c = &spec.criteria;
+        let criteria_text = [
+            ("from", &c.from),
+            ("to", &c.to),
+            ("subject", &c.subject),
+            ("hasTheWord", &c.query),
+            ("doesNotHaveTheWord", &c.negated_query),
+        ];
+        for (name, value) in criteria_text {
+            if let Some(value) = value {
+                properties.push((name, value.clone()));
+            }
+        }
+        if c.has_attachment == Some(true) {
+            properties.push(("hasAttachment", "true".to_string()));
+        }
+        if c.exclude_chats == Some(true) {
+            properties.push(("excludeChats", "true".to_string()));
+        }
+        if let Some(size) = c.size {
+            let operator = if c.size_comparison.as_deref() == Some("smaller") { "s_ss" } else { "s_sl" };
+            properties.push(("size", size.to_string()));
+            properties.push(("sizeOperator", operator.to_string()));
+            properties.push(("sizeUnit", "s_sb".to_string()));
+        }
+
+        for label in &spec.add_labels {
+            let property = match label.as_str() {
+                "STARRED" => ("shouldStar", "true".to_string()),
+                "TRASH" => ("shouldTrash", "true".to_string()),
+                "IMPORTANT" => ("shouldAlwaysMarkAsImportant", "true".to_string()),
+                "CATEGORY_PERSONAL" => ("smartLabelToApply", "^smartlabel_personal".to_string()),
+                "CATEGORY_SOCIAL" => ("smartLabelToApply", "^smartlabel_social".to_string()),
+                "CATEGORY_PROMOTIONS" => ("smartLabelToApply", "^smartlabel_promo".to_string()),
+                "CATEGORY_UPDATES" => ("smartLabelToApply", "^smartlabel_notification".to_string()),
+                "CATEGORY_FORUMS" => ("smartLabelToApply", "^smartlabel_group".to_string()),
+                _ => ("label", label.clone()),
+            };
+            properties.push(property);
+        }
+        for label in &spec.remove_labels {
+            let name = match label.as_str() {
+                "INBOX" => "shouldArchive",
+                "UNREAD" => "shouldMarkAsRead",
+                "SPAM" => "shouldNeverSpam",
+                "IMPORTANT" => "shouldNeverMarkAsImportant",
+                _ => continue,
+            };
+            properties.push((name, "true".to_string()));
+        }
+        if let Some(ref forward) = spec.forward {
+            properties.push(("forwardTo", forward.clone()));
+        }
+
+        xml.push_str("\t<entry>\n\t\t<category term='filter'></category>\n\t\t<title>Mail Filter</title>\n\t\t<content></content>\n");
+        for (name, value) in properties {
+            xml.push_str(&format!("\t\t<apps:property name='{}' value='{}'/>\n", name, xml_escape(&value)));
+        }
+        xml.push_str("\t</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(id: &str, name: &str) -> Label {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    fn live(id: &str, from: &str, add: &[&str]) -> Filter {
+        Filter {
+            id: Some(id.to_string()),
+            criteria: FilterCriteria { from: Some(from.to_string()), ..Default::default() },
+            action: FilterAction {
+                add_label_ids: add.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_plan_changes_matches_by_content() {
+        let labels = vec![label("Label_1", "Receipts"), label("INBOX", "INBOX")];
+        let file: FiltersFile = toml::from_str(r#"
+            [[filter]]
+            addLabels = ["receipts"]
+            removeLabels = ["INBOX"]
+            criteria = { from = "billing@example.com" }
+
+            [[filter]]
+            addLabels = ["Newsletters"]
+            criteria = { from = "news@example.com" }
+        "#).unwrap();
+
+        let mut same = live("f1", "billing@example.com", &["Label_1"]);
+        same.action.remove_label_ids = vec!["INBOX".to_string()];
+        let stale = live("f2", "old@example.com", &["Label_1"]);
+
+        let plan = plan_changes(&file.filters, &[same.clone(), stale.clone()], &labels, true);
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.create.len(), 1);
+        assert_eq!(plan.create[0].criteria.from.as_deref(), Some("news@example.com"));
+        assert_eq!(plan.delete.len(), 1);
+        assert_eq!(plan.delete[0].id, "f2");
+        assert_eq!(plan.delete[0].filter.add_labels, vec!["Receipts"]);
+
+        let keep = plan_changes(&file.filters, &[same, stale], &labels, false);
+        assert!(keep.delete.is_empty());
+
+        // Exported files round-trip through TOML
+        let exported = FiltersFile { filters: vec![spec_from_filter(&live("f3", "a@example.com", &["Label_1"]), &labels)] };
+        let text = render_filters(&exported, FiltersFormat::Toml).unwrap();
+        let parsed: FiltersFile = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.filters, exported.filters);
+        assert!(to_mail_filters_xml(&exported).contains("<apps:property name='label' value='Receipts'/>"));
+    }
+}
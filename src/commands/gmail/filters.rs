@@ -12,7 +12,7 @@ pub struct Filter {
 }
 
 /// Filter matching criteria
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterCriteria {
     /// Match emails from this sender
@@ -44,24 +44,8 @@ pub struct FilterCriteria {
     pub size_comparison: Option<String>,
 }
 
-impl Default for FilterCriteria {
-    fn default() -> Self {
-        Self {
-            from: None,
-            to: None,
-            subject: None,
-            query: None,
-            negated_query: None,
-            has_attachment: None,
-            exclude_chats: None,
-            size: None,
-            size_comparison: None,
-        }
-    }
-}
-
 /// Actions to perform on matching emails
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterAction {
     /// Add these label IDs
@@ -75,16 +59,6 @@ pub struct FilterAction {
     pub forward: Option<String>,
 }
 
-impl Default for FilterAction {
-    fn default() -> Self {
-        Self {
-            add_label_ids: Vec::new(),
-            remove_label_ids: Vec::new(),
-            forward: None,
-        }
-    }
-}
-
 /// Response from listing filters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -116,6 +90,7 @@ pub async fn delete_filter(client: &ApiClient, filter_id: &str) -> Result<()> {
 }
 
 /// Build a filter from common parameters
+#[allow(clippy::too_many_arguments)]
 pub fn build_filter(
     from: Option<&str>,
     to: Option<&str>,
@@ -1,43 +1,69 @@
 use crate::client::ApiClient;
 use crate::error::Result;
-use crate::utils::base64::decode_base64url_string;
-use crate::utils::html_to_md::smart_convert;
-use super::types::{Message, MessagePart, MinimalMessage};
+use crate::utils::base64::decode_base64url;
+use crate::utils::charset::{
+    content_type_param, decode_charset, decode_encoded_words, decode_quoted_printable, looks_quoted_printable,
+};
+use crate::utils::html_to_md::{html_to_markdown, smart_convert};
+use super::types::{Header, Message, MessagePart, MinimalMessage, RawMessage};
 
 pub async fn get_message(client: &ApiClient, id: &str, format: &str) -> Result<Message> {
     let query = [("format", format)];
     client.get_with_query(&format!("/users/me/messages/{}", id), &query).await
 }
 
+/// Get a message's RFC 2822 source (base64url-encoded in `raw`)
+pub async fn get_raw_message(client: &ApiClient, id: &str) -> Result<RawMessage> {
+    let query = [("format", "raw")];
+    client.get_with_query(&format!("/users/me/messages/{}", id), &query).await
+}
+
 /// Extract and decode the message body
+///
+/// Prefers text/plain. A message with only an HTML body is converted to
+/// Markdown-like text rather than returned as raw HTML.
 pub fn extract_body(message: &Message) -> Option<String> {
-    if let Some(ref payload) = message.payload {
-        // Try to get body from payload directly
-        if let Some(ref body) = payload.body {
-            if let Some(ref data) = body.data {
-                if !data.is_empty() {
-                    if let Ok(decoded) = decode_base64url_string(data) {
-                        return Some(decoded);
-                    }
-                }
+    let payload = message.payload.as_ref()?;
+
+    // Single-part message: the body sits on the payload itself
+    if let Some(data) = payload.body.as_ref().and_then(|b| b.data.as_deref()).filter(|d| !d.is_empty()) {
+        if let Some(body) = decode_part_body(data, &payload.headers) {
+            if payload.mime_type.as_deref() == Some("text/html") {
+                return Some(html_to_markdown(&body));
             }
+            return Some(body);
         }
+    }
 
-        // Try to find text/plain or text/html in parts
-        // Prefer text/plain over text/html for multipart/alternative
-        if let Some(text) = find_text_part(&payload.parts, "text/plain") {
-            return Some(text);
-        }
+    // Prefer text/plain over text/html for multipart/alternative
+    find_text_part(&payload.parts, "text/plain")
+        .or_else(|| find_text_part(&payload.parts, "text/html").map(|html| html_to_markdown(&html)))
+}
 
-        // Fallback to text/html if text/plain not found
-        if let Some(html) = find_text_part(&payload.parts, "text/html") {
-            return Some(html);
-        }
+/// Decode a part's base64url body data to text
+///
+/// Gmail strips the transfer encoding for `full` messages, but parts can
+/// still arrive quoted-printable, so those are decoded when they look
+/// encoded. The text is then decoded using the part's declared charset.
+pub(super) fn decode_part_body(data: &str, headers: &[Header]) -> Option<String> {
+    let mut bytes = decode_base64url(data).ok()?;
+    let header = |name: &str| headers.iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str());
+
+    let quoted_printable = header("Content-Transfer-Encoding")
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("quoted-printable"));
+    if quoted_printable && looks_quoted_printable(&bytes) {
+        bytes = decode_quoted_printable(&bytes);
     }
-    None
+
+    let charset = header("Content-Type").and_then(|v| content_type_param(v, "charset"));
+    Some(decode_charset(&bytes, charset.as_deref()))
 }
 
-fn find_text_part(parts: &[MessagePart], preferred_mime: &str) -> Option<String> {
+/// Find and decode the first part of `preferred_mime`, searching
+/// multipart/alternative containers first
+pub(super) fn find_text_part(parts: &[MessagePart], preferred_mime: &str) -> Option<String> {
     // Check if this is a multipart/alternative container
     for part in parts {
         let mime = part.mime_type.as_deref().unwrap_or("");
@@ -55,14 +81,9 @@ fn find_text_part(parts: &[MessagePart], preferred_mime: &str) -> Option<String>
         let mime = part.mime_type.as_deref().unwrap_or("");
 
         if mime == preferred_mime {
-            if let Some(ref body) = part.body {
-                if let Some(ref data) = body.data {
-                    if !data.is_empty() {
-                        if let Ok(decoded) = decode_base64url_string(data) {
-                            return Some(decoded);
-                        }
-                    }
-                }
+            let data = part.body.as_ref().and_then(|b| b.data.as_deref()).filter(|d| !d.is_empty());
+            if let Some(text) = data.and_then(|d| decode_part_body(d, &part.headers)) {
+                return Some(text);
             }
         }
     }
@@ -81,8 +102,13 @@ fn find_text_part(parts: &[MessagePart], preferred_mime: &str) -> Option<String>
     None
 }
 
-/// Get header value by name
+/// Get header value by name, with RFC 2047 encoded-words decoded
 pub fn get_header(message: &Message, name: &str) -> Option<String> {
+    get_raw_header(message, name).map(|value| decode_encoded_words(&value))
+}
+
+/// Get header value by name exactly as it appears in the message
+pub(super) fn get_raw_header(message: &Message, name: &str) -> Option<String> {
     message.payload.as_ref()?.headers.iter()
         .find(|h| h.name.eq_ignore_ascii_case(name))
         .map(|h| h.value.clone())
@@ -92,7 +118,11 @@ pub fn get_header(message: &Message, name: &str) -> Option<String> {
 /// Returns only essential headers and plain text body
 pub async fn get_message_minimal(client: &ApiClient, id: &str) -> Result<MinimalMessage> {
     let message = get_message(client, id, "full").await?;
+    Ok(minimal_message(message))
+}
 
+/// Reduce a full-format message to essential headers and a plain text body
+pub fn minimal_message(message: Message) -> MinimalMessage {
     // Extract essential headers
     let from = get_header(&message, "From");
     let to = get_header(&message, "To");
@@ -102,7 +132,7 @@ pub async fn get_message_minimal(client: &ApiClient, id: &str) -> Result<Minimal
     // Extract and convert body to plain text
     let body = extract_body(&message).map(|b| smart_convert(&b));
 
-    Ok(MinimalMessage {
+    MinimalMessage {
         id: message.id,
         thread_id: message.thread_id,
         from,
@@ -111,7 +141,7 @@ pub async fn get_message_minimal(client: &ApiClient, id: &str) -> Result<Minimal
         date,
         labels: message.label_ids,
         body,
-    })
+    }
 }
 
 /// Information about an attachment
@@ -180,3 +210,35 @@ pub async fn download_attachment(client: &ApiClient, message_id: &str, attachmen
             format!("Failed to decode attachment: {}", e)
         ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::base64::encode_base64url;
+
+    #[test]
+    fn test_extract_body_uses_declared_charset() {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "id": "m1",
+            "threadId": "t1",
+            "payload": {
+                "mimeType": "multipart/mixed",
+                "headers": [{"name": "Subject", "value": "=?ISO-2022-JP?B?GyRCJEckOSEjGyhC?="}],
+                "parts": [{
+                    "mimeType": "text/html",
+                    "headers": [
+                        {"name": "Content-Type", "value": "text/html; charset=windows-1252"},
+                        {"name": "Content-Transfer-Encoding", "value": "quoted-printable"}
+                    ],
+                    "body": {"data": encode_base64url(b"<p>Caf=E9 <b>menu</b></p>")}
+                }]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(get_header(&message, "subject").as_deref(), Some("です。"));
+        let body = extract_body(&message).unwrap();
+        assert!(body.contains("Café"));
+        assert!(!body.contains("<p>"));
+    }
+}
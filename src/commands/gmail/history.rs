@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::client::ApiClient;
+use crate::config::Config;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use super::list::enrich_messages;
+use super::types::{HistoryChanges, HistoryResponse, LabelChange, MessageRef, MessageSummary, Profile};
+
+pub struct HistoryParams {
+    pub start_history_id: String,
+    pub max_results: u32,
+    pub label_id: Option<String>,
+    /// messageAdded, messageDeleted, labelAdded, labelRemoved (empty = all)
+    pub history_types: Vec<String>,
+    pub page_token: Option<String>,
+}
+
+/// The mailbox profile; uncached, since its historyId is a sync baseline
+pub async fn get_profile(client: &ApiClient) -> Result<Profile> {
+    let no_query: [(&str, &str); 0] = [];
+    client.get_with_query_uncached("/users/me/profile", &no_query).await
+}
+
+pub async fn list_history(client: &ApiClient, params: &HistoryParams) -> Result<HistoryResponse> {
+    let mut query_params = vec![
+        ("startHistoryId", params.start_history_id.clone()),
+        ("maxResults", params.max_results.to_string()),
+    ];
+
+    if let Some(ref label) = params.label_id {
+        query_params.push(("labelId", label.clone()));
+    }
+    for history_type in &params.history_types {
+        query_params.push(("historyTypes", history_type.clone()));
+    }
+    if let Some(ref token) = params.page_token {
+        query_params.push(("pageToken", token.clone()));
+    }
+
+    // The same startHistoryId returns more once new mail arrives, so never serve it from the cache
+    client.get_with_query_uncached("/users/me/history", &query_params).await
+}
+
+/// Fetch mailbox changes since `params.start_history_id`
+///
+/// With `all`, follows `nextPageToken` until the history is exhausted;
+/// otherwise returns one page and the token for the next. Added messages
+/// are enriched with Subject/From/Date via a batch request.
+pub async fn get_changes(
+    client: &ApiClient,
+    mut params: HistoryParams,
+    all: bool,
+    access_token: &str,
+) -> Result<HistoryChanges> {
+    let mut pages = Vec::new();
+    loop {
+        let page = list_history(client, &params).await?;
+        progress::emit(ProgressEvent::Page {
+            page: pages.len() as u32 + 1,
+            items: page.history.len(),
+            has_more: page.next_page_token.is_some(),
+        });
+
+        params.page_token = page.next_page_token.clone();
+        pages.push(page);
+        if !all || params.page_token.is_none() {
+            break;
+        }
+    }
+
+    let mut changes = flatten_history(&params.start_history_id, &pages);
+    let added: Vec<MessageRef> = changes.messages_added.drain(..)
+        .map(|m| MessageRef { id: m.id, thread_id: m.thread_id })
+        .collect();
+    changes.messages_added = enrich_messages(added, access_token).await?;
+
+    Ok(changes)
+}
+
+/// Merge history pages into one change set
+///
+/// Messages that were added and then deleted within the window are reported
+/// only as deleted. Added messages carry just id/threadId until enriched.
+pub fn flatten_history(start_history_id: &str, pages: &[HistoryResponse]) -> HistoryChanges {
+    let mut changes = HistoryChanges {
+        history_id: start_history_id.to_string(),
+        ..Default::default()
+    };
+    let mut added_ids = HashSet::new();
+    let mut deleted_ids = HashSet::new();
+
+    for page in pages {
+        for record in &page.history {
+            for deleted in &record.messages_deleted {
+                if deleted_ids.insert(deleted.message.id.clone()) {
+                    changes.messages_deleted.push(MessageRef {
+                        id: deleted.message.id.clone(),
+                        thread_id: deleted.message.thread_id.clone(),
+                    });
+                }
+            }
+            for added in &record.messages_added {
+                if added_ids.insert(added.message.id.clone()) {
+                    changes.messages_added.push(MessageSummary {
+                        id: added.message.id.clone(),
+                        thread_id: added.message.thread_id.clone(),
+                        subject: None,
+                        from: None,
+                        date: None,
+                        snippet: None,
+                    });
+                }
+            }
+            for change in &record.labels_added {
+                changes.labels_added.push(LabelChange {
+                    id: change.message.id.clone(),
+                    thread_id: change.message.thread_id.clone(),
+                    label_ids: change.label_ids.clone(),
+                });
+            }
+            for change in &record.labels_removed {
+                changes.labels_removed.push(LabelChange {
+                    id: change.message.id.clone(),
+                    thread_id: change.message.thread_id.clone(),
+                    label_ids: change.label_ids.clone(),
+                });
+            }
+        }
+
+        if let Some(ref id) = page.history_id {
+            changes.history_id = id.clone();
+        }
+        changes.next_page_token = page.next_page_token.clone();
+    }
+
+    changes.messages_added.retain(|m| !deleted_ids.contains(&m.id));
+    changes
+}
+
+/// Last seen history ID per account, stored in `gmail_sync.json` in the config directory
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct SyncState {
+    #[serde(default)]
+    pub accounts: HashMap<String, String>,
+}
+
+impl SyncState {
+    pub fn path() -> Option<PathBuf> {
+        Config::config_dir().map(|dir| dir.join("gmail_sync.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Write to a temporary file and rename so an interrupted run keeps the old state
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+/// Result of `gmail sync`
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_history_id: Option<String>,
+    /// No stored state (or it expired): the current history ID was recorded
+    /// as the baseline and no changes are reported
+    pub baseline: bool,
+    #[serde(flatten)]
+    pub changes: HistoryChanges,
+}
+
+/// Report changes since the last sync for `account` and store the new history ID
+pub async fn sync(client: &ApiClient, account: &str, access_token: &str) -> Result<SyncResult> {
+    let mut state = SyncState::load();
+    let previous = state.accounts.get(account).cloned();
+
+    let (baseline, changes) = match previous {
+        Some(ref start) => {
+            let params = HistoryParams {
+                start_history_id: start.clone(),
+                max_results: 500,
+                label_id: None,
+                history_types: Vec::new(),
+                page_token: None,
+            };
+            match get_changes(client, params, true, access_token).await {
+                Ok(changes) => (false, changes),
+                // Gmail keeps history for about a week; an expired ID returns 404
+                Err(WorkspaceError::Api(ref e)) if e.code == 404 => (true, baseline_changes(client).await?),
+                Err(e) => return Err(e),
+            }
+        }
+        None => (true, baseline_changes(client).await?),
+    };
+
+    state.accounts.insert(account.to_string(), changes.history_id.clone());
+    state.save()?;
+
+    Ok(SyncResult {
+        account: account.to_string(),
+        previous_history_id: previous,
+        baseline,
+        changes,
+    })
+}
+
+async fn baseline_changes(client: &ApiClient) -> Result<HistoryChanges> {
+    let profile = get_profile(client).await?;
+    Ok(HistoryChanges {
+        history_id: profile.history_id,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_history_drops_added_then_deleted() {
+        let page: HistoryResponse = serde_json::from_value(serde_json::json!({
+            "history": [
+                {"id": "101", "messagesAdded": [{"message": {"id": "a", "threadId": "t1"}}]},
+                {"id": "102", "messagesAdded": [{"message": {"id": "b", "threadId": "t2"}}]},
+                {"id": "103", "messagesDeleted": [{"message": {"id": "b", "threadId": "t2"}}]},
+                {"id": "104", "labelsAdded": [{"message": {"id": "a", "threadId": "t1"}, "labelIds": ["STARRED"]}]}
+            ],
+            "historyId": "110"
+        }))
+        .unwrap();
+
+        let changes = flatten_history("100", &[page]);
+        assert_eq!(changes.history_id, "110");
+        assert_eq!(changes.messages_added.len(), 1);
+        assert_eq!(changes.messages_added[0].id, "a");
+        assert_eq!(changes.messages_deleted.len(), 1);
+        assert_eq!(changes.labels_added[0].label_ids, vec!["STARRED"]);
+        assert!(changes.next_page_token.is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::client::ApiClient;
-use crate::error::Result;
+use crate::error::{Result, WorkspaceError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +15,16 @@ pub struct Label {
     pub messages_unread: Option<i64>,
     pub threads_total: Option<i64>,
     pub threads_unread: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<LabelColor>,
+}
+
+/// Label colors; Gmail only accepts values from its fixed palette
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelColor {
+    pub text_color: String,
+    pub background_color: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +44,174 @@ pub async fn get_label(client: &ApiClient, label_id: &str) -> Result<Label> {
     client.get(&path).await
 }
 
+/// Fields to set when creating or updating a label
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<LabelColor>,
+}
+
+/// Create a label
+///
+/// Nested labels use `/` in the name (`Parent/Child`); missing parent labels
+/// are created first so Gmail shows the hierarchy.
+pub async fn create_label(client: &ApiClient, name: &str, color: Option<LabelColor>) -> Result<Label> {
+    let existing = list_labels(client).await?.labels;
+    create_label_path(client, &existing, name, color).await
+}
+
+async fn create_label_path(
+    client: &ApiClient,
+    existing: &[Label],
+    name: &str,
+    color: Option<LabelColor>,
+) -> Result<Label> {
+    let name = name.trim().trim_matches('/');
+    if name.is_empty() {
+        return Err(WorkspaceError::InvalidInput("Label name cannot be empty".to_string()));
+    }
+
+    for (i, _) in name.match_indices('/') {
+        let parent = &name[..i];
+        if find_label(existing, parent).is_none() {
+            let _: Label = client.post("/users/me/labels", &LabelUpdate {
+                name: Some(parent.to_string()),
+                color: None,
+            }).await?;
+        }
+    }
+
+    client.post("/users/me/labels", &LabelUpdate {
+        name: Some(name.to_string()),
+        color,
+    }).await
+}
+
+/// Result of `update_label`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedLabel {
+    #[serde(flatten)]
+    pub label: Label,
+    /// New names of nested labels moved along with a renamed parent
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub renamed_children: Vec<String>,
+}
+
+/// Rename and/or recolor a label
+///
+/// Renaming a parent also renames its nested labels (`Old/Child` becomes `New/Child`).
+pub async fn update_label(client: &ApiClient, label_id: &str, update: &LabelUpdate) -> Result<UpdatedLabel> {
+    let existing = list_labels(client).await?.labels;
+    let old_name = existing.iter().find(|l| l.id == label_id).map(|l| l.name.clone());
+
+    let path = format!("/users/me/labels/{}", urlencoding::encode(label_id));
+    let label: Label = client.patch(&path, update).await?;
+
+    let mut renamed_children = Vec::new();
+    if let (Some(old), Some(new)) = (old_name, update.name.as_deref()) {
+        let prefix = format!("{}/", old);
+        for child in existing.iter().filter(|l| l.name.starts_with(&prefix)) {
+            let child_name = format!("{}/{}", new.trim_matches('/'), &child.name[prefix.len()..]);
+            let path = format!("/users/me/labels/{}", urlencoding::encode(&child.id));
+            let _: Label = client.patch(&path, &LabelUpdate {
+                name: Some(child_name.clone()),
+                color: None,
+            }).await?;
+            renamed_children.push(child_name);
+        }
+    }
+
+    Ok(UpdatedLabel { label, renamed_children })
+}
+
+/// Delete a label (messages keep their other labels)
+pub async fn delete_label(client: &ApiClient, label_id: &str) -> Result<()> {
+    let path = format!("/users/me/labels/{}", urlencoding::encode(label_id));
+    client.delete(&path).await
+}
+
+/// Find a label by ID or name
+///
+/// Tries the exact ID, then the exact name, then a case-insensitive match on
+/// name or ID (so `inbox` finds `INBOX` and `work/clients` finds `Work/Clients`).
+pub fn find_label<'a>(labels: &'a [Label], key: &str) -> Option<&'a Label> {
+    let key = key.trim();
+    labels.iter().find(|l| l.id == key)
+        .or_else(|| labels.iter().find(|l| l.name == key))
+        .or_else(|| labels.iter().find(|l| l.name.eq_ignore_ascii_case(key) || l.id.eq_ignore_ascii_case(key)))
+}
+
+/// Resolves label names to IDs, loading the label list once
+pub struct LabelResolver {
+    labels: Vec<Label>,
+    create_missing: bool,
+}
+
+impl LabelResolver {
+    pub async fn load(client: &ApiClient) -> Result<Self> {
+        Ok(Self {
+            labels: list_labels(client).await?.labels,
+            create_missing: false,
+        })
+    }
+
+    /// Create labels that do not exist instead of failing
+    pub fn with_create_missing(mut self, create_missing: bool) -> Self {
+        self.create_missing = create_missing;
+        self
+    }
+
+    /// Resolve one label name or ID
+    pub async fn resolve(&mut self, client: &ApiClient, key: &str) -> Result<String> {
+        if let Some(label) = find_label(&self.labels, key) {
+            return Ok(label.id.clone());
+        }
+        if !self.create_missing {
+            return Err(WorkspaceError::NotFound(format!(
+                "Label '{}' not found (see `gmail labels`, or pass --create-labels)",
+                key
+            )));
+        }
+
+        let label = create_label_path(client, &self.labels, key, None).await?;
+        // Reload so newly created parents are known for later names
+        self.labels = list_labels(client).await?.labels;
+        Ok(label.id)
+    }
+
+    /// Names or IDs in `keys` that match no existing label
+    pub fn missing(&self, keys: &[String]) -> Vec<String> {
+        keys.iter()
+            .filter(|key| find_label(&self.labels, key).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Resolve a list of label names or IDs
+    pub async fn resolve_all(&mut self, client: &ApiClient, keys: &[String]) -> Result<Vec<String>> {
+        let mut ids = Vec::with_capacity(keys.len());
+        for key in keys {
+            ids.push(self.resolve(client, key).await?);
+        }
+        Ok(ids)
+    }
+}
+
+/// Resolve label names or IDs, skipping the label lookup when there are none
+pub async fn resolve_label_ids(client: &ApiClient, keys: &[String], create_missing: bool) -> Result<Vec<String>> {
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+    LabelResolver::load(client).await?
+        .with_create_missing(create_missing)
+        .resolve_all(client, keys)
+        .await
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModifyLabelsRequest {
@@ -73,3 +251,27 @@ pub async fn remove_labels(
 ) -> Result<super::types::Message> {
     modify_labels(client, message_id, vec![], label_ids).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(id: &str, name: &str) -> Label {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name})).unwrap()
+    }
+
+    #[test]
+    fn test_find_label_by_id_or_name() {
+        let labels = vec![
+            label("INBOX", "INBOX"),
+            label("Label_1", "Work"),
+            label("Label_2", "Work/Clients"),
+        ];
+
+        assert_eq!(find_label(&labels, "Label_2").unwrap().name, "Work/Clients");
+        assert_eq!(find_label(&labels, "Work").unwrap().id, "Label_1");
+        assert_eq!(find_label(&labels, "work/clients").unwrap().id, "Label_2");
+        assert_eq!(find_label(&labels, "inbox").unwrap().id, "INBOX");
+        assert!(find_label(&labels, "Personal").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::ApiClient;
 use crate::client::batch::{BatchClient, BatchRequest};
 use crate::error::Result;
+use crate::utils::charset::decode_encoded_words;
 use super::types::{ListMessagesResponse, MessageRef, MessageSummary, EnrichedListResponse};
 
 pub struct ListParams {
@@ -50,17 +51,20 @@ pub async fn enrich_messages(
         return Ok(vec![]);
     }
 
-    // Build batch requests for metadata
-    let requests: Vec<BatchRequest> = message_refs.iter()
-        .map(|msg| BatchRequest::get(
-            &msg.id,
-            format!("/gmail/v1/users/me/messages/{}?format=metadata&metadataHeaders=Subject&metadataHeaders=From&metadataHeaders=Date", msg.id)
-        ))
-        .collect();
-
+    // Build batch requests for metadata (at most 100 per batch call)
     let client = BatchClient::gmail();
-    let responses = client.execute(requests, access_token).await
-        .map_err(|e| crate::error::WorkspaceError::Config(format!("Batch request failed: {}", e)))?;
+    let mut responses = Vec::with_capacity(message_refs.len());
+    for chunk in message_refs.chunks(100) {
+        let requests: Vec<BatchRequest> = chunk.iter()
+            .map(|msg| BatchRequest::get(
+                &msg.id,
+                format!("/gmail/v1/users/me/messages/{}?format=metadata&metadataHeaders=Subject&metadataHeaders=From&metadataHeaders=Date", msg.id)
+            ))
+            .collect();
+
+        responses.extend(client.execute(requests, access_token).await
+            .map_err(|e| crate::error::WorkspaceError::Config(format!("Batch request failed: {}", e)))?);
+    }
 
     // Parse responses into MessageSummary
     let mut summaries = Vec::new();
@@ -82,7 +86,7 @@ pub async fn enrich_messages(
                 .find(|h| h.get("name").and_then(|n| n.as_str()) == Some(name))
                 .and_then(|h| h.get("value"))
                 .and_then(|v| v.as_str())
-                .map(|s| s.to_string())
+                .map(decode_encoded_words)
         };
 
         let snippet = resp.body.get("snippet")
@@ -0,0 +1,579 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::client::rate_limiter::{RateLimitConfig, TokenBucket};
+use crate::client::ApiClient;
+use crate::commands::sheets::{get_values, parse_range, update_values, UpdateParams, ValueInputOption};
+use crate::error::{CliError, ErrorCode, Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use super::drafts::send_draft;
+use super::send::{create_draft, load_attachment, send_message, Attachment, ComposeParams};
+use super::types::DraftResponse;
+
+/// Where merge rows come from
+#[derive(Debug, Clone)]
+pub enum MergeSource {
+    /// `sheet:<spreadsheet-id>!<range>`; results are written to the status column
+    Sheet { spreadsheet_id: String, range: String },
+    /// CSV file; results are kept in `<file>.merge-status.json`
+    Csv(PathBuf),
+}
+
+impl MergeSource {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let Some(rest) = spec.strip_prefix("sheet:") else {
+            return Ok(MergeSource::Csv(PathBuf::from(spec)));
+        };
+        let (id, range) = rest.split_once('!').unwrap_or((rest, "A1:ZZ"));
+        if id.is_empty() {
+            return Err(WorkspaceError::InvalidInput(
+                "Expected --data sheet:<spreadsheet-id>!<range>".to_string(),
+            ));
+        }
+        Ok(MergeSource::Sheet {
+            spreadsheet_id: id.to_string(),
+            range: range.to_string(),
+        })
+    }
+}
+
+/// Merge data: a header row followed by one row per message
+#[derive(Debug, Default)]
+pub struct MergeTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Sheet name and 1-based row/0-based column of the header cell (sheets only)
+    pub sheet: Option<String>,
+    pub header_row: u32,
+    pub first_column: u32,
+}
+
+impl MergeTable {
+    fn from_rows(mut rows: Vec<Vec<String>>) -> Result<Self> {
+        if rows.is_empty() {
+            return Err(WorkspaceError::InvalidInput("Merge data has no header row".to_string()));
+        }
+        let headers = rows.remove(0).into_iter().map(|h| h.trim().to_string()).collect();
+        Ok(Self {
+            headers,
+            rows,
+            sheet: None,
+            header_row: 1,
+            first_column: 0,
+        })
+    }
+
+    /// Index of a column by name (case-insensitive)
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|h| h.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Spreadsheet row number of the data row at `index`
+    pub fn row_number(&self, index: usize) -> u32 {
+        self.header_row + 1 + index as u32
+    }
+}
+
+/// Load merge rows from a sheet range or CSV file
+pub async fn load_table(sheets: &ApiClient, source: &MergeSource) -> Result<MergeTable> {
+    match source {
+        MergeSource::Sheet { spreadsheet_id, range } => {
+            let values = get_values(sheets, spreadsheet_id, range).await?;
+            let rows = values.values.iter()
+                .map(|row| row.iter().map(cell_to_string).collect())
+                .collect();
+            let mut table = MergeTable::from_rows(rows)?;
+
+            // The response range is normalized (e.g. "Contacts!A1:F40")
+            let (sheet, cells) = parse_range(&values.range);
+            let (column, row) = parse_cell(cells.split(':').next().unwrap_or("A1"));
+            table.sheet = sheet;
+            table.header_row = row;
+            table.first_column = column;
+            Ok(table)
+        }
+        MergeSource::Csv(path) => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(path)
+                .map_err(|e| WorkspaceError::InvalidInput(format!("Failed to read '{}': {}", path.display(), e)))?;
+            let mut rows = Vec::new();
+            for record in reader.records() {
+                let record = record.map_err(|e| {
+                    WorkspaceError::InvalidInput(format!("Invalid CSV in '{}': {}", path.display(), e))
+                })?;
+                rows.push(record.iter().map(String::from).collect());
+            }
+            MergeTable::from_rows(rows)
+        }
+    }
+}
+
+/// How the rendered template body is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    Plain,
+    Html,
+    Markdown,
+}
+
+impl BodyFormat {
+    /// Pick the format from the template extension (.md, .html)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("md") | Some("markdown") => BodyFormat::Markdown,
+            Some("html") | Some("htm") => BodyFormat::Html,
+            _ => BodyFormat::Plain,
+        }
+    }
+}
+
+pub struct MergeOptions {
+    pub subject: String,
+    pub template: String,
+    pub format: BodyFormat,
+    pub to_column: String,
+    pub cc_column: String,
+    pub bcc_column: String,
+    pub attachments_column: String,
+    pub status_column: String,
+    /// Create drafts instead of sending
+    pub draft: bool,
+    /// Render without sending or writing status
+    pub dry_run: bool,
+    /// Maximum messages sent per minute
+    pub per_minute: u32,
+    /// Stop after this many messages
+    pub limit: Option<usize>,
+}
+
+/// Outcome for one row
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeRow {
+    pub row: u32,
+    pub to: String,
+    /// sent, drafted, failed or preview
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    pub total: usize,
+    pub sent: usize,
+    pub drafted: usize,
+    /// Rows already completed in an earlier run or without a recipient
+    pub skipped: usize,
+    pub failed: usize,
+    /// Rows left for a later run (--limit reached or sending stopped)
+    pub pending: usize,
+    pub dry_run: bool,
+    /// Why the run stopped early (quota or rate limit, status write failure)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<String>,
+    pub rows: Vec<MergeRow>,
+}
+
+/// Record of completed rows, written back to the sheet or a CSV sidecar file
+struct StatusStore<'a> {
+    sheets: &'a ApiClient,
+    source: &'a MergeSource,
+    status_index: usize,
+    csv_state: BTreeMap<u32, String>,
+}
+
+impl<'a> StatusStore<'a> {
+    fn csv_state_path(path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".merge-status.json");
+        PathBuf::from(name)
+    }
+
+    fn load(sheets: &'a ApiClient, source: &'a MergeSource, status_index: usize) -> Self {
+        let csv_state = match source {
+            MergeSource::Csv(path) => std::fs::read_to_string(Self::csv_state_path(path))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default(),
+            MergeSource::Sheet { .. } => BTreeMap::new(),
+        };
+        Self { sheets, source, status_index, csv_state }
+    }
+
+    fn get(&self, table: &MergeTable, index: usize) -> String {
+        match self.source {
+            MergeSource::Csv(_) => self.csv_state.get(&table.row_number(index)).cloned().unwrap_or_default(),
+            MergeSource::Sheet { .. } => table.rows[index].get(self.status_index).cloned().unwrap_or_default(),
+        }
+    }
+
+    async fn set(&mut self, table: &MergeTable, row: u32, status: &str) -> Result<()> {
+        match self.source {
+            MergeSource::Csv(path) => {
+                self.csv_state.insert(row, status.to_string());
+                let state_path = Self::csv_state_path(path);
+                let tmp = state_path.with_extension("json.tmp");
+                std::fs::write(&tmp, serde_json::to_string_pretty(&self.csv_state)?)?;
+                std::fs::rename(tmp, state_path)?;
+                Ok(())
+            }
+            MergeSource::Sheet { spreadsheet_id, .. } => {
+                let column = column_letter(table.first_column + self.status_index as u32);
+                let range = match table.sheet {
+                    Some(ref sheet) => format!("'{}'!{}{}", sheet.replace('\'', "''"), column, row),
+                    None => format!("{}{}", column, row),
+                };
+                update_values(self.sheets, UpdateParams {
+                    spreadsheet_id: spreadsheet_id.clone(),
+                    range,
+                    values: vec![vec![serde_json::Value::String(status.to_string())]],
+                    value_input_option: ValueInputOption::Raw,
+                }).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Send (or draft) one templated message per row
+///
+/// Rows whose status is `sent:<id>` are skipped, so an interrupted merge can
+/// be re-run. In send mode, rows with `draft:<id>` send that draft, which lets
+/// a draft-only run be reviewed in Gmail before sending. Quota and rate limit
+/// errors stop the run; other errors are recorded on the row.
+pub async fn run_merge(
+    gmail: &ApiClient,
+    sheets: &ApiClient,
+    source: &MergeSource,
+    table: &MergeTable,
+    options: &MergeOptions,
+) -> Result<MergeResult> {
+    // Validate placeholders before sending anything
+    render_template(&options.subject, &table.headers, &[], BodyFormat::Plain)?;
+    render_template(&options.template, &table.headers, &[], options.format)?;
+
+    let to_index = table.column(&options.to_column).ok_or_else(|| {
+        WorkspaceError::InvalidInput(format!("Recipient column '{}' not found in header row", options.to_column))
+    })?;
+    let cc_index = table.column(&options.cc_column);
+    let bcc_index = table.column(&options.bcc_column);
+    let attachments_index = table.column(&options.attachments_column);
+    let status_index = table.column(&options.status_column).unwrap_or(table.headers.len());
+
+    let mut store = StatusStore::load(sheets, source, status_index);
+    if !options.dry_run && status_index == table.headers.len() {
+        if let MergeSource::Sheet { .. } = source {
+            store.set(table, table.header_row, &options.status_column).await?;
+        }
+    }
+
+    let bucket = TokenBucket::new(RateLimitConfig {
+        capacity: 1,
+        refill_rate: options.per_minute.max(1) as f64 / 60.0,
+        initial_tokens: Some(1),
+    });
+
+    let mut result = MergeResult {
+        total: table.rows.len(),
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+    let mut processed = 0;
+
+    for (index, row) in table.rows.iter().enumerate() {
+        let row_number = table.row_number(index);
+        let cell = |i: Option<usize>| {
+            i.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        };
+        let status = store.get(table, index);
+        let to = cell(Some(to_index)).unwrap_or_default();
+
+        if status.starts_with("sent:") || to.is_empty() || (options.draft && status.starts_with("draft:")) {
+            result.skipped += 1;
+            continue;
+        }
+        if result.stopped.is_some() || options.limit.is_some_and(|limit| processed >= limit) {
+            result.pending += 1;
+            continue;
+        }
+        processed += 1;
+
+        let mut outcome = MergeRow {
+            row: row_number,
+            to: to.clone(),
+            status: String::new(),
+            message_id: None,
+            draft_id: None,
+            error: None,
+            subject: None,
+            body: None,
+        };
+
+        let subject = render_template(&options.subject, &table.headers, row, BodyFormat::Plain)?;
+        let body = render_template(&options.template, &table.headers, row, options.format)?;
+        if options.dry_run {
+            outcome.status = "preview".to_string();
+            outcome.subject = Some(subject);
+            outcome.body = Some(body);
+            result.rows.push(outcome);
+            continue;
+        }
+
+        bucket.acquire(1).await.map_err(|e| WorkspaceError::Config(e.to_string()))?;
+        let delivered = match status.strip_prefix("draft:").filter(|_| !options.draft) {
+            Some(draft_id) => send_draft(gmail, draft_id.trim()).await.map(|m| Delivered::Sent(m.id)),
+            None => match cell(attachments_index).map(|paths| load_row_attachments(&paths)).transpose() {
+                Ok(attachments) => {
+                    let params = compose(
+                        options,
+                        to.clone(),
+                        subject,
+                        body,
+                        cell(cc_index),
+                        cell(bcc_index),
+                        attachments.unwrap_or_default(),
+                    );
+                    if options.draft {
+                        create_draft(gmail, params).await
+                            .map(|d| Delivered::Drafted(DraftResponse::from_draft(&d).id))
+                    } else {
+                        send_message(gmail, params).await.map(|m| Delivered::Sent(m.id))
+                    }
+                }
+                Err(e) => Err(e),
+            },
+        };
+
+        let new_status = match delivered {
+            Ok(Delivered::Sent(id)) => {
+                result.sent += 1;
+                outcome.status = "sent".to_string();
+                outcome.message_id = Some(id.clone());
+                Some(format!("sent:{}", id))
+            }
+            Ok(Delivered::Drafted(id)) => {
+                result.drafted += 1;
+                outcome.status = "drafted".to_string();
+                outcome.draft_id = Some(id.clone());
+                Some(format!("draft:{}", id))
+            }
+            Err(e) => {
+                result.failed += 1;
+                outcome.status = "failed".to_string();
+                outcome.error = Some(e.to_string());
+                let code = CliError::from(&e).error_code;
+                if matches!(code, ErrorCode::RateLimitExceeded | ErrorCode::QuotaExceeded) {
+                    result.stopped = Some(format!("Gmail sending limit reached: {}", e));
+                    None
+                } else if status.starts_with("draft:") {
+                    // Keep the draft reference so a later run can retry it
+                    None
+                } else {
+                    Some(format!("error: {}", e))
+                }
+            }
+        };
+
+        progress::emit(ProgressEvent::Item {
+            operation: "merge",
+            current: index + 1,
+            total: table.rows.len(),
+            name: to,
+            status: outcome.status.clone(),
+        });
+        result.rows.push(outcome);
+
+        if let Some(status) = new_status {
+            if let Err(e) = store.set(table, row_number, &status).await {
+                // Without a recorded status a re-run would send this row again
+                result.stopped = Some(format!("Failed to record status for row {}: {}", row_number, e));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+enum Delivered {
+    Sent(String),
+    Drafted(String),
+}
+
+fn compose(
+    options: &MergeOptions,
+    to: String,
+    subject: String,
+    body: String,
+    cc: Option<String>,
+    bcc: Option<String>,
+    attachments: Vec<Attachment>,
+) -> ComposeParams {
+    let (body, is_html, text_body) = match options.format {
+        BodyFormat::Plain => (body, false, None),
+        BodyFormat::Html => (body, true, None),
+        BodyFormat::Markdown => (crate::utils::markdown_to_html(&body), true, Some(crate::utils::markdown_to_text(&body))),
+    };
+    ComposeParams {
+        to,
+        subject,
+        body,
+        from: None,
+        cc,
+        bcc,
+        in_reply_to: None,
+        references: None,
+        thread_id: None,
+        is_html,
+        text_body,
+        inline_images: Vec::new(),
+        attachments,
+    }
+}
+
+/// Load attachments listed in a cell (separated by `;` or `,`)
+fn load_row_attachments(paths: &str) -> Result<Vec<Attachment>> {
+    paths
+        .split([';', ','])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|path| {
+            load_attachment(path).map_err(|e| {
+                WorkspaceError::InvalidInput(format!("Failed to load attachment '{}': {}", path, e))
+            })
+        })
+        .collect()
+}
+
+/// Replace `{{column}}` placeholders with values from `row`
+///
+/// Column names match case-insensitively; an unknown column is an error.
+/// Values are escaped for `format`, so cell contents are always shown as
+/// text: HTML-escaped for HTML, with every Markdown/HTML metacharacter
+/// backslash-escaped for Markdown, and unchanged for plain text.
+pub fn render_template(template: &str, headers: &[String], row: &[String], format: BodyFormat) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            return Ok(output);
+        };
+
+        let name = after[..end].trim();
+        let index = headers.iter().position(|h| h.eq_ignore_ascii_case(name)).ok_or_else(|| {
+            WorkspaceError::InvalidInput(format!("Template refers to unknown column '{{{{{}}}}}'", name))
+        })?;
+        let value = row.get(index).map(String::as_str).unwrap_or("");
+        match format {
+            BodyFormat::Plain => output.push_str(value),
+            BodyFormat::Html => {
+                output.push_str(&value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;"));
+            }
+            BodyFormat::Markdown => output.push_str(&escape_markdown(value)),
+        }
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Backslash-escape ASCII punctuation so Markdown renders `value` literally
+///
+/// CommonMark allows escaping any ASCII punctuation, which also covers
+/// inline HTML (`<`) and entities (`&`).
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn cell_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Split an A1 cell reference into a 0-based column and 1-based row
+fn parse_cell(cell: &str) -> (u32, u32) {
+    let letters: String = cell.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    let column = letters
+        .chars()
+        .fold(0u32, |acc, c| acc * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1))
+        .saturating_sub(1);
+    let row = cell[letters.len()..].parse().unwrap_or(1);
+    (column, row)
+}
+
+/// Column letters for a 0-based column index (0 -> A, 26 -> AA)
+fn column_letter(mut index: u32) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let headers = vec!["first_name".to_string(), "Company".to_string()];
+        let row = vec!["Ada".to_string(), "R&D <Labs>".to_string()];
+
+        assert_eq!(
+            render_template("Hi {{first_name}} at {{ company }}", &headers, &row, BodyFormat::Plain).unwrap(),
+            "Hi Ada at R&D <Labs>"
+        );
+        assert_eq!(
+            render_template("<p>{{company}}</p>", &headers, &row, BodyFormat::Html).unwrap(),
+            "<p>R&amp;D &lt;Labs&gt;</p>"
+        );
+        assert!(render_template("{{missing}}", &headers, &row, BodyFormat::Plain).is_err());
+
+        let row = vec!["*Ada*".to_string(), "<img src=x> [R&D](http://evil)".to_string()];
+        let markdown = render_template("Hi **{{first_name}}** at {{company}}", &headers, &row, BodyFormat::Markdown).unwrap();
+        let html = crate::utils::markdown_to_html(&markdown);
+        assert!(html.contains("<strong>*Ada*</strong>"));
+        assert!(html.contains("&lt;img src=x&gt; [R&amp;D](http://evil)"));
+        assert!(!html.contains("<img") && !html.contains("<a "));
+    }
+
+    #[test]
+    fn test_cell_addresses() {
+        assert_eq!(parse_cell("A1"), (0, 1));
+        assert_eq!(parse_cell("AB12"), (27, 12));
+        assert_eq!(parse_cell("C"), (2, 1));
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(27), "AB");
+    }
+}
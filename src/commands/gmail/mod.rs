@@ -1,12 +1,24 @@
 pub mod types;
 pub mod list;
 pub mod get;
+pub mod extract;
 pub mod send;
+pub mod drafts;
 pub mod delete;
 pub mod trash;
 pub mod labels;
 pub mod modify;
 pub mod filters;
+pub mod filter_sync;
+pub mod threads;
+pub mod history;
+pub mod bulk;
+pub mod merge;
+pub mod archive;
+pub mod attachments;
+pub mod settings;
+pub mod queue;
+pub mod stats;
 
 // Re-export main types and functions for convenience
 pub use types::{
@@ -18,13 +30,29 @@ pub use types::{
     ListMessagesResponse,
     MessageRef,
     SendMessageRequest,
+    Thread,
+    ListThreadsResponse,
+    Conversation,
+    RawMessage,
 };
 
 pub use list::{list_messages, ListParams};
-pub use get::{get_message, extract_body, get_header, list_attachments, get_attachment, download_attachment, AttachmentInfo, AttachmentData};
+pub use get::{get_message, get_raw_message, get_message_minimal, minimal_message, extract_body, get_header, list_attachments, get_attachment, download_attachment, AttachmentInfo, AttachmentData};
+pub use extract::{extract_message, unsubscribe, ExtractedMessage, Link, Unsubscribe, CalendarInvite, AuthenticationResults, TrackingPixel, UnsubscribeResult};
 pub use send::{send_message, create_draft, ComposeParams, Attachment, load_attachment, extract_forward_metadata, build_forward_body, ForwardMetadata};
+pub use drafts::{list_drafts, get_draft, get_draft_minimal, update_draft, send_draft, delete_draft, DraftUpdate};
 pub use delete::{delete_message, batch_delete};
 pub use trash::{trash_message, untrash_message};
-pub use labels::{list_labels, get_label, modify_labels, add_labels, remove_labels, Label, ListLabelsResponse};
+pub use labels::{list_labels, get_label, modify_labels, add_labels, remove_labels, create_label, update_label, delete_label, find_label, resolve_label_ids, Label, LabelColor, LabelResolver, LabelUpdate, ListLabelsResponse};
 pub use modify::{mark_read, mark_unread, star_message, unstar_message, archive_message, move_to_inbox};
 pub use filters::{Filter, FilterCriteria, FilterAction, ListFiltersResponse, list_filters, get_filter, create_filter, delete_filter, build_filter};
+pub use filter_sync::{export_filters, apply as apply_filters, plan_changes, load_filters_file, render_filters, ApplyOptions, ApplyResult, FilterPlan, FilterSpec, FiltersFile, FiltersFormat};
+pub use threads::{list_threads, get_thread, get_conversation, condense_thread, strip_quoted_text};
+pub use history::{get_profile, list_history, get_changes, sync, HistoryParams, SyncResult, SyncState};
+pub use bulk::{bulk, batch_modify, collect_message_ids, BulkAction, BulkResult};
+pub use merge::{load_table, run_merge, render_template, BodyFormat, MergeOptions, MergeResult, MergeSource, MergeTable};
+pub use archive::{export, import, ArchiveFormat, ExportOptions, ExportResult, ImportOptions, ImportResult};
+pub use attachments::{pull as pull_attachments, ManifestEntry, PullOptions, PullResult, PullTarget};
+pub use settings::{get_vacation, set_vacation, list_send_as, get_send_as, update_signature, get_forwarding, get_imap, get_pop, resolve_send_as, SendAs, VacationSettings, ForwardingSettings, ImapSettings, PopSettings};
+pub use queue::{schedule, cancel as cancel_scheduled, run_due, parse_send_at, QueuedMessage, QueueStatus, QueueSummary, RunResult, SendQueue};
+pub use stats::{stats, build_report, StatsOptions, StatsReport};
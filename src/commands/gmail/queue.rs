@@ -0,0 +1,503 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::config::Config;
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use super::drafts::{delete_draft, send_draft};
+use super::send::{create_draft, send_message, ComposeParams};
+
+/// Failed sends are retried on later runs up to this many attempts
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// A claimed entry older than this belongs to a run that died
+pub const STALE_CLAIM_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    /// Waiting for its send time (or a retry)
+    Pending,
+    /// Claimed by a running `queue run`; left behind if that run was interrupted
+    Sending,
+    /// Gave up after [`MAX_ATTEMPTS`]
+    Failed,
+}
+
+/// A message waiting to be sent, stored as one JSON file in the queue directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedMessage {
+    pub id: String,
+    /// Account the message is sent from; `run` only sends the current account's messages
+    pub account: String,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Gmail draft holding the message until it is sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft_id: Option<String>,
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub message: ComposeParams,
+}
+
+/// Queue entry without the message content, for listings
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSummary {
+    pub id: String,
+    pub account: String,
+    pub send_at: DateTime<Utc>,
+    pub status: QueueStatus,
+    pub to: String,
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    pub attachments: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft_id: Option<String>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl From<&QueuedMessage> for QueueSummary {
+    fn from(entry: &QueuedMessage) -> Self {
+        Self {
+            id: entry.id.clone(),
+            account: entry.account.clone(),
+            send_at: entry.send_at,
+            status: entry.status,
+            to: entry.message.to.clone(),
+            subject: entry.message.subject.clone(),
+            from: entry.message.from.clone(),
+            attachments: entry.message.attachments.len(),
+            draft_id: entry.draft_id.clone(),
+            attempts: entry.attempts,
+            last_error: entry.last_error.clone(),
+        }
+    }
+}
+
+/// A message sent by [`run_due`]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentEntry {
+    pub id: String,
+    pub message_id: String,
+    pub thread_id: String,
+}
+
+/// Result of one pass over the queue
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResult {
+    pub sent: Vec<SentEntry>,
+    /// Entries whose draft was deleted in Gmail, which cancels them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cancelled: Vec<String>,
+    /// Entries that failed this time (kept for retry unless now `failed`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    /// Entries still waiting for their send time
+    pub pending: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_send_at: Option<DateTime<Utc>>,
+}
+
+/// Durable queue of scheduled messages, one file per entry
+///
+/// Entries are written atomically (temporary file and rename). A run claims
+/// an entry by renaming `<id>.json` to `<id>.sending`, so overlapping runs
+/// (cron plus `--watch`) never send the same message twice.
+pub struct SendQueue {
+    dir: PathBuf,
+}
+
+impl SendQueue {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The queue in `gmail_queue/` under the config directory
+    pub fn open_default() -> Result<Self> {
+        Config::config_dir()
+            .map(|dir| Self::new(dir.join("gmail_queue")))
+            .ok_or_else(|| WorkspaceError::Config("Could not determine config directory".to_string()))
+    }
+
+    /// Store a new entry
+    pub fn add(&self, entry: &QueuedMessage) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        self.write(entry)
+    }
+
+    /// All entries, soonest first
+    ///
+    /// Files that can't be read as entries are reported on stderr and
+    /// skipped, so one bad file doesn't block the rest of the queue.
+    pub fn list(&self) -> Result<Vec<QueuedMessage>> {
+        let mut entries = Vec::new();
+        if !self.dir.exists() {
+            return Ok(entries);
+        }
+
+        for item in std::fs::read_dir(&self.dir)? {
+            let path = item?.path();
+            let sending = match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => false,
+                Some("sending") => true,
+                _ => continue,
+            };
+            let mut entry = match Self::read(&path) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("Warning: skipping queue entry {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if sending {
+                entry.status = QueueStatus::Sending;
+            }
+            entries.push(entry);
+        }
+
+        entries.sort_by_key(|e| e.send_at);
+        Ok(entries)
+    }
+
+    /// Remove an entry (in any state) and return it
+    pub fn remove(&self, id: &str) -> Result<QueuedMessage> {
+        for path in [self.path(id, "json"), self.path(id, "sending")] {
+            if path.exists() {
+                let entry = Self::read(&path)?;
+                std::fs::remove_file(&path)?;
+                return Ok(entry);
+            }
+        }
+        Err(WorkspaceError::NotFound(format!("No queued message '{}'", id)))
+    }
+
+    /// Put a failed entry, or one claimed by a run that died, back in the queue
+    ///
+    /// Claims younger than [`STALE_CLAIM_MINUTES`] may still be sending and
+    /// are refused. The entry is due right away with its attempts reset.
+    pub fn retry(&self, id: &str, now: DateTime<Utc>) -> Result<QueuedMessage> {
+        let sending = self.path(id, "sending");
+        let (path, mut entry) = if sending.exists() {
+            let claimed: DateTime<Utc> = std::fs::metadata(&sending)?.modified()?.into();
+            if now - claimed < Duration::minutes(STALE_CLAIM_MINUTES) {
+                return Err(WorkspaceError::InvalidInput(format!(
+                    "Queued message '{}' was claimed {} minutes ago and may still be sending; retry after {} minutes",
+                    id, (now - claimed).num_minutes(), STALE_CLAIM_MINUTES
+                )));
+            }
+            let entry = Self::read(&sending)?;
+            (Some(sending), entry)
+        } else {
+            let json = self.path(id, "json");
+            if !json.exists() {
+                return Err(WorkspaceError::NotFound(format!("No queued message '{}'", id)));
+            }
+            let entry = Self::read(&json)?;
+            if entry.status != QueueStatus::Failed {
+                return Err(WorkspaceError::InvalidInput(format!("Queued message '{}' is already pending", id)));
+            }
+            (None, entry)
+        };
+
+        entry.status = QueueStatus::Pending;
+        entry.attempts = 0;
+        entry.send_at = entry.send_at.min(now);
+        self.write(&entry)?;
+        if let Some(path) = path {
+            std::fs::remove_file(path)?;
+        }
+        Ok(entry)
+    }
+
+    /// Take a pending entry for sending; false if another run got it first
+    fn claim(&self, id: &str) -> bool {
+        let sending = self.path(id, "sending");
+        if std::fs::rename(self.path(id, "json"), &sending).is_err() {
+            return false;
+        }
+        // The modification time marks when the claim was made, for `retry`
+        if let Ok(file) = std::fs::File::options().write(true).open(&sending) {
+            let _ = file.set_modified(std::time::SystemTime::now());
+        }
+        true
+    }
+
+    /// Put a claimed entry back with updated state
+    fn release(&self, entry: &QueuedMessage) -> Result<()> {
+        self.write(entry)?;
+        std::fs::remove_file(self.path(&entry.id, "sending"))?;
+        Ok(())
+    }
+
+    /// Drop a claimed entry once it is sent or cancelled
+    fn finish(&self, id: &str) -> Result<()> {
+        std::fs::remove_file(self.path(id, "sending"))?;
+        Ok(())
+    }
+
+    fn read(path: &Path) -> Result<QueuedMessage> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn write(&self, entry: &QueuedMessage) -> Result<()> {
+        let path = self.path(&entry.id, "json");
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(entry)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn path(&self, id: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", sanitize_id(id), extension))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Queue `message` to be sent at `send_at`
+///
+/// With `create_draft`, the message is saved as a Gmail draft right away so
+/// it shows up in the Gmail UI; the draft is sent when due, and deleting it
+/// in Gmail cancels the scheduled send.
+pub async fn schedule(
+    client: &ApiClient,
+    queue: &SendQueue,
+    account: &str,
+    message: ComposeParams,
+    send_at: DateTime<Utc>,
+    with_draft: bool,
+) -> Result<QueueSummary> {
+    let draft_id = if with_draft {
+        let draft = create_draft(client, message.clone()).await?;
+        draft.get("id").and_then(|id| id.as_str()).map(String::from)
+    } else {
+        None
+    };
+
+    let entry = QueuedMessage {
+        id: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+        account: account.to_string(),
+        send_at,
+        created_at: Utc::now(),
+        draft_id,
+        status: QueueStatus::Pending,
+        attempts: 0,
+        last_error: None,
+        message,
+    };
+    queue.add(&entry)?;
+    Ok(QueueSummary::from(&entry))
+}
+
+/// Cancel a queued message, deleting its Gmail draft if it has one
+pub async fn cancel(client: &ApiClient, queue: &SendQueue, id: &str) -> Result<QueueSummary> {
+    let entry = queue.remove(id)?;
+    if let Some(ref draft_id) = entry.draft_id {
+        match delete_draft(client, draft_id).await {
+            Ok(()) => {}
+            Err(WorkspaceError::Api(ref e)) if e.code == 404 => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(QueueSummary::from(&entry))
+}
+
+/// Send every pending message of `account` whose time has come
+pub async fn run_due(client: &ApiClient, queue: &SendQueue, account: &str, now: DateTime<Utc>) -> Result<RunResult> {
+    let mut result = RunResult::default();
+    let entries: Vec<QueuedMessage> = queue.list()?.into_iter()
+        .filter(|e| e.account == account && e.status == QueueStatus::Pending)
+        .collect();
+    let due: Vec<&QueuedMessage> = entries.iter().filter(|e| e.send_at <= now).collect();
+
+    for waiting in entries.iter().filter(|e| e.send_at > now) {
+        result.pending += 1;
+        result.next_send_at = Some(result.next_send_at.map_or(waiting.send_at, |t| t.min(waiting.send_at)));
+    }
+
+    for (index, entry) in due.iter().enumerate() {
+        if !queue.claim(&entry.id) {
+            continue;
+        }
+
+        let outcome = match entry.draft_id {
+            Some(ref draft_id) => send_draft(client, draft_id).await,
+            None => send_message(client, entry.message.clone()).await,
+        };
+        let status = match outcome {
+            Ok(message) => {
+                queue.finish(&entry.id)?;
+                result.sent.push(SentEntry {
+                    id: entry.id.clone(),
+                    message_id: message.id,
+                    thread_id: message.thread_id,
+                });
+                "sent".to_string()
+            }
+            Err(WorkspaceError::Api(ref e)) if e.code == 404 && entry.draft_id.is_some() => {
+                queue.finish(&entry.id)?;
+                result.cancelled.push(entry.id.clone());
+                "cancelled (draft deleted)".to_string()
+            }
+            Err(e) => {
+                let mut retry = (*entry).clone();
+                retry.attempts += 1;
+                retry.last_error = Some(e.to_string());
+                if retry.attempts >= MAX_ATTEMPTS {
+                    retry.status = QueueStatus::Failed;
+                }
+                queue.release(&retry)?;
+                result.errors.push(format!("{}: {}", entry.id, e));
+                format!("failed: {}", e)
+            }
+        };
+
+        progress::emit(ProgressEvent::Item {
+            operation: "queue",
+            current: index + 1,
+            total: due.len(),
+            name: entry.id.clone(),
+            status,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Parse `--at`: an RFC 3339 time, a local `YYYY-MM-DD HH:MM`, or a
+/// relative offset like `+30m`, `+2h`, `+1d` or `+1w`
+pub fn parse_send_at(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    let invalid = || WorkspaceError::InvalidInput(format!(
+        "Invalid time '{}': use RFC 3339, 'YYYY-MM-DD HH:MM' (local) or +30m/+2h/+1d/+1w",
+        value
+    ));
+
+    if let Some(offset) = value.strip_prefix('+').or_else(|| value.strip_prefix("in ")) {
+        let offset = offset.trim();
+        let split = offset.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let amount: i64 = offset[..split].parse().map_err(|_| invalid())?;
+        let duration = match offset[split..].trim() {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(amount),
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(amount),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(amount),
+            "d" | "day" | "days" => Duration::days(amount),
+            "w" | "week" | "weeks" => Duration::weeks(amount),
+            _ => return Err(invalid()),
+        };
+        return Ok(now + duration);
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|local| Local.from_local_datetime(&local).earliest())
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(invalid)
+}
+
+/// Keep IDs from escaping the queue directory
+fn sanitize_id(id: &str) -> String {
+    id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::send::Attachment;
+
+    #[test]
+    fn test_parse_send_at() {
+        let now = DateTime::parse_from_rfc3339("2026-03-02T08:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_send_at("+90m", now).unwrap(), now + Duration::minutes(90));
+        assert_eq!(parse_send_at("in 2 hours", now).unwrap(), now + Duration::hours(2));
+        assert_eq!(parse_send_at("+1d", now).unwrap(), now + Duration::days(1));
+        assert_eq!(
+            parse_send_at("2026-03-09T09:00:00+01:00", now).unwrap(),
+            DateTime::parse_from_rfc3339("2026-03-09T08:00:00Z").unwrap()
+        );
+        assert!(parse_send_at("2026-03-09 09:00", now).is_ok());
+        assert!(parse_send_at("next monday", now).is_err());
+        assert!(parse_send_at("+5 fortnights", now).is_err());
+    }
+
+    #[test]
+    fn test_queue_round_trip_and_claim() {
+        let dir = std::env::temp_dir().join(format!("workspace-cli-queue-{}", uuid::Uuid::new_v4()));
+        let queue = SendQueue::new(&dir);
+        let entry = QueuedMessage {
+            id: "abc123".to_string(),
+            account: "me@example.com".to_string(),
+            send_at: Utc::now(),
+            created_at: Utc::now(),
+            draft_id: None,
+            status: QueueStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            message: ComposeParams {
+                to: "you@example.com".to_string(),
+                subject: "Later".to_string(),
+                body: "Hi".to_string(),
+                from: None,
+                cc: None,
+                bcc: None,
+                in_reply_to: None,
+                references: None,
+                thread_id: None,
+                is_html: false,
+                text_body: None,
+                inline_images: Vec::new(),
+                attachments: vec![Attachment {
+                    filename: "a.bin".to_string(),
+                    content: vec![0, 159, 146, 150],
+                    mime_type: "application/octet-stream".to_string(),
+                }],
+            },
+        };
+        queue.add(&entry).unwrap();
+
+        let listed = queue.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].message.attachments[0].content, vec![0, 159, 146, 150]);
+
+        // A foreign file doesn't hide the real entries
+        std::fs::write(dir.join("notes.json"), "not an entry").unwrap();
+        assert_eq!(queue.list().unwrap().len(), 1);
+
+        assert!(queue.claim("abc123"));
+        assert!(!queue.claim("abc123"));
+        assert_eq!(queue.list().unwrap()[0].status, QueueStatus::Sending);
+
+        // A fresh claim may still be sending; a stale one is put back
+        assert!(queue.retry("abc123", Utc::now()).is_err());
+        let stale = Utc::now() + Duration::minutes(STALE_CLAIM_MINUTES + 1);
+        let retried = queue.retry("abc123", stale).unwrap();
+        assert_eq!(retried.status, QueueStatus::Pending);
+        assert_eq!(queue.list().unwrap()[0].status, QueueStatus::Pending);
+        assert!(queue.retry("abc123", stale).is_err());
+        assert!(queue.claim("abc123"));
+
+        queue.remove("abc123").unwrap();
+        assert!(queue.list().unwrap().is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -1,16 +1,35 @@
 use crate::client::ApiClient;
 use crate::error::Result;
-use crate::utils::base64::encode_base64url_string;
+use crate::utils::base64::{encode_base64url_string, serde_base64url};
+use crate::utils::charset::decode_encoded_words;
+use serde::{Deserialize, Serialize};
 use super::types::Message;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
 
 /// Represents a file attachment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Attachment {
     pub filename: String,
+    #[serde(with = "serde_base64url")]
     pub content: Vec<u8>,
     pub mime_type: String,
 }
 
+/// An image embedded in an HTML body and referenced as `cid:<cid>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineImage {
+    pub cid: String,
+    pub filename: String,
+    #[serde(with = "serde_base64url")]
+    pub content: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// A message to compose; serializable so it can be queued for later sending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ComposeParams {
     pub to: String,
     pub subject: String,
@@ -26,6 +45,10 @@ pub struct ComposeParams {
     pub thread_id: Option<String>,
     /// Whether the body is HTML content
     pub is_html: bool,
+    /// Plain-text alternative for an HTML body (generated from the HTML when None)
+    pub text_body: Option<String>,
+    /// Images referenced from the HTML body via `cid:` URLs
+    pub inline_images: Vec<InlineImage>,
     /// File attachments
     pub attachments: Vec<Attachment>,
 }
@@ -65,7 +88,7 @@ pub async fn create_draft(client: &ApiClient, params: ComposeParams) -> Result<s
     client.post("/users/me/drafts", &request).await
 }
 
-fn build_raw_email(params: &ComposeParams) -> String {
+pub(super) fn build_raw_email(params: &ComposeParams) -> String {
     let mut email = String::new();
 
     // Add Date header (RFC 2822 requirement)
@@ -118,59 +141,80 @@ fn build_raw_email(params: &ComposeParams) -> String {
 
     email.push_str("MIME-Version: 1.0\r\n");
 
-    // If we have attachments, use multipart/mixed format
-    if !params.attachments.is_empty() {
-        let boundary = format!("boundary_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
-        email.push_str(&format!("Content-Type: multipart/mixed; boundary=\"{}\"\r\n", boundary));
-        email.push_str("\r\n");
-
-        // Body part
-        email.push_str(&format!("--{}\r\n", boundary));
-        if params.is_html {
-            email.push_str("Content-Type: text/html; charset=utf-8\r\n");
-        } else {
-            email.push_str("Content-Type: text/plain; charset=utf-8\r\n");
-        }
-        email.push_str("\r\n");
-        email.push_str(&params.body);
-        email.push_str("\r\n");
-
-        // Attachment parts
-        for attachment in &params.attachments {
-            email.push_str(&format!("--{}\r\n", boundary));
-            email.push_str(&format!("Content-Type: {}; name=\"{}\"\r\n",
-                attachment.mime_type,
-                sanitize_header(&attachment.filename)));
-            email.push_str("Content-Transfer-Encoding: base64\r\n");
-            email.push_str(&format!("Content-Disposition: attachment; filename=\"{}\"\r\n",
-                sanitize_header(&attachment.filename)));
-            email.push_str("\r\n");
-
-            // Base64 encode the attachment content with line breaks every 76 chars
-            let encoded = BASE64_STANDARD.encode(&attachment.content);
-            for chunk in encoded.as_bytes().chunks(76) {
-                email.push_str(std::str::from_utf8(chunk).unwrap_or(""));
-                email.push_str("\r\n");
-            }
+    // Body entity: text/plain, or multipart/alternative (text + HTML, with a
+    // multipart/related wrapper around the HTML when there are inline images)
+    let body = if params.is_html {
+        let text = params.text_body.clone()
+            .unwrap_or_else(|| crate::utils::html_to_text(&params.body));
+        let mut html = text_entity("text/html", &params.body);
+        if !params.inline_images.is_empty() {
+            let mut parts = vec![html];
+            parts.extend(params.inline_images.iter().map(|image| {
+                let disposition = format!("inline; filename=\"{}\"", sanitize_header(&image.filename));
+                binary_entity(&image.mime_type, &image.filename, &disposition, Some(&image.cid), &image.content)
+            }));
+            html = multipart_entity("related", &parts);
         }
-
-        // Closing boundary
-        email.push_str(&format!("--{}--\r\n", boundary));
+        multipart_entity("alternative", &[text_entity("text/plain", &text), html])
     } else {
-        // No attachments - simple email
-        if params.is_html {
-            email.push_str("Content-Type: text/html; charset=utf-8\r\n");
-        } else {
-            email.push_str("Content-Type: text/plain; charset=utf-8\r\n");
-        }
+        text_entity("text/plain", &params.body)
+    };
 
-        email.push_str("\r\n");
-        email.push_str(&params.body);
+    // If we have attachments, use multipart/mixed format
+    if params.attachments.is_empty() {
+        email.push_str(&body);
+    } else {
+        let mut parts = vec![body];
+        parts.extend(params.attachments.iter().map(|attachment| {
+            let disposition = format!("attachment; filename=\"{}\"", sanitize_header(&attachment.filename));
+            binary_entity(&attachment.mime_type, &attachment.filename, &disposition, None, &attachment.content)
+        }));
+        email.push_str(&multipart_entity("mixed", &parts));
     }
 
     email
 }
 
+/// MIME entity (headers, blank line, content) for a UTF-8 text body
+fn text_entity(mime_type: &str, content: &str) -> String {
+    format!("Content-Type: {}; charset=utf-8\r\n\r\n{}", mime_type, content)
+}
+
+/// Base64-encoded MIME entity for a file, with line breaks every 76 chars
+fn binary_entity(mime_type: &str, filename: &str, disposition: &str, content_id: Option<&str>, content: &[u8]) -> String {
+    let mut entity = format!(
+        "Content-Type: {}; name=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n",
+        mime_type,
+        sanitize_header(filename)
+    );
+    if let Some(cid) = content_id {
+        entity.push_str(&format!("Content-ID: <{}>\r\n", sanitize_header(cid)));
+    }
+    entity.push_str(&format!("Content-Disposition: {}\r\n\r\n", disposition));
+
+    let encoded = BASE64_STANDARD.encode(content);
+    for chunk in encoded.as_bytes().chunks(76) {
+        entity.push_str(std::str::from_utf8(chunk).unwrap_or(""));
+        entity.push_str("\r\n");
+    }
+    entity
+}
+
+/// Multipart MIME entity wrapping `parts` with a fresh boundary
+fn multipart_entity(subtype: &str, parts: &[String]) -> String {
+    let boundary = format!("boundary_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
+    let mut entity = format!("Content-Type: multipart/{}; boundary=\"{}\"\r\n\r\n", subtype, boundary);
+    for part in parts {
+        entity.push_str(&format!("--{}\r\n", boundary));
+        entity.push_str(part);
+        if !part.ends_with("\r\n") {
+            entity.push_str("\r\n");
+        }
+    }
+    entity.push_str(&format!("--{}--\r\n", boundary));
+    entity
+}
+
 /// Metadata extracted from an original message for constructing a reply
 pub struct ReplyMetadata {
     /// Recipient for the reply (Reply-To or From of original)
@@ -203,7 +247,7 @@ pub fn extract_reply_metadata(message: &Message) -> Option<ReplyMetadata> {
     let to = get("Reply-To").or_else(|| get("From"))?;
 
     // Get subject, add "Re: " prefix if not already present
-    let original_subject = get("Subject").unwrap_or_default();
+    let original_subject = get("Subject").map(|s| decode_encoded_words(&s)).unwrap_or_default();
     let subject = if original_subject.to_lowercase().starts_with("re:") {
         original_subject
     } else {
@@ -272,11 +316,11 @@ pub fn extract_forward_metadata(message: &Message, body: &str) -> Option<Forward
     let payload = message.payload.as_ref()?;
     let headers = &payload.headers;
 
-    // Helper to get header value by name (case-insensitive)
+    // Helper to get header value by name (case-insensitive), decoded for display
     let get = |name: &str| -> Option<String> {
         headers.iter()
             .find(|h| h.name.eq_ignore_ascii_case(name))
-            .map(|h| h.value.clone())
+            .map(|h| decode_encoded_words(&h.value))
     };
 
     // Get subject, add "Fwd: " prefix if not already present
@@ -318,7 +362,7 @@ pub fn build_forward_body(metadata: &ForwardMetadata, user_message: Option<&str>
     body.push_str(&format!("Date: {}\n", metadata.original_date));
     body.push_str(&format!("Subject: {}\n", metadata.subject.trim_start_matches("Fwd: ")));
     body.push_str(&format!("To: {}\n", metadata.original_to));
-    body.push_str("\n");
+    body.push('\n');
     body.push_str(&metadata.original_body);
 
     body
@@ -369,3 +413,57 @@ pub fn load_attachment(path: &str) -> std::io::Result<Attachment> {
         mime_type,
     })
 }
+
+/// Load an inline image from a `cid=path` argument
+pub fn load_inline_image(spec: &str) -> std::io::Result<InlineImage> {
+    let (cid, path) = spec.split_once('=').ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "expected cid=path")
+    })?;
+    let attachment = load_attachment(path)?;
+    Ok(InlineImage {
+        cid: cid.trim().to_string(),
+        filename: attachment.filename,
+        content: attachment.content,
+        mime_type: attachment.mime_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_body_with_inline_image_nests_alternative_and_related() {
+        let params = ComposeParams {
+            to: "alice@example.com".to_string(),
+            subject: "Report".to_string(),
+            body: "<p>See <img src=\"cid:chart\"></p>".to_string(),
+            from: None,
+            cc: None,
+            bcc: None,
+            in_reply_to: None,
+            references: None,
+            thread_id: None,
+            is_html: true,
+            text_body: Some("See the chart".to_string()),
+            inline_images: vec![InlineImage {
+                cid: "chart".to_string(),
+                filename: "chart.png".to_string(),
+                content: vec![1, 2, 3],
+                mime_type: "image/png".to_string(),
+            }],
+            attachments: Vec::new(),
+        };
+
+        let raw = build_raw_email(&params);
+        let alternative = raw.find("multipart/alternative").unwrap();
+        let plain = raw.find("text/plain").unwrap();
+        let related = raw.find("multipart/related").unwrap();
+        let html = raw.find("text/html").unwrap();
+        assert!(alternative < plain && plain < related && related < html);
+        assert!(raw.contains("See the chart"));
+        assert!(raw.contains("Content-ID: <chart>"));
+        assert!(raw.contains("Content-Disposition: inline; filename=\"chart.png\""));
+        assert!(!raw.contains("multipart/mixed"));
+    }
+}
@@ -0,0 +1,314 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::error::{Result, WorkspaceError};
+
+/// Vacation responder (out-of-office auto-reply) settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacationSettings {
+    #[serde(default)]
+    pub enable_auto_reply: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body_plain_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body_html: Option<String>,
+    /// Only reply to senders in the user's contacts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrict_to_contacts: Option<bool>,
+    /// Only reply to senders in the user's Workspace domain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrict_to_domain: Option<bool>,
+    /// Start of the auto-reply window (epoch milliseconds, as a string)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    /// End of the auto-reply window (epoch milliseconds, as a string)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+}
+
+/// A send-as alias (the primary address is one too)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendAs {
+    pub send_as_email: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to_address: Option<String>,
+    /// HTML signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub treat_as_alias: bool,
+    /// "accepted" or "pending"; absent for the primary address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_status: Option<String>,
+}
+
+impl SendAs {
+    /// Whether mail can be sent from this address
+    pub fn is_usable(&self) -> bool {
+        self.verification_status.as_deref().is_none_or(|s| s == "accepted")
+    }
+
+    /// From header value: the display name (RFC 2047 encoded if needed) and address
+    pub fn mailbox(&self) -> String {
+        format_mailbox(&self.display_name, &self.send_as_email)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSendAsResponse {
+    #[serde(default)]
+    pub send_as: Vec<SendAs>,
+}
+
+/// Automatic forwarding of all incoming mail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoForwarding {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+    /// What happens to the original: leaveInInbox, archive, trash or markRead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disposition: Option<String>,
+}
+
+/// An address mail may be forwarded to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardingAddress {
+    pub forwarding_email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListForwardingAddressesResponse {
+    #[serde(default)]
+    pub forwarding_addresses: Vec<ForwardingAddress>,
+}
+
+/// Auto-forwarding together with the verified forwarding addresses
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardingSettings {
+    pub auto_forwarding: AutoForwarding,
+    pub forwarding_addresses: Vec<ForwardingAddress>,
+}
+
+/// IMAP access settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImapSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_expunge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expunge_behavior: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_folder_size: Option<i64>,
+}
+
+/// POP access settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PopSettings {
+    /// disabled, allMail or fromNowOn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_window: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disposition: Option<String>,
+}
+
+/// Get the vacation responder settings
+pub async fn get_vacation(client: &ApiClient) -> Result<VacationSettings> {
+    client.get("/users/me/settings/vacation").await
+}
+
+/// Replace the vacation responder settings
+pub async fn set_vacation(client: &ApiClient, settings: &VacationSettings) -> Result<VacationSettings> {
+    client.put("/users/me/settings/vacation", settings).await
+}
+
+/// List send-as aliases, including the primary address
+pub async fn list_send_as(client: &ApiClient) -> Result<ListSendAsResponse> {
+    client.get("/users/me/settings/sendAs").await
+}
+
+/// Get one send-as alias by address
+pub async fn get_send_as(client: &ApiClient, email: &str) -> Result<SendAs> {
+    client.get(&format!("/users/me/settings/sendAs/{}", urlencoding::encode(email))).await
+}
+
+/// Replace the HTML signature of a send-as alias
+pub async fn update_signature(client: &ApiClient, email: &str, signature: &str) -> Result<SendAs> {
+    let request = serde_json::json!({ "signature": signature });
+    client.patch(&format!("/users/me/settings/sendAs/{}", urlencoding::encode(email)), &request).await
+}
+
+/// Get auto-forwarding and the registered forwarding addresses
+pub async fn get_forwarding(client: &ApiClient) -> Result<ForwardingSettings> {
+    let auto_forwarding: AutoForwarding = client.get("/users/me/settings/autoForwarding").await?;
+    let addresses: ListForwardingAddressesResponse = client.get("/users/me/settings/forwardingAddresses").await?;
+    Ok(ForwardingSettings {
+        auto_forwarding,
+        forwarding_addresses: addresses.forwarding_addresses,
+    })
+}
+
+/// Get IMAP settings
+pub async fn get_imap(client: &ApiClient) -> Result<ImapSettings> {
+    client.get("/users/me/settings/imap").await
+}
+
+/// Get POP settings
+pub async fn get_pop(client: &ApiClient) -> Result<PopSettings> {
+    client.get("/users/me/settings/pop").await
+}
+
+/// Find the send-as alias for `from`, or the default alias when `from` is None
+///
+/// `from` may be a bare address or `Name <address>`. Errors if the address
+/// is not a verified alias of the account.
+pub async fn resolve_send_as(client: &ApiClient, from: Option<&str>) -> Result<SendAs> {
+    let aliases = list_send_as(client).await?.send_as;
+    find_send_as(aliases, from)
+}
+
+fn find_send_as(aliases: Vec<SendAs>, from: Option<&str>) -> Result<SendAs> {
+    let Some(from) = from else {
+        return aliases.into_iter()
+            .find(|a| a.is_default)
+            .ok_or_else(|| WorkspaceError::NotFound("No default send-as address".to_string()));
+    };
+
+    let address = mailbox_address(from);
+    let available = aliases.iter()
+        .filter(|a| a.is_usable())
+        .map(|a| a.send_as_email.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let alias = aliases.iter()
+        .find(|a| a.send_as_email.eq_ignore_ascii_case(address))
+        .ok_or_else(|| WorkspaceError::InvalidInput(format!(
+            "'{}' is not a send-as address of this account (available: {})", address, available
+        )))?;
+    if !alias.is_usable() {
+        return Err(WorkspaceError::InvalidInput(format!(
+            "Send-as address '{}' is not verified yet", alias.send_as_email
+        )));
+    }
+    Ok(alias.clone())
+}
+
+/// From header for `--from`: the alias' display name unless `from` brings its own
+pub fn from_header(alias: &SendAs, from: &str) -> String {
+    match from.split_once('<') {
+        Some((name, _)) if !name.trim().is_empty() => {
+            format_mailbox(name.trim().trim_matches('"'), &alias.send_as_email)
+        }
+        _ => alias.mailbox(),
+    }
+}
+
+/// Address part of `Name <address>`, or the whole value
+fn mailbox_address(value: &str) -> &str {
+    match (value.find('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => value[start + 1..end].trim(),
+        _ => value.trim(),
+    }
+}
+
+fn format_mailbox(name: &str, email: &str) -> String {
+    if name.is_empty() {
+        email.to_string()
+    } else if name.is_ascii() {
+        format!("\"{}\" <{}>", name.replace(['"', '\\'], ""), email)
+    } else {
+        format!("=?UTF-8?B?{}?= <{}>", BASE64_STANDARD.encode(name.as_bytes()), email)
+    }
+}
+
+/// Parse a vacation start/end as epoch milliseconds
+///
+/// Accepts RFC 3339 timestamps or `YYYY-MM-DD` dates in local time. A date
+/// used as the end of the window means the end of that day.
+pub fn parse_vacation_time(value: &str, is_end: bool) -> Result<i64> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp_millis());
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        WorkspaceError::InvalidInput(format!("Invalid date '{}': use YYYY-MM-DD or RFC 3339", value))
+    })?;
+    let date = if is_end { date.succ_opt().unwrap_or(date) } else { date };
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local.from_local_datetime(&midnight)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+        .ok_or_else(|| WorkspaceError::InvalidInput(format!("Invalid local date '{}'", value)))
+}
+
+/// Turn plain text into signature HTML: escaped, with line breaks kept
+pub fn text_to_signature_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .lines()
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(email: &str, name: &str, is_default: bool, status: Option<&str>) -> SendAs {
+        SendAs {
+            send_as_email: email.to_string(),
+            display_name: name.to_string(),
+            reply_to_address: None,
+            signature: None,
+            is_primary: is_default,
+            is_default,
+            treat_as_alias: false,
+            verification_status: status.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_send_as() {
+        let aliases = vec![
+            alias("me@example.com", "Me", true, None),
+            alias("support@example.com", "Support Desk", false, Some("accepted")),
+            alias("new@example.com", "", false, Some("pending")),
+        ];
+
+        let default = find_send_as(aliases.clone(), None).unwrap();
+        assert_eq!(default.mailbox(), "\"Me\" <me@example.com>");
+
+        let support = find_send_as(aliases.clone(), Some("Help <Support@Example.com>")).unwrap();
+        assert_eq!(support.send_as_email, "support@example.com");
+        assert_eq!(from_header(&support, "Help <Support@Example.com>"), "\"Help\" <support@example.com>");
+        assert_eq!(from_header(&support, "support@example.com"), "\"Support Desk\" <support@example.com>");
+
+        assert!(find_send_as(aliases.clone(), Some("new@example.com")).is_err());
+        assert!(find_send_as(aliases, Some("stranger@example.com")).is_err());
+    }
+}
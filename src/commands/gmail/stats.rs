@@ -0,0 +1,556 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::client::batch::{BatchClient, BatchRequest};
+use crate::client::rate_limiter::TokenBucket;
+use crate::client::{gmail_costs, ApiClient, RateLimitConfig};
+use crate::error::{Result, WorkspaceError};
+use crate::output::progress::{self, ProgressEvent};
+use crate::utils::charset::decode_encoded_words;
+use super::bulk::collect_message_ids;
+use super::history::get_profile;
+use super::labels::list_labels;
+
+/// Messages per batch call; 50 metadata gets use the full per-second quota
+const BATCH_SIZE: usize = 50;
+/// Passes over messages whose batch item was rate limited or failed
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct StatsOptions {
+    pub query: Option<String>,
+    /// Only messages newer than this: 90d, 12w, 6m, 1y or YYYY-MM-DD
+    pub since: Option<String>,
+    /// Maximum messages to analyze
+    pub max: usize,
+    /// Entries in each top-N list
+    pub top: usize,
+}
+
+/// Aggregate mailbox metrics for the messages matching a query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsReport {
+    pub query: String,
+    pub generated_at: String,
+    pub totals: Totals,
+    pub top_senders: Vec<SenderStats>,
+    pub top_domains: Vec<DomainStats>,
+    pub volume_by_day: Vec<DayVolume>,
+    pub volume_by_label: Vec<LabelVolume>,
+    pub response_time: ResponseTime,
+    pub largest_messages: Vec<LargeMessage>,
+    /// Senders by total size of their messages with attachments
+    pub attachment_storage: Vec<SenderStorage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Totals {
+    pub messages: usize,
+    pub threads: usize,
+    pub unread: usize,
+    pub received: usize,
+    pub sent: usize,
+    pub with_attachments: usize,
+    /// Bytes, from Gmail's size estimates
+    pub total_size: u64,
+    /// More messages matched than --max; the report covers the newest ones
+    pub capped: bool,
+    /// Messages whose metadata could not be fetched
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenderStats {
+    pub address: String,
+    pub name: Option<String>,
+    pub messages: usize,
+    pub unread: usize,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainStats {
+    pub domain: String,
+    pub messages: usize,
+    pub unread: usize,
+    pub senders: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayVolume {
+    /// Local date, YYYY-MM-DD
+    pub date: String,
+    pub received: usize,
+    pub sent: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelVolume {
+    pub id: String,
+    pub name: String,
+    pub messages: usize,
+    pub unread: usize,
+}
+
+/// How quickly you reply in threads you took part in
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseTime {
+    pub threads: usize,
+    pub replies: usize,
+    pub average_seconds: Option<i64>,
+    pub median_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargeMessage {
+    pub id: String,
+    pub thread_id: String,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+    pub date: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenderStorage {
+    pub address: String,
+    pub messages: usize,
+    pub size: u64,
+}
+
+/// The metadata of one message that the report is built from
+#[derive(Debug, Clone)]
+pub struct MessageMeta {
+    pub id: String,
+    pub thread_id: String,
+    pub label_ids: Vec<String>,
+    /// Epoch milliseconds
+    pub internal_date: i64,
+    pub size: u64,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+}
+
+impl MessageMeta {
+    fn has_label(&self, label: &str) -> bool {
+        self.label_ids.iter().any(|l| l == label)
+    }
+
+    fn sender_address(&self) -> Option<String> {
+        self.from.as_deref().map(|from| split_mailbox(from).1)
+    }
+}
+
+/// Compute the report for the messages matching the query and --since window
+pub async fn stats(client: &ApiClient, access_token: &str, options: &StatsOptions) -> Result<StatsReport> {
+    let query = build_query(options.query.as_deref(), options.since.as_deref())?;
+    let (ids, capped) = collect_message_ids(client, &query, options.max).await?;
+
+    // Only IDs are needed to tell which messages have attachments
+    let attachment_query = format!("{} has:attachment", query).trim().to_string();
+    let (attachment_ids, _) = collect_message_ids(client, &attachment_query, options.max).await?;
+    let attachment_ids: HashSet<String> = attachment_ids.into_iter().collect();
+
+    let (messages, failed) = fetch_metadata(&ids, access_token).await?;
+    let me = get_profile(client).await?.email_address;
+    let labels: HashMap<String, String> = list_labels(client).await?.labels
+        .into_iter()
+        .map(|label| (label.id, label.name))
+        .collect();
+
+    let mut report = build_report(query, &messages, &me, &labels, &attachment_ids, options.top);
+    report.totals.capped = capped;
+    report.totals.failed = failed;
+    Ok(report)
+}
+
+/// Combine the user's query with the --since window
+pub fn build_query(query: Option<&str>, since: Option<&str>) -> Result<String> {
+    let mut parts: Vec<String> = query.map(str::trim).filter(|q| !q.is_empty()).map(str::to_string).into_iter().collect();
+
+    if let Some(since) = since.map(str::trim) {
+        if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+            parts.push(format!("after:{}", date.format("%Y/%m/%d")));
+        } else {
+            let invalid = || WorkspaceError::InvalidInput(format!(
+                "Invalid --since '{}': use e.g. 90d, 12w, 6m, 1y or YYYY-MM-DD", since
+            ));
+            let split = since.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+            let amount: u32 = since[..split].parse().map_err(|_| invalid())?;
+            // Gmail's newer_than only knows days, months and years
+            let window = match &since[split..] {
+                "d" => format!("{}d", amount),
+                "w" => format!("{}d", amount * 7),
+                "m" => format!("{}m", amount),
+                "y" => format!("{}y", amount),
+                _ => return Err(invalid()),
+            };
+            parts.push(format!("newer_than:{}", window));
+        }
+    }
+
+    Ok(parts.join(" "))
+}
+
+/// Fetch metadata for messages with batch requests, paced to the Gmail quota
+///
+/// Items that fail (usually rate limited) are retried in later passes.
+/// Returns the metadata and the number of messages that still failed.
+pub async fn fetch_metadata(ids: &[String], access_token: &str) -> Result<(Vec<MessageMeta>, usize)> {
+    let client = BatchClient::gmail();
+    let bucket = TokenBucket::new(RateLimitConfig::gmail());
+    let mut messages = Vec::with_capacity(ids.len());
+    let mut pending: Vec<String> = ids.to_vec();
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        if pending.is_empty() {
+            break;
+        }
+        if attempt > 1 {
+            tokio::time::sleep(std::time::Duration::from_secs(2u64.pow(attempt - 1))).await;
+        }
+
+        let mut retry = Vec::new();
+        for chunk in pending.chunks(BATCH_SIZE) {
+            bucket.acquire(gmail_costs::GET * chunk.len() as u32).await
+                .map_err(|e| WorkspaceError::Config(e.to_string()))?;
+
+            let requests: Vec<BatchRequest> = chunk.iter()
+                .map(|id| BatchRequest::get(
+                    id,
+                    format!("/gmail/v1/users/me/messages/{}?format=metadata&metadataHeaders=From&metadataHeaders=Subject", id),
+                ))
+                .collect();
+
+            progress::emit(ProgressEvent::BatchStart { service: "gmail".to_string(), requests: requests.len() });
+            let started = Instant::now();
+            let responses = client.execute(requests, access_token).await
+                .map_err(|e| WorkspaceError::Config(format!("Batch request failed: {}", e)))?;
+
+            let mut succeeded = 0;
+            let mut answered = HashSet::new();
+            for response in responses {
+                answered.insert(response.id.clone());
+                match response.is_success().then(|| parse_metadata(&response.body)).flatten() {
+                    Some(meta) => {
+                        succeeded += 1;
+                        messages.push(meta);
+                    }
+                    None => retry.push(response.id),
+                }
+            }
+            retry.extend(chunk.iter().filter(|id| !answered.contains(*id)).cloned());
+            progress::emit(ProgressEvent::BatchEnd {
+                service: "gmail".to_string(),
+                succeeded,
+                failed: chunk.len() - succeeded,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            });
+        }
+        pending = retry;
+    }
+
+    Ok((messages, pending.len()))
+}
+
+fn parse_metadata(body: &serde_json::Value) -> Option<MessageMeta> {
+    let headers = body.get("payload").and_then(|p| p.get("headers")).and_then(|h| h.as_array());
+    let header = |name: &str| headers?.iter()
+        .find(|h| h.get("name").and_then(|n| n.as_str()).is_some_and(|n| n.eq_ignore_ascii_case(name)))
+        .and_then(|h| h.get("value"))
+        .and_then(|v| v.as_str())
+        .map(decode_encoded_words);
+
+    Some(MessageMeta {
+        id: body.get("id")?.as_str()?.to_string(),
+        thread_id: body.get("threadId").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        label_ids: body.get("labelIds")
+            .and_then(|v| v.as_array())
+            .map(|labels| labels.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+            .unwrap_or_default(),
+        internal_date: body.get("internalDate").and_then(|v| v.as_str()).and_then(|v| v.parse().ok()).unwrap_or(0),
+        size: body.get("sizeEstimate").and_then(|v| v.as_u64()).unwrap_or(0),
+        from: header("From"),
+        subject: header("Subject"),
+    })
+}
+
+/// Aggregate fetched metadata into the report
+///
+/// `me` is the account address, used with the SENT label to tell your own
+/// messages apart; `labels` maps label IDs to names.
+pub fn build_report(
+    query: String,
+    messages: &[MessageMeta],
+    me: &str,
+    labels: &HashMap<String, String>,
+    attachment_ids: &HashSet<String>,
+    top: usize,
+) -> StatsReport {
+    let is_mine = |m: &MessageMeta| m.has_label("SENT") || m.sender_address().is_some_and(|a| a.eq_ignore_ascii_case(me));
+
+    let mut totals = Totals {
+        messages: messages.len(),
+        threads: messages.iter().map(|m| &m.thread_id).collect::<HashSet<_>>().len(),
+        ..Default::default()
+    };
+    let mut senders: HashMap<String, SenderStats> = HashMap::new();
+    let mut domains: HashMap<String, (DomainStats, HashSet<String>)> = HashMap::new();
+    let mut days: BTreeMap<NaiveDate, DayVolume> = BTreeMap::new();
+    let mut by_label: HashMap<String, LabelVolume> = HashMap::new();
+    let mut storage: HashMap<String, SenderStorage> = HashMap::new();
+
+    for message in messages {
+        let unread = message.has_label("UNREAD");
+        let mine = is_mine(message);
+        totals.unread += unread as usize;
+        totals.total_size += message.size;
+
+        let date = local_date(message.internal_date);
+        let day = days.entry(date).or_insert_with(|| DayVolume { date: date.to_string(), received: 0, sent: 0 });
+        if mine {
+            totals.sent += 1;
+            day.sent += 1;
+        } else {
+            totals.received += 1;
+            day.received += 1;
+        }
+        let has_attachments = attachment_ids.contains(&message.id);
+        if has_attachments {
+            totals.with_attachments += 1;
+        }
+
+        for label in message.label_ids.iter().filter(|l| *l != "UNREAD") {
+            let entry = by_label.entry(label.clone()).or_insert_with(|| LabelVolume {
+                id: label.clone(),
+                name: labels.get(label).cloned().unwrap_or_else(|| label.clone()),
+                messages: 0,
+                unread: 0,
+            });
+            entry.messages += 1;
+            entry.unread += unread as usize;
+        }
+
+        // Sender metrics are about mail you received
+        let Some(from) = message.from.as_deref().filter(|_| !mine) else {
+            continue;
+        };
+        let (name, address) = split_mailbox(from);
+        let sender = senders.entry(address.clone()).or_insert_with(|| SenderStats {
+            address: address.clone(),
+            name: None,
+            messages: 0,
+            unread: 0,
+            size: 0,
+        });
+        sender.name = sender.name.take().or(name);
+        sender.messages += 1;
+        sender.unread += unread as usize;
+        sender.size += message.size;
+
+        let domain = address.rsplit_once('@').map(|(_, d)| d.to_string()).unwrap_or_default();
+        let (domain_stats, domain_senders) = domains.entry(domain.clone()).or_insert_with(|| {
+            (DomainStats { domain, messages: 0, unread: 0, senders: 0 }, HashSet::new())
+        });
+        domain_stats.messages += 1;
+        domain_stats.unread += unread as usize;
+        domain_senders.insert(address.clone());
+
+        if has_attachments {
+            let entry = storage.entry(address.clone()).or_insert_with(|| SenderStorage { address, messages: 0, size: 0 });
+            entry.messages += 1;
+            entry.size += message.size;
+        }
+    }
+
+    let mut top_senders: Vec<SenderStats> = senders.into_values().collect();
+    top_senders.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| a.address.cmp(&b.address)));
+    top_senders.truncate(top);
+
+    let mut top_domains: Vec<DomainStats> = domains.into_values()
+        .map(|(mut stats, senders)| {
+            stats.senders = senders.len();
+            stats
+        })
+        .collect();
+    top_domains.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| a.domain.cmp(&b.domain)));
+    top_domains.truncate(top);
+
+    let mut volume_by_label: Vec<LabelVolume> = by_label.into_values().collect();
+    volume_by_label.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| a.name.cmp(&b.name)));
+
+    let mut attachment_storage: Vec<SenderStorage> = storage.into_values().collect();
+    attachment_storage.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.address.cmp(&b.address)));
+    attachment_storage.truncate(top);
+
+    let mut largest: Vec<&MessageMeta> = messages.iter().collect();
+    largest.sort_by_key(|m| std::cmp::Reverse(m.size));
+    let largest_messages = largest.into_iter().take(top).map(|m| LargeMessage {
+        id: m.id.clone(),
+        thread_id: m.thread_id.clone(),
+        from: m.from.clone(),
+        subject: m.subject.clone(),
+        date: timestamp(m.internal_date),
+        size: m.size,
+    }).collect();
+
+    StatsReport {
+        query,
+        generated_at: Utc::now().to_rfc3339(),
+        totals,
+        top_senders,
+        top_domains,
+        volume_by_day: fill_days(days),
+        volume_by_label,
+        response_time: response_time(messages, is_mine),
+        largest_messages,
+        attachment_storage,
+    }
+}
+
+/// Time from the first unanswered incoming message in a thread to your reply
+///
+/// Only messages in the analyzed set count, so the query has to include
+/// your sent mail (the default does).
+fn response_time(messages: &[MessageMeta], is_mine: impl Fn(&MessageMeta) -> bool) -> ResponseTime {
+    let mut threads: HashMap<&str, Vec<&MessageMeta>> = HashMap::new();
+    for message in messages {
+        threads.entry(&message.thread_id).or_default().push(message);
+    }
+
+    let mut delays: Vec<i64> = Vec::new();
+    let mut participated = 0;
+    for thread in threads.values_mut() {
+        thread.sort_by_key(|m| m.internal_date);
+        let mut waiting_since: Option<i64> = None;
+        let mut replied = false;
+        for message in thread.iter() {
+            if is_mine(message) {
+                if let Some(since) = waiting_since.take() {
+                    delays.push((message.internal_date - since) / 1000);
+                    replied = true;
+                }
+            } else if waiting_since.is_none() {
+                waiting_since = Some(message.internal_date);
+            }
+        }
+        participated += replied as usize;
+    }
+
+    if delays.is_empty() {
+        return ResponseTime::default();
+    }
+    delays.sort_unstable();
+    let middle = delays.len() / 2;
+    let median = if delays.len().is_multiple_of(2) { (delays[middle - 1] + delays[middle]) / 2 } else { delays[middle] };
+    ResponseTime {
+        threads: participated,
+        replies: delays.len(),
+        average_seconds: Some(delays.iter().sum::<i64>() / delays.len() as i64),
+        median_seconds: Some(median),
+    }
+}
+
+/// Days from the first to the last message, with empty days included for charts
+fn fill_days(days: BTreeMap<NaiveDate, DayVolume>) -> Vec<DayVolume> {
+    let (Some(first), Some(last)) = (days.keys().next().copied(), days.keys().next_back().copied()) else {
+        return Vec::new();
+    };
+    first.iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| days.get(&date).cloned().unwrap_or_else(|| DayVolume { date: date.to_string(), received: 0, sent: 0 }))
+        .collect()
+}
+
+fn local_date(millis: i64) -> NaiveDate {
+    Local.timestamp_millis_opt(millis).single().map(|t| t.date_naive()).unwrap_or_default()
+}
+
+fn timestamp(millis: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(millis).map(|t| t.to_rfc3339()).unwrap_or_default()
+}
+
+/// Split `Name <address>` into the display name and lowercase address
+fn split_mailbox(value: &str) -> (Option<String>, String) {
+    match (value.rfind('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => {
+            let name = value[..start].trim().trim_matches('"').trim();
+            ((!name.is_empty()).then(|| name.to_string()), value[start + 1..end].trim().to_lowercase())
+        }
+        _ => (None, value.trim().to_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(id: &str, thread: &str, from: &str, labels: &[&str], minutes: i64, size: u64) -> MessageMeta {
+        MessageMeta {
+            id: id.to_string(),
+            thread_id: thread.to_string(),
+            label_ids: labels.iter().map(|l| l.to_string()).collect(),
+            internal_date: 1_760_000_000_000 + minutes * 60_000,
+            size,
+            from: Some(from.to_string()),
+            subject: Some(format!("Subject {}", id)),
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        assert_eq!(build_query(Some("in:inbox"), Some("2w")).unwrap(), "in:inbox newer_than:14d");
+        assert_eq!(build_query(None, Some("2026-01-31")).unwrap(), "after:2026/01/31");
+        assert!(build_query(None, Some("90x")).is_err());
+
+        let messages = vec![
+            meta("1", "t1", "Alice <alice@example.com>", &["INBOX", "UNREAD"], 0, 1_000),
+            meta("2", "t1", "Me <me@example.com>", &["SENT"], 30, 500),
+            meta("3", "t1", "Alice <ALICE@example.com>", &["INBOX"], 60, 2_000),
+            meta("4", "t1", "me@example.com", &["SENT"], 150, 400),
+            meta("5", "t2", "news@shop.example.org", &["CATEGORY_PROMOTIONS", "UNREAD"], 4 * 24 * 60, 90_000),
+            meta("6", "t3", "Bob <bob@example.com>", &["INBOX", "Label_1"], 10, 3_000),
+        ];
+        let labels = HashMap::from([("Label_1".to_string(), "Clients".to_string())]);
+        let attachments = HashSet::from(["5".to_string(), "2".to_string()]);
+
+        let report = build_report("".into(), &messages, "me@example.com", &labels, &attachments, 2);
+        assert_eq!((report.totals.messages, report.totals.threads, report.totals.unread), (6, 3, 2));
+        assert_eq!((report.totals.sent, report.totals.received, report.totals.with_attachments), (2, 4, 2));
+
+        assert_eq!(report.top_senders.len(), 2);
+        assert_eq!(report.top_senders[0].address, "alice@example.com");
+        assert_eq!(report.top_senders[0].messages, 2);
+        assert_eq!(report.top_senders[0].name.as_deref(), Some("Alice"));
+        assert_eq!(report.top_domains[0].domain, "example.com");
+        assert_eq!(report.top_domains[0].senders, 2);
+
+        // 30 and 90 minutes to reply in t1
+        assert_eq!(report.response_time, ResponseTime {
+            threads: 1,
+            replies: 2,
+            average_seconds: Some(3_600),
+            median_seconds: Some(3_600),
+        });
+
+        assert!(report.volume_by_day.len() >= 5);
+        assert_eq!(report.volume_by_day.iter().map(|d| d.received + d.sent).sum::<usize>(), 6);
+        assert_eq!(report.largest_messages[0].id, "5");
+        // Your own sent mail counts in the total but not per sender
+        assert_eq!(report.attachment_storage.len(), 1);
+        assert_eq!(report.attachment_storage[0].address, "news@shop.example.org");
+        assert!(report.volume_by_label.iter().any(|l| l.name == "Clients" && l.messages == 1));
+        assert!(report.volume_by_label.iter().all(|l| l.id != "UNREAD"));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::client::ApiClient;
+use crate::error::Result;
+use crate::utils::html_to_md::smart_convert;
+use super::get::{extract_body, get_header};
+use super::list::ListParams;
+use super::types::{Conversation, ConversationMessage, ListThreadsResponse, Thread};
+
+pub async fn list_threads(client: &ApiClient, params: ListParams) -> Result<ListThreadsResponse> {
+    let mut query_params = vec![
+        ("maxResults", params.max_results.to_string()),
+    ];
+
+    if let Some(ref q) = params.query {
+        query_params.push(("q", q.clone()));
+    }
+    if let Some(ref token) = params.page_token {
+        query_params.push(("pageToken", token.clone()));
+    }
+    if let Some(ref labels) = params.label_ids {
+        for label in labels {
+            query_params.push(("labelIds", label.clone()));
+        }
+    }
+
+    client.get_with_query("/users/me/threads", &query_params).await
+}
+
+pub async fn get_thread(client: &ApiClient, id: &str, format: &str) -> Result<Thread> {
+    let query = [("format", format)];
+    client.get_with_query(&format!("/users/me/threads/{}", id), &query).await
+}
+
+/// Get a thread as a chronological conversation with quoted replies and
+/// signatures stripped from each message
+pub async fn get_conversation(client: &ApiClient, id: &str) -> Result<Conversation> {
+    let thread = get_thread(client, id, "full").await?;
+    Ok(condense_thread(thread))
+}
+
+/// Reduce a full thread to `{from, date, newText}` per message, oldest first
+pub fn condense_thread(thread: Thread) -> Conversation {
+    let mut messages = thread.messages;
+    messages.sort_by_key(|m| {
+        m.internal_date.as_deref().and_then(|d| d.parse::<i64>().ok()).unwrap_or(0)
+    });
+
+    let subject = messages.first().and_then(|m| get_header(m, "Subject"));
+    let condensed: Vec<ConversationMessage> = messages
+        .iter()
+        .map(|message| {
+            let body = extract_body(message).map(|b| smart_convert(&b)).unwrap_or_default();
+            ConversationMessage {
+                id: message.id.clone(),
+                from: get_header(message, "From"),
+                date: get_header(message, "Date"),
+                new_text: strip_quoted_text(&body),
+            }
+        })
+        .collect();
+
+    Conversation {
+        thread_id: thread.id,
+        subject,
+        message_count: condensed.len(),
+        messages: condensed,
+    }
+}
+
+/// Remove quoted replies, forwarded history and signatures from a message body
+///
+/// Keeps the text up to the first reply header ("On ... wrote:", Outlook's
+/// "From:/Sent:" block or "-----Original Message-----") or signature
+/// delimiter, and drops `>`-quoted lines.
+pub fn strip_quoted_text(body: &str) -> String {
+    let body = body.replace("\r\n", "\n");
+    let lines: Vec<&str> = body.lines().collect();
+    let mut kept: Vec<&str> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if is_reply_header(&lines, i) || is_signature_start(line) {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        kept.push(line.trim_end());
+    }
+
+    // Collapse runs of blank lines left behind by removed quotes
+    let mut text = String::new();
+    let mut blank = false;
+    for line in kept {
+        if line.is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !text.is_empty() {
+            text.push('\n');
+        }
+        blank = false;
+        text.push_str(line);
+        text.push('\n');
+    }
+
+    text.trim().to_string()
+}
+
+fn is_reply_header(lines: &[&str], i: usize) -> bool {
+    let trimmed = lines[i].trim();
+    let lower = trimmed.to_lowercase();
+
+    if lower.contains("original message") && trimmed.starts_with('-') {
+        return true;
+    }
+    // Outlook separator line
+    if trimmed.len() >= 16 && trimmed.chars().all(|c| c == '_') {
+        return true;
+    }
+    // "On <date>, <name> wrote:" which mail clients often wrap onto two lines
+    if trimmed.starts_with("On ") {
+        if trimmed.ends_with("wrote:") {
+            return true;
+        }
+        if let Some(next) = lines.get(i + 1) {
+            if next.trim().ends_with("wrote:") {
+                return true;
+            }
+        }
+    }
+    // Outlook header block: "From: ..." followed closely by "Sent:" or "Date:"
+    if trimmed.starts_with("From:") {
+        return lines[i + 1..]
+            .iter()
+            .take(3)
+            .any(|l| l.trim().starts_with("Sent:") || l.trim().starts_with("Date:"));
+    }
+    false
+}
+
+fn is_signature_start(line: &str) -> bool {
+    let trimmed = line.trim_end();
+    // "-- " is the standard delimiter; many clients drop the trailing space
+    trimmed == "--"
+        || trimmed.starts_with("Sent from my ")
+        || trimmed.starts_with("Get Outlook for ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_gmail_quote_and_signature() {
+        let body = "Sounds good, see you then.\n\nThanks\n-- \nAlice\nACME Corp\n\nOn Mon, Jan 6, 2025 at 10:00 AM Bob <bob@example.com>\nwrote:\n> Can we meet at 3?\n> Bob";
+        assert_eq!(strip_quoted_text(body), "Sounds good, see you then.\n\nThanks");
+    }
+
+    #[test]
+    fn test_strip_outlook_header_block() {
+        let body = "Approved.\r\n\r\nFrom: Carol <carol@example.com>\r\nSent: Monday, January 6, 2025 9:00 AM\r\nTo: Dave\r\nSubject: Budget\r\n\r\nPlease approve.";
+        assert_eq!(strip_quoted_text(body), "Approved.");
+    }
+}
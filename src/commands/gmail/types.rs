@@ -65,6 +65,126 @@ pub struct MessageRef {
     pub thread_id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub id: String,
+    pub history_id: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListThreadsResponse {
+    #[serde(default)]
+    pub threads: Vec<ThreadRef>,
+    pub next_page_token: Option<String>,
+    pub result_size_estimate: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadRef {
+    pub id: String,
+    #[serde(default)]
+    pub snippet: String,
+    pub history_id: Option<String>,
+}
+
+/// Condensed conversation view of a thread (quoted text and signatures removed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub thread_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub message_count: usize,
+    pub messages: Vec<ConversationMessage>,
+}
+
+/// One message of a conversation, reduced to the text it added
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationMessage {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    pub new_text: String,
+}
+
+/// One page of `users.history.list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryResponse {
+    #[serde(default)]
+    pub history: Vec<HistoryRecord>,
+    pub next_page_token: Option<String>,
+    pub history_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    pub id: String,
+    #[serde(default)]
+    pub messages_added: Vec<HistoryMessage>,
+    #[serde(default)]
+    pub messages_deleted: Vec<HistoryMessage>,
+    #[serde(default)]
+    pub labels_added: Vec<HistoryLabelChange>,
+    #[serde(default)]
+    pub labels_removed: Vec<HistoryLabelChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMessage {
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryLabelChange {
+    pub message: Message,
+    #[serde(default)]
+    pub label_ids: Vec<String>,
+}
+
+/// Mailbox changes since a history ID, flattened for agents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryChanges {
+    /// Pass this as `--since` next time
+    pub history_id: String,
+    pub messages_added: Vec<MessageSummary>,
+    pub messages_deleted: Vec<MessageRef>,
+    pub labels_added: Vec<LabelChange>,
+    pub labels_removed: Vec<LabelChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelChange {
+    pub id: String,
+    pub thread_id: String,
+    pub label_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub email_address: String,
+    #[serde(default)]
+    pub messages_total: u64,
+    #[serde(default)]
+    pub threads_total: u64,
+    pub history_id: String,
+}
+
 /// Enriched message summary with headers (used by list with metadata)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -159,6 +279,82 @@ pub struct DraftResponse {
     pub thread_id: Option<String>,
 }
 
+impl DraftResponse {
+    pub fn from_draft(draft: &serde_json::Value) -> Self {
+        Self {
+            success: true,
+            id: draft["id"].as_str().unwrap_or("").to_string(),
+            message_id: draft["message"]["id"].as_str().map(String::from),
+            thread_id: draft["message"]["threadId"].as_str().map(String::from),
+        }
+    }
+}
+
+/// Message in `raw` format (base64url-encoded RFC 2822 source)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawMessage {
+    pub id: String,
+    pub thread_id: String,
+    #[serde(default)]
+    pub label_ids: Vec<String>,
+    pub internal_date: Option<String>,
+    pub raw: String,
+}
+
+/// Draft resource (message in `full` or `raw` format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Draft {
+    pub id: String,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftRef {
+    pub id: String,
+    pub message: MessageRef,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDraftsResponse {
+    #[serde(default)]
+    pub drafts: Vec<DraftRef>,
+    pub next_page_token: Option<String>,
+    pub result_size_estimate: Option<u64>,
+}
+
+/// Draft with its message headers (used by drafts list)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftSummary {
+    pub id: String,
+    pub message: MessageSummary,
+}
+
+/// Draft list with message metadata
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftList {
+    pub drafts: Vec<DraftSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+/// Decoded draft (minimal token usage)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftDetail {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bcc: Option<String>,
+    pub message: MinimalMessage,
+}
+
 // For sending emails
 #[derive(Debug, Clone, Serialize)]
 pub struct SendMessageRequest {
@@ -13,9 +13,10 @@ pub struct UpdateParams {
     pub value_input_option: ValueInputOption,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum ValueInputOption {
     Raw,
+    #[default]
     UserEntered,
 }
 
@@ -28,12 +29,6 @@ impl ValueInputOption {
     }
 }
 
-impl Default for ValueInputOption {
-    fn default() -> Self {
-        Self::UserEntered
-    }
-}
-
 pub async fn update_values(
     client: &ApiClient,
     params: UpdateParams,
@@ -94,7 +89,7 @@ pub async fn clear_values(
 /// Parse values from JSON string input (for CLI usage)
 pub fn parse_values_json(json: &str) -> Result<Vec<Vec<serde_json::Value>>> {
     serde_json::from_str(json)
-        .map_err(|e| crate::error::WorkspaceError::Config(format!("Invalid JSON values: {}", e)))
+        .map_err(|e| crate::error::WorkspaceError::InvalidInput(format!("Invalid JSON values: {}", e)))
 }
 
 /// Add a new sheet (tab) to a spreadsheet
@@ -111,7 +111,7 @@ pub fn get_summary(presentation: &Presentation) -> serde_json::Value {
         .map(|(i, slide)| {
             // Try to extract title from first text element
             let title = slide.page_elements.iter()
-                .filter_map(|e| extract_element_text(e))
+                .filter_map(extract_element_text)
                 .next()
                 .map(|t| t.lines().next().unwrap_or("").to_string())
                 .unwrap_or_else(|| format!("Slide {}", i + 1));
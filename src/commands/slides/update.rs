@@ -122,6 +122,7 @@ pub async fn add_slide(
 }
 
 /// Add text to a slide at a specific position
+#[allow(clippy::too_many_arguments)]
 pub async fn add_text(
     client: &ApiClient,
     presentation_id: &str,
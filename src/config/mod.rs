@@ -1,3 +1,3 @@
 mod settings;
 
-pub use settings::{ApiConfig, AuthConfig, Config, OutputConfig};
+pub use settings::{ApiConfig, AuthConfig, CacheConfig, Config, OutputConfig, TelemetryConfig};
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub auth: AuthConfig,
@@ -9,9 +9,13 @@ pub struct Config {
     pub output: OutputConfig,
     #[serde(default)]
     pub api: ApiConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// Path to OAuth2 client credentials JSON
     #[serde(default)]
@@ -47,6 +51,32 @@ pub struct ApiConfig {
     pub max_retries: u32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Cache GET responses on disk
+    #[serde(default)]
+    pub enabled: bool,
+    /// Cache directory (defaults to the platform cache dir)
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// TTL in seconds for services without an explicit entry
+    #[serde(default = "default_cache_ttl")]
+    pub default_ttl_seconds: u64,
+    /// Per-service TTLs in seconds, keyed by service name (gmail, drive, docs, ...)
+    #[serde(default)]
+    pub ttl_seconds: std::collections::HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Append finished spans as JSON lines to this file
+    #[serde(default)]
+    pub trace_file: Option<PathBuf>,
+    /// OTLP/HTTP collector endpoint (e.g. http://localhost:4318)
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+}
+
 fn default_format() -> String {
     "json".to_string()
 }
@@ -59,25 +89,8 @@ fn default_retries() -> u32 {
     3
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            auth: AuthConfig::default(),
-            output: OutputConfig::default(),
-            api: ApiConfig::default(),
-        }
-    }
-}
-
-impl Default for AuthConfig {
-    fn default() -> Self {
-        Self {
-            credentials_path: None,
-            service_account_path: None,
-            current_account: None,
-            accounts: std::collections::HashMap::new(),
-        }
-    }
+fn default_cache_ttl() -> u64 {
+    300
 }
 
 impl Default for OutputConfig {
@@ -98,6 +111,17 @@ impl Default for ApiConfig {
     }
 }
 
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            default_ttl_seconds: default_cache_ttl(),
+            ttl_seconds: std::collections::HashMap::new(),
+        }
+    }
+}
+
 impl Config {
     /// Load config from file, falling back to defaults
     pub fn load() -> Self {
@@ -117,6 +141,11 @@ impl Config {
         dirs::config_dir().map(|p| p.join("workspace-cli"))
     }
 
+    /// Get the response cache directory path
+    pub fn cache_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("workspace-cli"))
+    }
+
     /// Save config to file
     pub fn save(&self) -> std::io::Result<()> {
         if let Some(dir) = Self::config_dir() {
@@ -153,6 +182,18 @@ impl Config {
                 self.api.max_retries = max;
             }
         }
+        if let Ok(enabled) = std::env::var("WORKSPACE_CACHE") {
+            self.cache.enabled = enabled.eq_ignore_ascii_case("true") || enabled == "1";
+        }
+        if let Ok(dir) = std::env::var("WORKSPACE_CACHE_DIR") {
+            self.cache.dir = Some(PathBuf::from(dir));
+        }
+        if let Ok(path) = std::env::var("WORKSPACE_TRACE_FILE") {
+            self.telemetry.trace_file = Some(PathBuf::from(path));
+        }
+        if let Ok(endpoint) = std::env::var("WORKSPACE_OTLP_ENDPOINT") {
+            self.telemetry.otlp_endpoint = Some(endpoint);
+        }
         self
     }
 }
@@ -30,6 +30,79 @@ pub enum ErrorCode {
     Unknown,
 }
 
+/// Exit code for a command that completed but reported per-item failures
+/// (e.g. a batch whose status is "partial")
+pub const EXIT_PARTIAL: i32 = 12;
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 11] = [
+        ErrorCode::Unknown,
+        ErrorCode::InvalidRequest,
+        ErrorCode::AuthenticationFailed,
+        ErrorCode::TokenExpired,
+        ErrorCode::PermissionDenied,
+        ErrorCode::NotFound,
+        ErrorCode::RateLimitExceeded,
+        ErrorCode::QuotaExceeded,
+        ErrorCode::NetworkError,
+        ErrorCode::ServerError,
+        ErrorCode::ConfigurationError,
+    ];
+
+    /// Process exit code for this error
+    ///
+    /// These values are part of the CLI contract and must not be renumbered.
+    /// 2 is shared with clap's usage errors.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::Unknown => 1,
+            ErrorCode::InvalidRequest => 2,
+            ErrorCode::AuthenticationFailed => 3,
+            ErrorCode::TokenExpired => 4,
+            ErrorCode::PermissionDenied => 5,
+            ErrorCode::NotFound => 6,
+            ErrorCode::RateLimitExceeded => 7,
+            ErrorCode::QuotaExceeded => 8,
+            ErrorCode::NetworkError => 9,
+            ErrorCode::ServerError => 10,
+            ErrorCode::ConfigurationError => 11,
+        }
+    }
+
+    /// Name as it appears in `error_code` of JSON errors
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::AuthenticationFailed => "authentication_failed",
+            ErrorCode::TokenExpired => "token_expired",
+            ErrorCode::RateLimitExceeded => "rate_limit_exceeded",
+            ErrorCode::QuotaExceeded => "quota_exceeded",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::PermissionDenied => "permission_denied",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::NetworkError => "network_error",
+            ErrorCode::ServerError => "server_error",
+            ErrorCode::ConfigurationError => "configuration_error",
+            ErrorCode::Unknown => "unknown",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::Unknown => "Unexpected error (I/O, serialization, ...)",
+            ErrorCode::InvalidRequest => "Invalid arguments, input or API request",
+            ErrorCode::AuthenticationFailed => "Not logged in or credentials rejected",
+            ErrorCode::TokenExpired => "Access token expired or revoked (HTTP 401)",
+            ErrorCode::PermissionDenied => "Insufficient permissions (HTTP 403)",
+            ErrorCode::NotFound => "Resource not found",
+            ErrorCode::RateLimitExceeded => "Rate limited; retry after retry_after_seconds",
+            ErrorCode::QuotaExceeded => "API quota exhausted",
+            ErrorCode::NetworkError => "Connection or timeout failure",
+            ErrorCode::ServerError => "Google API server error (HTTP 5xx)",
+            ErrorCode::ConfigurationError => "Invalid or missing configuration",
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum WorkspaceError {
     #[error("Authentication failed: {0}")]
@@ -52,6 +125,9 @@ pub enum WorkspaceError {
 
     #[error("Resource not found: {0}")]
     NotFound(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
 }
 
 #[derive(Debug, Error)]
@@ -106,8 +182,12 @@ impl From<&WorkspaceError> for CliError {
                     .with_fix("Run 'workspace-cli auth login' to re-authenticate")
             }
             WorkspaceError::Api(api_err) => {
+                let message = api_err.message.to_lowercase();
                 let code = match api_err.code {
                     401 => ErrorCode::TokenExpired,
+                    // Google reports quota and per-user rate limits as 403
+                    403 if message.contains("quota") => ErrorCode::QuotaExceeded,
+                    403 if message.contains("rate limit") => ErrorCode::RateLimitExceeded,
                     403 => ErrorCode::PermissionDenied,
                     404 => ErrorCode::NotFound,
                     429 => ErrorCode::RateLimitExceeded,
@@ -118,6 +198,12 @@ impl From<&WorkspaceError> for CliError {
                 if let Some(retry) = api_err.retry_after {
                     cli_err = cli_err.with_retry(retry);
                 }
+                // Tokens from before a scope was added (e.g. gmail.settings.basic) lack it
+                if api_err.code == 403 && message.contains("insufficient") && message.contains("scope") {
+                    cli_err = cli_err.with_fix(
+                        "Run 'workspace-cli auth login' again to grant the scopes this command needs",
+                    );
+                }
                 cli_err
             }
             WorkspaceError::Network(e) => {
@@ -136,8 +222,51 @@ impl From<&WorkspaceError> for CliError {
             WorkspaceError::NotFound(msg) => {
                 CliError::new(ErrorCode::NotFound, "resource", msg.clone())
             }
+            WorkspaceError::InvalidInput(msg) => {
+                CliError::new(ErrorCode::InvalidRequest, "input", msg.clone())
+            }
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, WorkspaceError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let mut codes: Vec<i32> = ErrorCode::ALL.iter().map(|c| c.exit_code()).collect();
+        codes.push(EXIT_PARTIAL);
+        let count = codes.len();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), count);
+        assert!(!codes.contains(&0));
+    }
+
+    #[test]
+    fn test_error_code_names_match_serialization() {
+        for code in ErrorCode::ALL {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+
+    #[test]
+    fn test_insufficient_scope_suggests_login() {
+        let api_error = |message: &str| WorkspaceError::Api(ApiError {
+            code: 403,
+            message: message.to_string(),
+            domain: "gmail".to_string(),
+            retry_after: None,
+        });
+
+        let err = CliError::from(&api_error("Request had insufficient authentication scopes."));
+        assert!(matches!(err.error_code, ErrorCode::PermissionDenied));
+        assert!(err.actionable_fix.as_deref().is_some_and(|fix| fix.contains("auth login")));
+
+        let err = CliError::from(&api_error("The caller does not have permission"));
+        assert!(err.actionable_fix.is_none());
+    }
+}
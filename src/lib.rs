@@ -4,6 +4,7 @@ pub mod commands;
 pub mod config;
 pub mod error;
 pub mod output;
+pub mod telemetry;
 pub mod utils;
 pub mod cli;
 
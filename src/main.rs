@@ -1,3228 +1,29 @@
-use clap::{Parser, Subcommand};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use clap::Parser;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+use workspace_cli::cli::{self, Cli};
+use workspace_cli::telemetry::{self, TelemetrySettings};
 use workspace_cli::Config;
-use workspace_cli::auth::TokenManager;
-use workspace_cli::client::ApiClient;
-use workspace_cli::output::{Formatter, OutputFormat};
-use tracing_subscriber::EnvFilter;
-
-#[derive(Parser)]
-#[command(name = "workspace-cli")]
-#[command(about = "High-performance Google Workspace CLI for AI agent integration")]
-#[command(long_about = "workspace-cli provides programmatic access to Google Workspace APIs \
-    (Gmail, Drive, Calendar, Docs, Sheets, Slides, Tasks) with structured JSON output \
-    optimized for AI agent consumption.\n\n\
-    All commands output JSON by default. Use --format to change output format.\n\
-    Use --fields to limit response fields for token efficiency.")]
-#[command(author, version)]
-#[command(propagate_version = true)]
-struct Cli {
-    #[command(subcommand)]
-    command: Commands,
-
-    /// Output format: json, jsonl, csv
-    #[arg(long, short = 'f', global = true, default_value = "json")]
-    format: String,
-
-    /// Fields to include in response (comma-separated)
-    #[arg(long, global = true)]
-    fields: Option<String>,
-
-    /// Write output to file instead of stdout
-    #[arg(long, short = 'o', global = true)]
-    output: Option<String>,
-
-    /// Suppress non-essential output
-    #[arg(long, short = 'q', global = true)]
-    quiet: bool,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Gmail operations
-    #[command(long_about = "Gmail operations for listing, reading, and sending emails.\n\n\
-        Examples:\n\
-        List unread emails:\n  \
-        workspace-cli gmail list --query 'is:unread' --limit 10\n\n\
-        Get specific email with decoded body:\n  \
-        workspace-cli gmail get <message-id> --decode-body\n\n\
-        Send an email:\n  \
-        workspace-cli gmail send --to user@example.com --subject 'Hello' --body 'Message'\n\n\
-        Search emails by sender:\n  \
-        workspace-cli gmail list --query 'from:boss@company.com' --limit 5")]
-    Gmail {
-        #[command(subcommand)]
-        command: GmailCommands,
-    },
-    /// Google Drive operations
-    #[command(long_about = "Google Drive operations for file management and storage.\n\n\
-        Examples:\n\
-        List recent files:\n  \
-        workspace-cli drive list --limit 20\n\n\
-        Search for documents:\n  \
-        workspace-cli drive list --query \"mimeType='application/vnd.google-apps.document'\"\n\n\
-        Get file metadata:\n  \
-        workspace-cli drive get <file-id>\n\n\
-        Upload a file:\n  \
-        workspace-cli drive upload /path/to/file.pdf --parent <folder-id>\n\n\
-        Download a file:\n  \
-        workspace-cli drive download <file-id> --output /path/to/save")]
-    Drive {
-        #[command(subcommand)]
-        command: DriveCommands,
-    },
-    /// Google Calendar operations
-    #[command(long_about = "Google Calendar operations for event management and scheduling.\n\n\
-        Examples:\n\
-        List upcoming events:\n  \
-        workspace-cli calendar list --time-min 2025-01-01T00:00:00Z --limit 10\n\n\
-        List today's events:\n  \
-        workspace-cli calendar list --time-min $(date -u +%Y-%m-%dT00:00:00Z) \\\n    \
-        --time-max $(date -u -d '+1 day' +%Y-%m-%dT00:00:00Z)\n\n\
-        Create an event:\n  \
-        workspace-cli calendar create --summary 'Team Meeting' \\\n    \
-        --start 2025-01-15T14:00:00Z --end 2025-01-15T15:00:00Z\n\n\
-        Update an event:\n  \
-        workspace-cli calendar update <event-id> --summary 'Updated Meeting'\n\n\
-        Delete an event:\n  \
-        workspace-cli calendar delete <event-id>")]
-    Calendar {
-        #[command(subcommand)]
-        command: CalendarCommands,
-    },
-    /// Google Docs operations
-    #[command(long_about = "Google Docs operations for document access and editing.\n\n\
-        Examples:\n\
-        Get document content:\n  \
-        workspace-cli docs get <document-id>\n\n\
-        Get document as markdown:\n  \
-        workspace-cli docs get <document-id> --markdown\n\n\
-        Append text to document:\n  \
-        workspace-cli docs append <document-id> 'New paragraph text'\n\n\
-        Extract content for AI processing:\n  \
-        workspace-cli docs get <document-id> --markdown --fields content")]
-    Docs {
-        #[command(subcommand)]
-        command: DocsCommands,
-    },
-    /// Google Sheets operations
-    #[command(long_about = "Google Sheets operations for spreadsheet data access and manipulation.\n\n\
-        Examples:\n\
-        Get range of cells:\n  \
-        workspace-cli sheets get <spreadsheet-id> --range 'Sheet1!A1:C10'\n\n\
-        Update cells:\n  \
-        workspace-cli sheets update <spreadsheet-id> --range 'Sheet1!A1:B2' \\\n    \
-        --values '[[\"Name\",\"Value\"],[\"Item1\",\"100\"]]'\n\n\
-        Append rows:\n  \
-        workspace-cli sheets append <spreadsheet-id> --range 'Sheet1!A:B' \\\n    \
-        --values '[[\"New Row\",\"Data\"]]'\n\n\
-        Extract data for analysis:\n  \
-        workspace-cli sheets get <spreadsheet-id> --range 'Sheet1!A:Z' --format jsonl")]
-    Sheets {
-        #[command(subcommand)]
-        command: SheetsCommands,
-    },
-    /// Google Slides operations
-    #[command(long_about = "Google Slides operations for presentation access and content extraction.\n\n\
-        Examples:\n\
-        Get presentation info:\n  \
-        workspace-cli slides get <presentation-id>\n\n\
-        Get presentation text only:\n  \
-        workspace-cli slides get <presentation-id> --text-only\n\n\
-        Get specific slide:\n  \
-        workspace-cli slides page <presentation-id> --page 0\n\n\
-        Extract all text from presentation:\n  \
-        workspace-cli slides get <presentation-id> --text-only --format json")]
-    Slides {
-        #[command(subcommand)]
-        command: SlidesCommands,
-    },
-    /// Google Tasks operations
-    #[command(long_about = "Google Tasks operations for task and to-do list management.\n\n\
-        Examples:\n\
-        List all task lists:\n  \
-        workspace-cli tasks lists\n\n\
-        List tasks in default list:\n  \
-        workspace-cli tasks list\n\n\
-        List tasks including completed:\n  \
-        workspace-cli tasks list --show-completed\n\n\
-        Create a task:\n  \
-        workspace-cli tasks create 'Buy groceries' --due 2025-01-20T12:00:00Z\n\n\
-        Update and complete a task:\n  \
-        workspace-cli tasks update <task-id> --complete\n\n\
-        Delete a task:\n  \
-        workspace-cli tasks delete <task-id>")]
-    Tasks {
-        #[command(subcommand)]
-        command: TasksCommands,
-    },
-    /// Authentication management
-    #[command(long_about = "Authentication management for Google Workspace APIs.\n\n\
-        Examples:\n\
-        Login with OAuth2 (interactive browser flow):\n  \
-        workspace-cli auth login --credentials credentials.json --account user@example.com\n\n\
-        Login with auto-generated account name:\n  \
-        workspace-cli auth login --credentials credentials.json\n\n\
-        List all authenticated accounts:\n  \
-        workspace-cli auth accounts\n\n\
-        Switch to a different account:\n  \
-        workspace-cli auth switch user2@example.com\n\n\
-        Check authentication status:\n  \
-        workspace-cli auth status\n\n\
-        Logout specific account:\n  \
-        workspace-cli auth logout --account user@example.com\n\n\
-        Logout all accounts:\n  \
-        workspace-cli auth logout --all\n\n\
-        Note: Multi-account support allows you to manage multiple Google accounts simultaneously.")]
-    Auth {
-        #[command(subcommand)]
-        command: AuthCommands,
-    },
-    /// Execute batch API requests (up to 100 per batch)
-    #[command(long_about = "Execute multiple API requests in a single HTTP call for efficiency.\n\n\
-        Batch requests allow you to combine up to 100 API calls into a single request,\n\
-        significantly reducing latency and quota usage for bulk operations.\n\n\
-        Input format (JSON array):\n  \
-        [{\"id\":\"req1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc123\"},\n   \
-        {\"id\":\"req2\",\"method\":\"POST\",\"path\":\"/users/me/messages/xyz/modify\",\n    \
-        \"body\":{\"addLabelIds\":[\"STARRED\"]}}]\n\n\
-        Examples:\n\
-        Batch Gmail requests from JSON string:\n  \
-        workspace-cli batch gmail --requests '[{\"id\":\"1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc\"}]'\n\n\
-        Batch Gmail requests from file:\n  \
-        workspace-cli batch gmail --file requests.json\n\n\
-        Batch from stdin (pipe):\n  \
-        echo '[{\"id\":\"1\",\"method\":\"GET\",\"path\":\"/users/me/messages/abc\"}]' | workspace-cli batch gmail")]
-    Batch {
-        #[command(subcommand)]
-        command: BatchCommands,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum GmailCommands {
-    /// List messages
-    List {
-        /// Search query (Gmail search syntax)
-        #[arg(long)]
-        query: Option<String>,
-        /// Maximum number of results
-        #[arg(long, default_value = "20")]
-        limit: u32,
-        /// Label ID to filter by
-        #[arg(long)]
-        label: Option<String>,
-    },
-    /// Get a specific message
-    Get {
-        /// Message ID
-        id: String,
-        /// Return full message structure (includes all headers, MIME parts, raw body)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Send an email
-    Send {
-        /// Recipient email
-        #[arg(long)]
-        to: String,
-        /// CC recipients (comma-separated)
-        #[arg(long)]
-        cc: Option<String>,
-        /// BCC recipients (comma-separated)
-        #[arg(long)]
-        bcc: Option<String>,
-        /// Email subject
-        #[arg(long)]
-        subject: String,
-        /// Email body (or use --body-file)
-        #[arg(long)]
-        body: Option<String>,
-        /// Read body from file
-        #[arg(long)]
-        body_file: Option<String>,
-        /// File attachments (can be specified multiple times)
-        #[arg(long, action = clap::ArgAction::Append)]
-        attachment: Vec<String>,
-        /// Send as HTML content
-        #[arg(long)]
-        html: bool,
-    },
-    /// Create a draft
-    Draft {
-        /// Recipient email
-        #[arg(long)]
-        to: String,
-        /// CC recipients (comma-separated)
-        #[arg(long)]
-        cc: Option<String>,
-        /// BCC recipients (comma-separated)
-        #[arg(long)]
-        bcc: Option<String>,
-        /// Email subject
-        #[arg(long)]
-        subject: String,
-        /// Email body
-        #[arg(long)]
-        body: Option<String>,
-        /// File attachments (can be specified multiple times)
-        #[arg(long, action = clap::ArgAction::Append)]
-        attachment: Vec<String>,
-        /// Send as HTML content
-        #[arg(long)]
-        html: bool,
-    },
-    /// Permanently delete a message (bypasses trash)
-    Delete {
-        /// Message ID to delete
-        id: String,
-    },
-    /// Move message to trash
-    Trash {
-        /// Message ID to trash
-        id: String,
-    },
-    /// Remove message from trash
-    Untrash {
-        /// Message ID to untrash
-        id: String,
-    },
-    /// List all labels
-    Labels,
-    /// Modify labels on a message
-    Modify {
-        /// Message ID
-        id: String,
-        /// Labels to add (comma-separated)
-        #[arg(long)]
-        add_labels: Option<String>,
-        /// Labels to remove (comma-separated)
-        #[arg(long)]
-        remove_labels: Option<String>,
-        /// Mark as read
-        #[arg(long)]
-        mark_read: bool,
-        /// Mark as unread
-        #[arg(long)]
-        mark_unread: bool,
-        /// Star message
-        #[arg(long)]
-        star: bool,
-        /// Unstar message
-        #[arg(long)]
-        unstar: bool,
-        /// Archive message (remove from inbox)
-        #[arg(long)]
-        archive: bool,
-    },
-    /// Reply to a message
-    Reply {
-        /// Message ID to reply to
-        id: String,
-        /// Reply body (or use --body-file)
-        #[arg(long)]
-        body: Option<String>,
-        /// Read body from file
-        #[arg(long)]
-        body_file: Option<String>,
-        /// Reply-all (include Cc recipients)
-        #[arg(long)]
-        all: bool,
-        /// Send as HTML content
-        #[arg(long)]
-        html: bool,
-    },
-    /// Create a draft reply to a message
-    ReplyDraft {
-        /// Message ID to reply to
-        id: String,
-        /// Reply body
-        #[arg(long)]
-        body: Option<String>,
-        /// Reply-all (include Cc recipients)
-        #[arg(long)]
-        all: bool,
-        /// Send as HTML content
-        #[arg(long)]
-        html: bool,
-    },
-    /// List attachments in a message
-    Attachments {
-        /// Message ID
-        id: String,
-    },
-    /// Download an attachment from a message
-    Attachment {
-        /// Message ID
-        message_id: String,
-        /// Attachment ID
-        attachment_id: String,
-        /// Output file path
-        #[arg(long, short = 'o')]
-        output: String,
-    },
-    /// Forward a message to another recipient
-    Forward {
-        /// Message ID to forward
-        id: String,
-        /// Recipient email
-        #[arg(long)]
-        to: String,
-        /// CC recipients (comma-separated)
-        #[arg(long)]
-        cc: Option<String>,
-        /// BCC recipients (comma-separated)
-        #[arg(long)]
-        bcc: Option<String>,
-        /// Optional message to include before the forwarded content
-        #[arg(long)]
-        message: Option<String>,
-    },
-    /// List email filters
-    Filters,
-    /// Create an email filter
-    CreateFilter {
-        /// Match emails from this sender
-        #[arg(long)]
-        from: Option<String>,
-        /// Match emails to this recipient
-        #[arg(long)]
-        to: Option<String>,
-        /// Match emails with this subject
-        #[arg(long)]
-        subject: Option<String>,
-        /// Match emails containing this query (Gmail search syntax)
-        #[arg(long)]
-        query: Option<String>,
-        /// Match emails with attachments
-        #[arg(long)]
-        has_attachment: bool,
-        /// Labels to add (comma-separated label IDs)
-        #[arg(long)]
-        add_labels: Option<String>,
-        /// Labels to remove (comma-separated label IDs)
-        #[arg(long)]
-        remove_labels: Option<String>,
-        /// Forward matching emails to this address
-        #[arg(long)]
-        forward_to: Option<String>,
-        /// Skip the inbox (archive)
-        #[arg(long)]
-        skip_inbox: bool,
-        /// Mark as read
-        #[arg(long)]
-        mark_read: bool,
-        /// Star the message
-        #[arg(long)]
-        star: bool,
-        /// Move to trash
-        #[arg(long)]
-        trash: bool,
-    },
-    /// Delete an email filter
-    DeleteFilter {
-        /// Filter ID to delete
-        id: String,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum DriveCommands {
-    /// List files
-    List {
-        /// Search query (Drive query syntax)
-        #[arg(long)]
-        query: Option<String>,
-        /// Maximum results
-        #[arg(long, default_value = "20")]
-        limit: u32,
-        /// Parent folder ID
-        #[arg(long)]
-        parent: Option<String>,
-        /// Order by field
-        #[arg(long)]
-        order_by: Option<String>,
-    },
-    /// Upload a file
-    Upload {
-        /// Local file path
-        file: String,
-        /// Destination folder ID
-        #[arg(long)]
-        parent: Option<String>,
-        /// Custom name for uploaded file
-        #[arg(long)]
-        name: Option<String>,
-    },
-    /// Download a file
-    Download {
-        /// File ID
-        id: String,
-        /// Output path
-        #[arg(long, short = 'o')]
-        output: Option<String>,
-    },
-    /// Get file metadata
-    Get {
-        /// File ID
-        id: String,
-    },
-    /// Permanently delete a file (bypasses trash)
-    Delete {
-        /// File ID to delete
-        id: String,
-    },
-    /// Move file to trash
-    Trash {
-        /// File ID to trash
-        id: String,
-    },
-    /// Restore file from trash
-    Untrash {
-        /// File ID to restore
-        id: String,
-    },
-    /// Create a new folder
-    Mkdir {
-        /// Folder name
-        name: String,
-        /// Parent folder ID
-        #[arg(long)]
-        parent: Option<String>,
-    },
-    /// Move a file to a different folder
-    Move {
-        /// File ID to move
-        id: String,
-        /// Destination folder ID
-        #[arg(long)]
-        to: String,
-    },
-    /// Copy a file
-    Copy {
-        /// File ID to copy
-        id: String,
-        /// New name for the copy
-        #[arg(long)]
-        name: Option<String>,
-        /// Destination folder ID
-        #[arg(long)]
-        parent: Option<String>,
-    },
-    /// Rename a file
-    Rename {
-        /// File ID to rename
-        id: String,
-        /// New name
-        name: String,
-    },
-    /// Share a file
-    Share {
-        /// File ID to share
-        id: String,
-        /// Share with this email address
-        #[arg(long)]
-        email: Option<String>,
-        /// Share with anyone (make public)
-        #[arg(long)]
-        anyone: bool,
-        /// Role: reader, commenter, writer
-        #[arg(long, default_value = "reader")]
-        role: String,
-    },
-    /// List permissions on a file
-    Permissions {
-        /// File ID
-        id: String,
-    },
-    /// Remove a permission from a file
-    Unshare {
-        /// File ID
-        id: String,
-        /// Permission ID to remove
-        permission_id: String,
-    },
-    /// Get a start page token for watching changes
-    StartPageToken,
-    /// Watch for changes to Drive (requires webhook URL)
-    Watch {
-        /// Page token from start-page-token command
-        #[arg(long)]
-        page_token: String,
-        /// Webhook URL to receive notifications (must be HTTPS)
-        #[arg(long)]
-        webhook: String,
-        /// Optional verification token
-        #[arg(long)]
-        token: Option<String>,
-    },
-    /// Watch for changes to a specific file
-    WatchFile {
-        /// File ID to watch
-        id: String,
-        /// Webhook URL to receive notifications (must be HTTPS)
-        #[arg(long)]
-        webhook: String,
-        /// Optional verification token
-        #[arg(long)]
-        token: Option<String>,
-    },
-    /// Stop watching for changes
-    StopWatch {
-        /// Channel ID (from watch response)
-        #[arg(long)]
-        channel_id: String,
-        /// Resource ID (from watch response)
-        #[arg(long)]
-        resource_id: String,
-    },
-    /// List recent changes to Drive
-    Changes {
-        /// Page token (from start-page-token or previous changes response)
-        #[arg(long)]
-        page_token: String,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum CalendarCommands {
-    /// List events
-    List {
-        /// Calendar ID (default: primary)
-        #[arg(long, default_value = "primary")]
-        calendar: String,
-        /// Start time (RFC3339)
-        #[arg(long)]
-        time_min: Option<String>,
-        /// End time (RFC3339)
-        #[arg(long)]
-        time_max: Option<String>,
-        /// Maximum results
-        #[arg(long, default_value = "20")]
-        limit: u32,
-        /// Sync token for incremental sync
-        #[arg(long)]
-        sync_token: Option<String>,
-        /// Return full event data (includes attendees, organizer, description, etc.)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Get a specific event by ID
-    Get {
-        /// Event ID
-        id: String,
-        /// Calendar ID (default: primary)
-        #[arg(long, default_value = "primary")]
-        calendar: String,
-    },
-    /// Create an event
-    Create {
-        /// Event summary/title
-        #[arg(long)]
-        summary: String,
-        /// Start time (RFC3339)
-        #[arg(long)]
-        start: String,
-        /// End time (RFC3339)
-        #[arg(long)]
-        end: String,
-        /// Description
-        #[arg(long)]
-        description: Option<String>,
-        /// Attendee emails (comma-separated)
-        #[arg(long)]
-        attendees: Option<String>,
-        /// Calendar ID
-        #[arg(long, default_value = "primary")]
-        calendar: String,
-        /// Recurrence rule (e.g., "RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR")
-        #[arg(long)]
-        recurrence: Option<String>,
-        /// Reminders (e.g., "email:30,popup:10" for email 30 min before and popup 10 min before)
-        #[arg(long)]
-        reminders: Option<String>,
-    },
-    /// Update an event
-    Update {
-        /// Event ID
-        id: String,
-        /// New summary
-        #[arg(long)]
-        summary: Option<String>,
-        /// New start time
-        #[arg(long)]
-        start: Option<String>,
-        /// New end time
-        #[arg(long)]
-        end: Option<String>,
-        /// Calendar ID
-        #[arg(long, default_value = "primary")]
-        calendar: String,
-    },
-    /// Delete an event
-    Delete {
-        /// Event ID
-        id: String,
-        /// Calendar ID
-        #[arg(long, default_value = "primary")]
-        calendar: String,
-    },
-    /// Query free/busy information for calendars
-    FreeBusy {
-        /// Start time (RFC3339)
-        #[arg(long)]
-        time_min: String,
-        /// End time (RFC3339)
-        #[arg(long)]
-        time_max: String,
-        /// Calendar IDs to check (comma-separated, use "primary" for your calendar)
-        #[arg(long, default_value = "primary")]
-        calendars: String,
-        /// Timezone (e.g., "America/New_York")
-        #[arg(long)]
-        timezone: Option<String>,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum DocsCommands {
-    /// Get document content
-    Get {
-        /// Document ID
-        id: String,
-        /// Output as markdown
-        #[arg(long)]
-        markdown: bool,
-        /// Output as plain text (most token-efficient)
-        #[arg(long)]
-        text: bool,
-    },
-    /// Append text to document
-    Append {
-        /// Document ID
-        id: String,
-        /// Text to append
-        text: String,
-    },
-    /// Create a new document
-    Create {
-        /// Document title
-        title: String,
-    },
-    /// Replace text in document
-    Replace {
-        /// Document ID
-        id: String,
-        /// Text to find
-        #[arg(long)]
-        find: String,
-        /// Text to replace with
-        #[arg(long, name = "with")]
-        replace_with: String,
-        /// Match case
-        #[arg(long)]
-        match_case: bool,
-    },
-    /// Move document to trash
-    Delete {
-        /// Document ID
-        id: String,
-    },
-    /// Insert an image into document
-    InsertImage {
-        /// Document ID
-        id: String,
-        /// Image URL (must be publicly accessible)
-        #[arg(long)]
-        uri: String,
-        /// Insert position (index in document, use 1 for start, or omit to append)
-        #[arg(long)]
-        index: Option<i64>,
-        /// Image width in points
-        #[arg(long)]
-        width: Option<f64>,
-        /// Image height in points
-        #[arg(long)]
-        height: Option<f64>,
-    },
-    /// Insert a table into document
-    InsertTable {
-        /// Document ID
-        id: String,
-        /// Number of rows
-        #[arg(long)]
-        rows: i64,
-        /// Number of columns
-        #[arg(long)]
-        columns: i64,
-        /// Insert position (index in document, omit to append at end)
-        #[arg(long)]
-        index: Option<i64>,
-    },
-    /// Export document to file
-    Export {
-        /// Document ID
-        id: String,
-        /// Output file path
-        #[arg(long, short)]
-        output: String,
-        /// Export format: pdf, docx, txt, html, odt, rtf, epub
-        #[arg(long, short, default_value = "pdf")]
-        format: String,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum SheetsCommands {
-    /// Get spreadsheet values
-    Get {
-        /// Spreadsheet ID
-        id: String,
-        /// Range in A1 notation (e.g., Sheet1!A1:C10)
-        #[arg(long)]
-        range: String,
-        /// Return full ValueRange (includes range, majorDimension metadata)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Update spreadsheet values
-    Update {
-        /// Spreadsheet ID
-        id: String,
-        /// Range in A1 notation
-        #[arg(long)]
-        range: String,
-        /// Values as JSON array of arrays
-        #[arg(long)]
-        values: String,
-    },
-    /// Append rows to spreadsheet
-    Append {
-        /// Spreadsheet ID
-        id: String,
-        /// Range in A1 notation
-        #[arg(long)]
-        range: String,
-        /// Values as JSON array of arrays
-        #[arg(long)]
-        values: String,
-    },
-    /// Create a new spreadsheet
-    Create {
-        /// Spreadsheet title
-        title: String,
-    },
-    /// Clear a range of cells
-    Clear {
-        /// Spreadsheet ID
-        id: String,
-        /// Range to clear in A1 notation
-        #[arg(long)]
-        range: String,
-    },
-    /// List all sheets (tabs) in a spreadsheet
-    ListSheets {
-        /// Spreadsheet ID
-        id: String,
-    },
-    /// Move spreadsheet to trash
-    Delete {
-        /// Spreadsheet ID
-        id: String,
-    },
-    /// Add a new sheet (tab) to a spreadsheet
-    AddSheet {
-        /// Spreadsheet ID
-        id: String,
-        /// Name for the new sheet
-        #[arg(long)]
-        title: String,
-        /// Position index (0 = first, omit for end)
-        #[arg(long)]
-        index: Option<i64>,
-    },
-    /// Rename a sheet (tab) in a spreadsheet
-    RenameSheet {
-        /// Spreadsheet ID
-        id: String,
-        /// Sheet ID (numeric, from list-sheets command)
-        #[arg(long)]
-        sheet_id: i64,
-        /// New name for the sheet
-        #[arg(long)]
-        title: String,
-    },
-    /// Export spreadsheet to file
-    Export {
-        /// Spreadsheet ID
-        id: String,
-        /// Output file path
-        #[arg(long, short)]
-        output: String,
-        /// Export format: csv, xlsx, pdf, ods, tsv, html
-        #[arg(long, short, default_value = "csv")]
-        format: String,
-        /// Sheet name for CSV/TSV export (optional, exports first sheet by default)
-        #[arg(long)]
-        sheet: Option<String>,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum SlidesCommands {
-    /// Get presentation info
-    Get {
-        /// Presentation ID
-        id: String,
-        /// Return full presentation structure (includes masters, layouts, transforms, etc.)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Get specific page
-    Page {
-        /// Presentation ID
-        id: String,
-        /// Page number (0-indexed)
-        #[arg(long)]
-        page: u32,
-        /// Return full page structure (includes transforms, sizes, etc.)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Export presentation to file
-    Export {
-        /// Presentation ID
-        id: String,
-        /// Output file path
-        #[arg(long, short)]
-        output: String,
-        /// Export format: pdf, pptx, odp, txt
-        #[arg(long, short, default_value = "pdf")]
-        format: String,
-    },
-    /// Create a new presentation
-    Create {
-        /// Presentation title
-        #[arg(long)]
-        title: String,
-    },
-    /// Add a slide to a presentation
-    AddSlide {
-        /// Presentation ID
-        id: String,
-        /// Slide index (0-based position to insert)
-        #[arg(long)]
-        index: Option<i32>,
-        /// Layout: BLANK, TITLE, TITLE_AND_BODY, TITLE_ONLY, etc.
-        #[arg(long, default_value = "BLANK")]
-        layout: String,
-    },
-    /// Add text to a slide
-    AddText {
-        /// Presentation ID
-        id: String,
-        /// Page/slide object ID
-        #[arg(long)]
-        page_id: String,
-        /// Text content
-        #[arg(long)]
-        text: String,
-        /// X position in points (from left edge)
-        #[arg(long, default_value = "100")]
-        x: f64,
-        /// Y position in points (from top edge)
-        #[arg(long, default_value = "100")]
-        y: f64,
-        /// Width in points
-        #[arg(long, default_value = "400")]
-        width: f64,
-        /// Height in points
-        #[arg(long, default_value = "50")]
-        height: f64,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum TasksCommands {
-    /// List task lists
-    Lists,
-    /// List tasks in a task list
-    List {
-        /// Task list ID
-        #[arg(long, default_value = "@default")]
-        list: String,
-        /// Maximum number of results (1-100)
-        #[arg(long, default_value = "20")]
-        limit: u32,
-        /// Show completed tasks
-        #[arg(long)]
-        show_completed: bool,
-        /// Return full task data (includes kind, etag, links, position, etc.)
-        #[arg(long)]
-        full: bool,
-    },
-    /// Create a task
-    Create {
-        /// Task title
-        title: String,
-        /// Task list ID
-        #[arg(long, default_value = "@default")]
-        list: String,
-        /// Due date (RFC3339)
-        #[arg(long)]
-        due: Option<String>,
-        /// Notes
-        #[arg(long)]
-        notes: Option<String>,
-    },
-    /// Update a task
-    Update {
-        /// Task ID
-        id: String,
-        /// Task list ID
-        #[arg(long, default_value = "@default")]
-        list: String,
-        /// New title
-        #[arg(long)]
-        title: Option<String>,
-        /// Mark as completed
-        #[arg(long)]
-        complete: bool,
-    },
-    /// Delete a task
-    Delete {
-        /// Task ID
-        id: String,
-        /// Task list ID
-        #[arg(long, default_value = "@default")]
-        list: String,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum AuthCommands {
-    /// Login with OAuth2 (interactive browser flow)
-    Login {
-        /// Path to OAuth2 client credentials JSON
-        #[arg(long)]
-        credentials: Option<String>,
-        /// Account identifier (email or name). Defaults to extracted email from OAuth.
-        #[arg(long)]
-        account: Option<String>,
-    },
-    /// Logout and clear stored tokens
-    Logout {
-        /// Specific account to logout (defaults to current account)
-        #[arg(long)]
-        account: Option<String>,
-        /// Logout all accounts
-        #[arg(long)]
-        all: bool,
-    },
-    /// Show current authentication status
-    Status,
-    /// List all authenticated accounts
-    Accounts,
-    /// Switch to a different account
-    Switch {
-        /// Account identifier (email or name)
-        account: String,
-    },
-}
-
-#[derive(Debug, Subcommand)]
-enum BatchCommands {
-    /// Execute a batch of Gmail API requests
-    Gmail {
-        /// JSON array of requests
-        #[arg(long)]
-        requests: Option<String>,
-        /// Read requests from JSON file
-        #[arg(long)]
-        file: Option<String>,
-    },
-    /// Execute a batch of Drive API requests
-    Drive {
-        /// JSON array of requests
-        #[arg(long)]
-        requests: Option<String>,
-        /// Read requests from JSON file
-        #[arg(long)]
-        file: Option<String>,
-    },
-    /// Execute a batch of Calendar API requests
-    Calendar {
-        /// JSON array of requests
-        #[arg(long)]
-        requests: Option<String>,
-        /// Read requests from JSON file
-        #[arg(long)]
-        file: Option<String>,
-    },
-}
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
-
     let cli = Cli::parse();
-
-    if let Err(e) = run(cli).await {
-        eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-        std::process::exit(1);
-    }
-}
-
-async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
-    // Load config and create shared token manager
     let config = Config::load().with_env_overrides();
-    let token_manager = Arc::new(RwLock::new(TokenManager::new(config.clone())));
-
-    // Determine output format
-    let format = OutputFormat::from_str(&cli.format).unwrap_or(OutputFormat::Json);
-
-    // Parse fields for filtering
-    let fields: Option<Vec<String>> = cli.fields.as_ref().map(|f| {
-        f.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
-    });
-    let quiet = cli.quiet;
-
-    // Route commands
-    match cli.command {
-        Commands::Gmail { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::gmail(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                GmailCommands::List { query, limit, label } => {
-                    let params = workspace_cli::commands::gmail::list::ListParams {
-                        query,
-                        max_results: limit,
-                        label_ids: label.map(|l| vec![l]),
-                        page_token: None,
-                    };
-                    // Get access token for batch metadata request
-                    let access_token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-                    match workspace_cli::commands::gmail::list::list_messages_with_metadata(&client, params, &access_token).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Get { id, full } => {
-                    if full {
-                        // Return full message structure
-                        match workspace_cli::commands::gmail::get::get_message(&client, &id, "full").await {
-                            Ok(response) => {
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response)?;
-                                } else {
-                                    formatter.write(&response)?;
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    } else {
-                        // Default: minimal format (essential headers + plain text body)
-                        match workspace_cli::commands::gmail::get::get_message_minimal(&client, &id).await {
-                            Ok(response) => {
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response)?;
-                                } else {
-                                    formatter.write(&response)?;
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-                }
-                GmailCommands::Send { to, cc, bcc, subject, body, body_file, attachment, html } => {
-                    let body_content = if let Some(file_path) = body_file {
-                        std::fs::read_to_string(file_path)?
-                    } else {
-                        body.unwrap_or_default()
-                    };
-
-                    // Load attachments from file paths
-                    let mut attachments = Vec::new();
-                    for path in attachment {
-                        match workspace_cli::commands::gmail::send::load_attachment(&path) {
-                            Ok(att) => attachments.push(att),
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"Failed to load attachment '{}': {}"}}"#, path, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-
-                    let params = workspace_cli::commands::gmail::send::ComposeParams {
-                        to,
-                        subject,
-                        body: body_content,
-                        from: None,
-                        cc,
-                        bcc,
-                        in_reply_to: None,
-                        references: None,
-                        thread_id: None,
-                        is_html: html,
-                        attachments,
-                    };
-
-                    match workspace_cli::commands::gmail::send::send_message(&client, params).await {
-                        Ok(message) => {
-                            // Return minimal response (success + id + threadId)
-                            let response = workspace_cli::commands::gmail::types::SendResponse::from_message(&message);
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Draft { to, cc, bcc, subject, body, attachment, html } => {
-                    let body_content = body.unwrap_or_default();
-
-                    // Load attachments from file paths
-                    let mut attachments = Vec::new();
-                    for path in attachment {
-                        match workspace_cli::commands::gmail::send::load_attachment(&path) {
-                            Ok(att) => attachments.push(att),
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"Failed to load attachment '{}': {}"}}"#, path, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-
-                    let params = workspace_cli::commands::gmail::send::ComposeParams {
-                        to,
-                        subject,
-                        body: body_content,
-                        from: None,
-                        cc,
-                        bcc,
-                        in_reply_to: None,
-                        references: None,
-                        thread_id: None,
-                        is_html: html,
-                        attachments,
-                    };
-
-                    match workspace_cli::commands::gmail::send::create_draft(&client, params).await {
-                        Ok(draft) => {
-                            // Return minimal response (success + draft id + message info)
-                            let response = workspace_cli::commands::gmail::types::DraftResponse {
-                                success: true,
-                                id: draft["id"].as_str().unwrap_or("").to_string(),
-                                message_id: draft["message"]["id"].as_str().map(String::from),
-                                thread_id: draft["message"]["threadId"].as_str().map(String::from),
-                            };
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Delete { id } => {
-                    match workspace_cli::commands::gmail::delete::delete_message(&client, &id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Message deleted permanently"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Trash { id } => {
-                    match workspace_cli::commands::gmail::trash::trash_message(&client, &id).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Untrash { id } => {
-                    match workspace_cli::commands::gmail::trash::untrash_message(&client, &id).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Labels => {
-                    match workspace_cli::commands::gmail::labels::list_labels(&client).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Modify { id, add_labels, remove_labels, mark_read, mark_unread, star, unstar, archive } => {
-                    // Build label modifications
-                    let mut add: Vec<String> = add_labels
-                        .map(|s| s.split(',').map(|l| l.trim().to_string()).collect())
-                        .unwrap_or_default();
-                    let mut remove: Vec<String> = remove_labels
-                        .map(|s| s.split(',').map(|l| l.trim().to_string()).collect())
-                        .unwrap_or_default();
-
-                    // Handle convenience flags
-                    if mark_read {
-                        remove.push("UNREAD".to_string());
-                    }
-                    if mark_unread {
-                        add.push("UNREAD".to_string());
-                    }
-                    if star {
-                        add.push("STARRED".to_string());
-                    }
-                    if unstar {
-                        remove.push("STARRED".to_string());
-                    }
-                    if archive {
-                        remove.push("INBOX".to_string());
-                    }
-
-                    match workspace_cli::commands::gmail::labels::modify_labels(&client, &id, add, remove).await {
-                        Ok(response) => {
-                            // Return minimal response (success + id + labels) to reduce token usage
-                            let minimal = workspace_cli::commands::gmail::types::ModifyResponse::from_message(&response);
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&minimal)?;
-                            } else {
-                                formatter.write(&minimal)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Reply { id, body, body_file, all, html } => {
-                    // Fetch original message to get headers
-                    let original = match workspace_cli::commands::gmail::get::get_message(&client, &id, "metadata").await {
-                        Ok(msg) => msg,
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"Failed to fetch original message: {}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    // Extract reply metadata
-                    let metadata = match workspace_cli::commands::gmail::send::extract_reply_metadata(&original) {
-                        Some(m) => m,
-                        None => {
-                            eprintln!(r#"{{"status":"error","message":"Could not extract reply metadata from message (missing Message-ID or From header)"}}"#);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    // Get reply body
-                    let body_content = if let Some(file_path) = body_file {
-                        match std::fs::read_to_string(&file_path) {
-                            Ok(content) => content,
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"Failed to read body file: {}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    } else {
-                        body.unwrap_or_default()
-                    };
-
-                    // Build ComposeParams with reply fields
-                    let params = workspace_cli::commands::gmail::send::ComposeParams {
-                        to: metadata.to,
-                        subject: metadata.subject,
-                        body: body_content,
-                        from: None,
-                        cc: if all { metadata.cc } else { None },
-                        bcc: None,
-                        in_reply_to: Some(metadata.in_reply_to),
-                        references: Some(metadata.references),
-                        thread_id: Some(metadata.thread_id),
-                        is_html: html,
-                        attachments: Vec::new(),
-                    };
 
-                    match workspace_cli::commands::gmail::send::send_message(&client, params).await {
-                        Ok(message) => {
-                            // Return minimal response (success + id + threadId)
-                            let response = workspace_cli::commands::gmail::types::SendResponse::from_message(&message);
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::ReplyDraft { id, body, all, html } => {
-                    // Fetch original message to get headers
-                    let original = match workspace_cli::commands::gmail::get::get_message(&client, &id, "metadata").await {
-                        Ok(msg) => msg,
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"Failed to fetch original message: {}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    // Extract reply metadata
-                    let metadata = match workspace_cli::commands::gmail::send::extract_reply_metadata(&original) {
-                        Some(m) => m,
-                        None => {
-                            eprintln!(r#"{{"status":"error","message":"Could not extract reply metadata from message (missing Message-ID or From header)"}}"#);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    // Build ComposeParams with reply fields
-                    let params = workspace_cli::commands::gmail::send::ComposeParams {
-                        to: metadata.to,
-                        subject: metadata.subject,
-                        body: body.unwrap_or_default(),
-                        from: None,
-                        cc: if all { metadata.cc } else { None },
-                        bcc: None,
-                        in_reply_to: Some(metadata.in_reply_to),
-                        references: Some(metadata.references),
-                        thread_id: Some(metadata.thread_id),
-                        is_html: html,
-                        attachments: Vec::new(),
-                    };
-
-                    match workspace_cli::commands::gmail::send::create_draft(&client, params).await {
-                        Ok(draft) => {
-                            // Return minimal response (success + draft id + message info)
-                            let response = workspace_cli::commands::gmail::types::DraftResponse {
-                                success: true,
-                                id: draft["id"].as_str().unwrap_or("").to_string(),
-                                message_id: draft["message"]["id"].as_str().map(String::from),
-                                thread_id: draft["message"]["threadId"].as_str().map(String::from),
-                            };
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Attachments { id } => {
-                    // Get the message to extract attachment info
-                    match workspace_cli::commands::gmail::get::get_message(&client, &id, "full").await {
-                        Ok(message) => {
-                            let attachments = workspace_cli::commands::gmail::get::list_attachments(&message);
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&attachments)?;
-                            } else {
-                                formatter.write(&attachments)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Attachment { message_id, attachment_id, output } => {
-                    match workspace_cli::commands::gmail::get::download_attachment(&client, &message_id, &attachment_id).await {
-                        Ok(data) => {
-                            std::fs::write(&output, &data)?;
-                            let response = serde_json::json!({
-                                "success": true,
-                                "path": output,
-                                "size": data.len()
-                            });
-                            if !quiet {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Forward { id, to, cc, bcc, message } => {
-                    // Get original message
-                    match workspace_cli::commands::gmail::get::get_message(&client, &id, "full").await {
-                        Ok(original) => {
-                            // Extract body
-                            let body = workspace_cli::commands::gmail::get::extract_body(&original).unwrap_or_default();
-
-                            // Build forward metadata
-                            if let Some(metadata) = workspace_cli::commands::gmail::send::extract_forward_metadata(&original, &body) {
-                                let forward_body = workspace_cli::commands::gmail::send::build_forward_body(&metadata, message.as_deref());
-
-                                let params = workspace_cli::commands::gmail::send::ComposeParams {
-                                    to,
-                                    subject: metadata.subject,
-                                    body: forward_body,
-                                    from: None,
-                                    cc,
-                                    bcc,
-                                    in_reply_to: None,
-                                    references: None,
-                                    thread_id: None,
-                                    is_html: false,
-                                    attachments: Vec::new(),
-                                };
-
-                                match workspace_cli::commands::gmail::send::send_message(&client, params).await {
-                                    Ok(msg) => {
-                                        let response = workspace_cli::commands::gmail::types::SendResponse::from_message(&msg);
-                                        formatter.write(&response)?;
-                                    }
-                                    Err(e) => {
-                                        eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                                        std::process::exit(1);
-                                    }
-                                }
-                            } else {
-                                eprintln!(r#"{{"status":"error","message":"Could not extract forward metadata from message"}}"#);
-                                std::process::exit(1);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::Filters => {
-                    match workspace_cli::commands::gmail::filters::list_filters(&client).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::CreateFilter { from, to, subject, query, has_attachment, add_labels, remove_labels, forward_to, skip_inbox, mark_read, star, trash } => {
-                    let add_label_ids: Vec<String> = add_labels.map(|l| l.split(',').map(|s| s.trim().to_string()).collect()).unwrap_or_default();
-                    let remove_label_ids: Vec<String> = remove_labels.map(|l| l.split(',').map(|s| s.trim().to_string()).collect()).unwrap_or_default();
-
-                    let filter = workspace_cli::commands::gmail::filters::build_filter(
-                        from.as_deref(),
-                        to.as_deref(),
-                        subject.as_deref(),
-                        query.as_deref(),
-                        if has_attachment { Some(true) } else { None },
-                        add_label_ids,
-                        remove_label_ids,
-                        forward_to.as_deref(),
-                        skip_inbox,
-                        mark_read,
-                        star,
-                        false,
-                        trash,
-                    );
-
-                    match workspace_cli::commands::gmail::filters::create_filter(&client, &filter).await {
-                        Ok(created) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&created)?;
-                            } else {
-                                formatter.write(&created)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                GmailCommands::DeleteFilter { id } => {
-                    match workspace_cli::commands::gmail::filters::delete_filter(&client, &id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Filter deleted"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Drive { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::drive(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                DriveCommands::List { query, limit, parent, order_by } => {
-                    // Build query with optional parent filter
-                    let final_query = match (query, parent) {
-                        (Some(q), Some(p)) => Some(format!("'{}' in parents and ({})", p, q)),
-                        (Some(q), None) => Some(q),
-                        (None, Some(p)) => Some(format!("'{}' in parents", p)),
-                        (None, None) => None,
-                    };
-
-                    let params = workspace_cli::commands::drive::list::ListParams {
-                        query: final_query,
-                        max_results: limit,
-                        page_token: None,
-                        fields: None,
-                        order_by,
-                    };
-                    match workspace_cli::commands::drive::list::list_files(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Upload { file, parent, name } => {
-                    // Get access token for direct upload
-                    let token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-
-                    let params = workspace_cli::commands::drive::upload::UploadParams {
-                        file_path: file,
-                        name,
-                        parent_id: parent,
-                        mime_type: None,
-                    };
-
-                    match workspace_cli::commands::drive::upload::upload_file(&token, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Download { id, output } => {
-                    // Get access token for direct download
-                    let token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-
-                    let output_path = output
-                        .map(std::path::PathBuf::from)
-                        .unwrap_or_else(|| std::path::PathBuf::from(&id));
-
-                    match workspace_cli::commands::drive::download::download_file(&token, &id, &output_path).await {
-                        Ok(bytes) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","file":"{}","bytes":{}}}"#, output_path.display(), bytes);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Get { id } => {
-                    match workspace_cli::commands::drive::list::get_file(&client, &id, None).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Delete { id } => {
-                    match workspace_cli::commands::drive::delete::delete_file(&client, &id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"File deleted permanently"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Trash { id } => {
-                    match workspace_cli::commands::drive::delete::trash_file(&client, &id).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Untrash { id } => {
-                    match workspace_cli::commands::drive::delete::untrash_file(&client, &id).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Mkdir { name, parent } => {
-                    match workspace_cli::commands::drive::mkdir::create_folder(&client, &name, parent.as_deref()).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Move { id, to } => {
-                    match workspace_cli::commands::drive::operations::move_file(&client, &id, &to, true).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Copy { id, name, parent } => {
-                    match workspace_cli::commands::drive::operations::copy_file(&client, &id, name.as_deref(), parent.as_deref()).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Rename { id, name } => {
-                    match workspace_cli::commands::drive::operations::rename_file(&client, &id, &name).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Share { id, email, anyone, role } => {
-                    let result = if anyone {
-                        workspace_cli::commands::drive::share::share_with_anyone(&client, &id, &role).await
-                    } else if let Some(email) = email {
-                        workspace_cli::commands::drive::share::share_with_user(&client, &id, &email, &role).await
-                    } else {
-                        eprintln!(r#"{{"status":"error","message":"Must specify --email or --anyone"}}"#);
-                        std::process::exit(1);
-                    };
-
-                    match result {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Permissions { id } => {
-                    match workspace_cli::commands::drive::share::list_permissions(&client, &id).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Unshare { id, permission_id } => {
-                    match workspace_cli::commands::drive::share::remove_permission(&client, &id, &permission_id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Permission removed"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::StartPageToken => {
-                    match workspace_cli::commands::drive::watch::get_start_page_token(&client).await {
-                        Ok(response) => {
-                            formatter.write(&response)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Watch { page_token, webhook, token } => {
-                    let params = workspace_cli::commands::drive::watch::WatchChangesParams {
-                        page_token,
-                        webhook_url: webhook,
-                        token,
-                        expiration: None,
-                    };
-                    match workspace_cli::commands::drive::watch::watch_changes(&client, params).await {
-                        Ok(channel) => {
-                            formatter.write(&channel)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::WatchFile { id, webhook, token } => {
-                    let params = workspace_cli::commands::drive::watch::WatchFileParams {
-                        file_id: id,
-                        webhook_url: webhook,
-                        token,
-                        expiration: None,
-                    };
-                    match workspace_cli::commands::drive::watch::watch_file(&client, params).await {
-                        Ok(channel) => {
-                            formatter.write(&channel)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::StopWatch { channel_id, resource_id } => {
-                    match workspace_cli::commands::drive::watch::stop_channel(&client, &channel_id, &resource_id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Watch channel stopped"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DriveCommands::Changes { page_token } => {
-                    match workspace_cli::commands::drive::watch::list_changes(&client, &page_token).await {
-                        Ok(response) => {
-                            formatter.write(&response)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Calendar { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::calendar(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                CalendarCommands::List { calendar, time_min, time_max, limit, sync_token, full } => {
-                    let params = workspace_cli::commands::calendar::list::ListEventsParams {
-                        calendar_id: calendar,
-                        time_min,
-                        time_max,
-                        max_results: limit,
-                        single_events: true,
-                        order_by: Some("startTime".to_string()),
-                        sync_token,
-                        page_token: None,
-                    };
-                    match workspace_cli::commands::calendar::list::list_events(&client, params).await {
-                        Ok(response) => {
-                            if full {
-                                // Return full event data
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response)?;
-                                } else {
-                                    formatter.write(&response)?;
-                                }
-                            } else {
-                                // Default: minimal event data (id, summary, start, end, status)
-                                let minimal = workspace_cli::commands::calendar::types::MinimalEventList::from_event_list(&response);
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&minimal)?;
-                                } else {
-                                    formatter.write(&minimal)?;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                CalendarCommands::Get { id, calendar } => {
-                    match workspace_cli::commands::calendar::get_event(&client, &calendar, &id).await {
-                        Ok(event) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&event)?;
-                            } else {
-                                formatter.write(&event)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                CalendarCommands::Create { summary, start, end, description, attendees, calendar, recurrence, reminders } => {
-                    let attendee_list = attendees.map(|a| {
-                        a.split(',').map(|s| s.trim().to_string()).collect()
-                    });
-                    let params = workspace_cli::commands::calendar::create::CreateEventParams {
-                        calendar_id: calendar,
-                        summary,
-                        start,
-                        end,
-                        description,
-                        location: None,
-                        attendees: attendee_list,
-                        time_zone: None,
-                        recurrence,
-                        reminders,
-                    };
-
-                    match workspace_cli::commands::calendar::create::create_event(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                CalendarCommands::Update { id, summary, start, end, calendar } => {
-                    let params = workspace_cli::commands::calendar::update::UpdateEventParams {
-                        calendar_id: calendar,
-                        event_id: id,
-                        summary,
-                        description: None,
-                        location: None,
-                        start,
-                        end,
-                        time_zone: None,
-                    };
-
-                    match workspace_cli::commands::calendar::update::update_event(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                CalendarCommands::Delete { id, calendar } => {
-                    match workspace_cli::commands::calendar::delete::delete_event(&client, &calendar, &id).await {
-                        Ok(()) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Event deleted"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                CalendarCommands::FreeBusy { time_min, time_max, calendars, timezone } => {
-                    let calendar_ids: Vec<String> = calendars.split(',').map(|s| s.trim().to_string()).collect();
-                    let params = workspace_cli::commands::calendar::list::FreeBusyParams {
-                        time_min,
-                        time_max,
-                        calendars: calendar_ids,
-                        time_zone: timezone,
-                    };
-                    match workspace_cli::commands::calendar::list::query_free_busy(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Docs { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::docs(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                DocsCommands::Get { id, markdown, text } => {
-                    match workspace_cli::commands::docs::get::get_document(&client, &id).await {
-                        Ok(doc) => {
-                            if text {
-                                // Plain text output (most token-efficient)
-                                let txt = workspace_cli::commands::docs::get::document_to_text(&doc);
-                                println!("{}", txt);
-                            } else if markdown {
-                                let md = workspace_cli::commands::docs::get::document_to_markdown(&doc);
-                                println!("{}", md);
-                            } else if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&doc)?;
-                            } else {
-                                formatter.write(&doc)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::Append { id, text } => {
-                    match workspace_cli::commands::docs::update::append_text(&client, &id, &text).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::Create { title } => {
-                    match workspace_cli::commands::docs::create::create_document(&client, &title).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::Replace { id, find, replace_with, match_case } => {
-                    match workspace_cli::commands::docs::update::replace_text(&client, &id, &find, &replace_with, match_case).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::Delete { id } => {
-                    // Use Drive API to trash the document
-                    let drive_client = ApiClient::drive(token_manager.clone());
-                    match workspace_cli::commands::drive::delete::trash_file(&drive_client, &id).await {
-                        Ok(response) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "id": response.id,
-                                "trashed": true
-                            });
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&result)?;
-                            } else {
-                                formatter.write(&result)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::InsertImage { id, uri, index, width, height } => {
-                    let result = if let Some(idx) = index {
-                        workspace_cli::commands::docs::update::insert_image(&client, &id, &uri, idx, width, height).await
-                    } else {
-                        workspace_cli::commands::docs::update::append_image(&client, &id, &uri, width, height).await
-                    };
-
-                    match result {
-                        Ok(response) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "documentId": response.document_id,
-                                "uri": uri
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::InsertTable { id, rows, columns, index } => {
-                    let result = if let Some(idx) = index {
-                        workspace_cli::commands::docs::update::insert_table(&client, &id, rows, columns, idx).await
-                    } else {
-                        workspace_cli::commands::docs::update::append_table(&client, &id, rows, columns).await
-                    };
-
-                    match result {
-                        Ok(response) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "documentId": response.document_id,
-                                "rows": rows,
-                                "columns": columns
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                DocsCommands::Export { id, output, format: export_format } => {
-                    // Map format to MIME type
-                    let mime_type = match export_format.to_lowercase().as_str() {
-                        "pdf" => "application/pdf",
-                        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
-                        "txt" | "text" => "text/plain",
-                        "html" => "text/html",
-                        "odt" => "application/vnd.oasis.opendocument.text",
-                        "rtf" => "application/rtf",
-                        "epub" => "application/epub+zip",
-                        _ => {
-                            eprintln!(r#"{{"status":"error","message":"Unsupported format '{}'. Supported: pdf, docx, txt, html, odt, rtf, epub"}}"#, export_format);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    let access_token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-
-                    let output_path = std::path::Path::new(&output);
-                    match workspace_cli::commands::drive::download::export_file(&access_token, &id, mime_type, output_path).await {
-                        Ok(bytes) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "id": id,
-                                "format": export_format,
-                                "output": output,
-                                "bytes": bytes
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Sheets { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::sheets(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                SheetsCommands::Get { id, range, full } => {
-                    match workspace_cli::commands::sheets::get::get_values(&client, &id, &range).await {
-                        Ok(response) => {
-                            if format == OutputFormat::Csv {
-                                let csv = workspace_cli::commands::sheets::get::values_to_csv(&response);
-                                if let Some(ref output_path) = cli.output {
-                                    std::fs::write(output_path, &csv)?;
-                                } else if !quiet {
-                                    print!("{}", csv);
-                                }
-                            } else if full {
-                                // Return full ValueRange with metadata
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response)?;
-                                } else {
-                                    formatter.write(&response)?;
-                                }
-                            } else {
-                                // Default: return just the values array (minimal, token-efficient)
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response.values)?;
-                                } else {
-                                    formatter.write(&response.values)?;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Update { id, range, values } => {
-                    let parsed_values = workspace_cli::commands::sheets::update::parse_values_json(&values)?;
-                    let params = workspace_cli::commands::sheets::update::UpdateParams {
-                        spreadsheet_id: id,
-                        range,
-                        values: parsed_values,
-                        value_input_option: workspace_cli::commands::sheets::update::ValueInputOption::UserEntered,
-                    };
-
-                    match workspace_cli::commands::sheets::update::update_values(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Append { id, range, values } => {
-                    let parsed_values = workspace_cli::commands::sheets::update::parse_values_json(&values)?;
-
-                    match workspace_cli::commands::sheets::update::append_values(
-                        &client,
-                        &id,
-                        &range,
-                        parsed_values,
-                        workspace_cli::commands::sheets::update::ValueInputOption::UserEntered,
-                    ).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Create { title } => {
-                    match workspace_cli::commands::sheets::create::create_spreadsheet(&client, &title).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Clear { id, range } => {
-                    match workspace_cli::commands::sheets::update::clear_values(&client, &id, &range).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::ListSheets { id } => {
-                    match workspace_cli::commands::sheets::get_spreadsheet(&client, &id).await {
-                        Ok(spreadsheet) => {
-                            let response = workspace_cli::commands::sheets::SheetListResponse::from_spreadsheet(&spreadsheet);
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Delete { id } => {
-                    // Use Drive API to trash the spreadsheet
-                    let drive_client = ApiClient::drive(token_manager.clone());
-                    match workspace_cli::commands::drive::delete::trash_file(&drive_client, &id).await {
-                        Ok(response) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "id": response.id,
-                                "trashed": true
-                            });
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&result)?;
-                            } else {
-                                formatter.write(&result)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::AddSheet { id, title, index } => {
-                    match workspace_cli::commands::sheets::update::add_sheet(&client, &id, &title, index).await {
-                        Ok(response) => {
-                            // Extract the new sheet info from the reply
-                            let sheet_info = response.replies.first()
-                                .and_then(|r| r.get("addSheet"))
-                                .and_then(|s| s.get("properties"));
-
-                            let result = serde_json::json!({
-                                "success": true,
-                                "spreadsheetId": response.spreadsheet_id,
-                                "title": title,
-                                "sheetId": sheet_info.and_then(|p| p.get("sheetId")),
-                                "index": sheet_info.and_then(|p| p.get("index"))
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::RenameSheet { id, sheet_id, title } => {
-                    match workspace_cli::commands::sheets::update::rename_sheet(&client, &id, sheet_id, &title).await {
-                        Ok(response) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "spreadsheetId": response.spreadsheet_id,
-                                "sheetId": sheet_id,
-                                "newTitle": title
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SheetsCommands::Export { id, output, format: export_format, sheet } => {
-                    // Map format to MIME type
-                    // Note: For CSV/TSV, sheet parameter can specify which sheet to export
-                    let mime_type = match export_format.to_lowercase().as_str() {
-                        "csv" => "text/csv",
-                        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
-                        "pdf" => "application/pdf",
-                        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
-                        "tsv" => "text/tab-separated-values",
-                        "html" => "text/html",
-                        _ => {
-                            eprintln!(r#"{{"status":"error","message":"Unsupported format '{}'. Supported: csv, xlsx, pdf, ods, tsv, html"}}"#, export_format);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    let access_token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-
-                    // For CSV/TSV exports with a specific sheet, we need to use the gid parameter
-                    let export_id = if let Some(ref sheet_name) = sheet {
-                        // For sheet-specific export, we'd need to get the sheet ID first
-                        // For now, just use the sheet name in the URL
-                        format!("{}?gid={}", id, sheet_name)
-                    } else {
-                        id.clone()
-                    };
-
-                    let output_path = std::path::Path::new(&output);
-                    match workspace_cli::commands::drive::download::export_file(&access_token, &export_id, mime_type, output_path).await {
-                        Ok(bytes) => {
-                            let mut result = serde_json::json!({
-                                "success": true,
-                                "id": id,
-                                "format": export_format,
-                                "output": output,
-                                "bytes": bytes
-                            });
-                            if let Some(ref s) = sheet {
-                                result["sheet"] = serde_json::json!(s);
-                            }
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Slides { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::slides(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                SlidesCommands::Get { id, full } => {
-                    match workspace_cli::commands::slides::get::get_presentation(&client, &id).await {
-                        Ok(presentation) => {
-                            if full {
-                                // Return full presentation structure
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&presentation)?;
-                                } else {
-                                    formatter.write(&presentation)?;
-                                }
-                            } else {
-                                // Default: text extraction (minimal, token-efficient)
-                                let text = workspace_cli::commands::slides::get::extract_all_text(&presentation);
-                                if let Some(ref output_path) = cli.output {
-                                    std::fs::write(output_path, &text)?;
-                                } else {
-                                    println!("{}", text);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SlidesCommands::Page { id, page, full } => {
-                    // Get the presentation first, then extract the specific slide
-                    match workspace_cli::commands::slides::get::get_presentation(&client, &id).await {
-                        Ok(presentation) => {
-                            let page_index = page as usize;
-                            if page_index >= presentation.slides.len() {
-                                eprintln!(r#"{{"status":"error","message":"Page {} not found. Presentation has {} slides."}}"#, page, presentation.slides.len());
-                                std::process::exit(1);
-                            }
-
-                            let slide = &presentation.slides[page_index];
-                            if full {
-                                // Return full page structure
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(slide)?;
-                                } else {
-                                    formatter.write(slide)?;
-                                }
-                            } else {
-                                // Default: text extraction (minimal, token-efficient)
-                                let text = workspace_cli::commands::slides::get::extract_page_text(slide);
-                                if let Some(ref output_path) = cli.output {
-                                    std::fs::write(output_path, &text)?;
-                                } else {
-                                    println!("{}", text);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SlidesCommands::Export { id, output, format: export_format } => {
-                    // Map format to MIME type
-                    let mime_type = match export_format.to_lowercase().as_str() {
-                        "pdf" => "application/pdf",
-                        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
-                        "odp" => "application/vnd.oasis.opendocument.presentation",
-                        "txt" | "text" => "text/plain",
-                        _ => {
-                            eprintln!(r#"{{"status":"error","message":"Unsupported format '{}'. Supported: pdf, pptx, odp, txt"}}"#, export_format);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    let access_token = {
-                        let mut tm = token_manager.write().await;
-                        tm.get_access_token().await.map_err(|e| {
-                            eprintln!(r#"{{"status":"error","message":"Failed to get token: {}"}}"#, e);
-                            std::process::exit(1);
-                        }).unwrap()
-                    };
-
-                    let output_path = std::path::Path::new(&output);
-                    match workspace_cli::commands::drive::download::export_file(&access_token, &id, mime_type, output_path).await {
-                        Ok(bytes) => {
-                            let result = serde_json::json!({
-                                "success": true,
-                                "id": id,
-                                "format": export_format,
-                                "output": output,
-                                "bytes": bytes
-                            });
-                            formatter.write(&result)?;
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SlidesCommands::Create { title } => {
-                    match workspace_cli::commands::slides::create_presentation(&client, &title).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SlidesCommands::AddSlide { id, index, layout } => {
-                    match workspace_cli::commands::slides::add_slide(&client, &id, index, Some(&layout)).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                SlidesCommands::AddText { id, page_id, text, x, y, width, height } => {
-                    match workspace_cli::commands::slides::add_text(&client, &id, &page_id, &text, x, y, width, height).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Tasks { command } => {
-            // Ensure we're authenticated before making API calls
-            {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-
-            let client = ApiClient::tasks(token_manager.clone());
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            match command {
-                TasksCommands::Lists => {
-                    match workspace_cli::commands::tasks::list::list_task_lists(&client).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                TasksCommands::List { list, limit, show_completed, full } => {
-                    let params = workspace_cli::commands::tasks::list::ListTasksParams {
-                        task_list_id: list,
-                        max_results: limit.min(100),  // API max is 100
-                        show_completed,
-                        show_hidden: false,
-                        page_token: None,
-                    };
-                    match workspace_cli::commands::tasks::list::list_tasks(&client, params).await {
-                        Ok(response) => {
-                            if full {
-                                // Return full task data
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&response)?;
-                                } else {
-                                    formatter.write(&response)?;
-                                }
-                            } else {
-                                // Default: minimal task data (id, title, status, due, notes, completed)
-                                let minimal = workspace_cli::commands::tasks::types::MinimalTasks::from_tasks(&response);
-                                if let Some(ref output_path) = cli.output {
-                                    let file = std::fs::File::create(output_path)?;
-                                    let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                    file_formatter.write(&minimal)?;
-                                } else {
-                                    formatter.write(&minimal)?;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                TasksCommands::Create { title, list, due, notes } => {
-                    let params = workspace_cli::commands::tasks::create::CreateTaskParams {
-                        task_list_id: list,
-                        title,
-                        notes,
-                        due,
-                        parent: None,
-                    };
-                    match workspace_cli::commands::tasks::create::create_task(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                TasksCommands::Update { id, list, title, complete } => {
-                    use workspace_cli::commands::tasks::update::TaskStatus;
-                    let params = workspace_cli::commands::tasks::update::UpdateTaskParams {
-                        task_list_id: list,
-                        task_id: id,
-                        title,
-                        status: if complete { Some(TaskStatus::Completed) } else { None },
-                        notes: None,
-                        due: None,
-                    };
-                    match workspace_cli::commands::tasks::update::update_task(&client, params).await {
-                        Ok(response) => {
-                            if let Some(ref output_path) = cli.output {
-                                let file = std::fs::File::create(output_path)?;
-                                let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                                file_formatter.write(&response)?;
-                            } else {
-                                formatter.write(&response)?;
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                TasksCommands::Delete { id, list } => {
-                    match workspace_cli::commands::tasks::update::delete_task(&client, &list, &id).await {
-                        Ok(_) => {
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Task deleted"}}"#);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Auth { command } => {
-            match command {
-                AuthCommands::Login { credentials, account } => {
-                    let creds_path = credentials.map(std::path::PathBuf::from);
-                    
-                    // Determine account name
-                    let account_name = if let Some(acc) = account {
-                        acc.clone()
-                    } else {
-                        // Use timestamp-based default if not specified
-                        format!("account_{}", std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .map(|d| d.as_secs())
-                            .unwrap_or(0))
-                    };
-                    
-                    // Create token manager for specific account
-                    let config = workspace_cli::config::Config::load();
-                    let mut tm = workspace_cli::auth::TokenManager::new_for_account(config.clone(), &account_name);
-                    
-                    match tm.login_interactive(creds_path.clone()).await {
-                        Ok(()) => {
-                            // Save account info to config
-                            let mut config = workspace_cli::config::Config::load();
-                            config.auth.current_account = Some(account_name.clone());
-                            if let Some(path) = creds_path {
-                                let abs_path = std::fs::canonicalize(&path).unwrap_or(path.clone());
-                                config.auth.accounts.insert(account_name.clone(), abs_path);
-                                config.auth.credentials_path = Some(path);
-                            }
-                            if let Err(e) = config.save() {
-                                eprintln!(r#"{{"status":"warning","message":"Login succeeded but failed to save config: {}"}}"#, e);
-                            }
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Login successful","account":"{}"}}"#, account_name);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                AuthCommands::Logout { account, all } => {
-                    let config = workspace_cli::config::Config::load();
-                    
-                    if all {
-                        // Logout all accounts
-                        match workspace_cli::auth::TokenManager::list_accounts() {
-                            Ok(accounts) => {
-                                let mut any_error = false;
-                                for acc in accounts {
-                                    let mut tm = workspace_cli::auth::TokenManager::new_for_account(config.clone(), &acc);
-                                    if let Err(e) = tm.logout() {
-                                        eprintln!(r#"{{"status":"warning","message":"Failed to logout {}: {}"}}"#, acc, e);
-                                        any_error = true;
-                                    }
-                                }
-                                
-                                // Clear current account from config
-                                let mut config = workspace_cli::config::Config::load();
-                                config.auth.current_account = None;
-                                config.auth.accounts.clear();
-                                let _ = config.save();
-                                
-                                if !quiet {
-                                    if any_error {
-                                        println!(r#"{{"status":"success","message":"Logged out all accounts (with some warnings)"}}"#);
-                                    } else {
-                                        println!(r#"{{"status":"success","message":"Logged out all accounts"}}"#);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    } else {
-                        // Logout specific account or current
-                        let account_name = account.or_else(|| config.auth.current_account.clone())
-                            .unwrap_or_else(|| "default".to_string());
-                        
-                        let mut tm = workspace_cli::auth::TokenManager::new_for_account(config.clone(), &account_name);
-                        match tm.logout() {
-                            Ok(()) => {
-                                // Remove from config if it was the current account
-                                let mut config = workspace_cli::config::Config::load();
-                                if config.auth.current_account.as_deref() == Some(&account_name) {
-                                    config.auth.current_account = None;
-                                }
-                                config.auth.accounts.remove(&account_name);
-                                let _ = config.save();
-                                
-                                if !quiet {
-                                    println!(r#"{{"status":"success","message":"Logged out account '{}'"}}"#, account_name);
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-                }
-                AuthCommands::Status => {
-                    let config = workspace_cli::config::Config::load();
-                    let tm = token_manager.read().await;
-                    let status = tm.status();
-                    
-                    if !quiet {
-                        println!("{{");
-                        println!(r#"  "authenticated": {},"#, status.authenticated);
-                        println!(r#"  "current_account": {},"#, 
-                            config.auth.current_account.as_deref()
-                                .map(|s| format!(r#""{}""#, s))
-                                .unwrap_or_else(|| "null".to_string())
-                        );
-                        println!(r#"  "storage_type": "{}","#, status.storage_type);
-                        println!(r#"  "token_cache_path": "{}""#, status.token_cache_path.display());
-                        println!("}}");
-                    }
-                }
-                AuthCommands::Accounts => {
-                    match workspace_cli::auth::TokenManager::list_accounts() {
-                        Ok(accounts) => {
-                            let config = workspace_cli::config::Config::load();
-                            let current = config.auth.current_account.as_deref();
-                            
-                            if !quiet {
-                                println!("{{");
-                                println!(r#"  "accounts": ["#);
-                                for (i, account) in accounts.iter().enumerate() {
-                                    let is_current = current == Some(account.as_str());
-                                    let comma = if i < accounts.len() - 1 { "," } else { "" };
-                                    println!(r#"    {{"name": "{}", "current": {}}}{}"#, account, is_current, comma);
-                                }
-                                println!("  ]");
-                                println!("}}");
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                AuthCommands::Switch { account } => {
-                    // Verify the account exists
-                    match workspace_cli::auth::TokenManager::list_accounts() {
-                        Ok(accounts) => {
-                            if !accounts.contains(&account) {
-                                eprintln!(r#"{{"status":"error","message":"Account '{}' not found. Use 'workspace-cli auth accounts' to list available accounts."}}"#, account);
-                                std::process::exit(1);
-                            }
-                            
-                            // Update current account in config
-                            let mut config = workspace_cli::config::Config::load();
-                            config.auth.current_account = Some(account.clone());
-                            if let Err(e) = config.save() {
-                                eprintln!(r#"{{"status":"error","message":"Failed to save config: {}"}}"#, e);
-                                std::process::exit(1);
-                            }
-                            
-                            if !quiet {
-                                println!(r#"{{"status":"success","message":"Switched to account '{}'"}}"#, account);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!(r#"{{"status":"error","message":"{}"}}"#, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
-        }
-        Commands::Batch { command } => {
-            // Ensure we're authenticated before making API calls
-            let access_token = {
-                let mut tm = token_manager.write().await;
-                if let Err(e) = tm.ensure_authenticated().await {
-                    eprintln!(r#"{{"status":"error","message":"Authentication failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-                match tm.get_access_token().await {
-                    Ok(token) => token,
-                    Err(e) => {
-                        eprintln!(r#"{{"status":"error","message":"Failed to get access token: {}"}}"#, e);
-                        std::process::exit(1);
-                    }
-                }
-            };
-
-            let mut formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet);
-
-            // Determine service and get JSON input
-            let (service, requests_json, file_path) = match command {
-                BatchCommands::Gmail { requests, file } => ("gmail", requests, file),
-                BatchCommands::Drive { requests, file } => ("drive", requests, file),
-                BatchCommands::Calendar { requests, file } => ("calendar", requests, file),
-            };
-
-            // Parse input JSON from argument, file, or stdin
-            let json_str = if let Some(path) = file_path {
-                match std::fs::read_to_string(&path) {
-                    Ok(content) => content,
-                    Err(e) => {
-                        eprintln!(r#"{{"status":"error","message":"Failed to read file '{}': {}"}}"#, path, e);
-                        std::process::exit(1);
-                    }
-                }
-            } else if let Some(json) = requests_json {
-                json
-            } else {
-                // Read from stdin for piping
-                use std::io::Read;
-                let mut buffer = String::new();
-                if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
-                    eprintln!(r#"{{"status":"error","message":"Failed to read from stdin: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-                buffer
-            };
-
-            // Parse JSON array of requests
-            let inputs: Vec<workspace_cli::commands::batch::BatchRequestInput> = match serde_json::from_str(&json_str) {
-                Ok(inputs) => inputs,
-                Err(e) => {
-                    eprintln!(r#"{{"status":"error","message":"Invalid JSON input: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            };
+    // Initialize tracing: log output filtered by RUST_LOG, plus optional span export
+    let settings = TelemetrySettings {
+        trace_file: cli.trace_file.clone().map(Into::into).or_else(|| config.telemetry.trace_file.clone()),
+        otlp_endpoint: cli.otlp_endpoint.clone().or_else(|| config.telemetry.otlp_endpoint.clone()),
+        timings: cli.timings,
+    };
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(EnvFilter::from_default_env()))
+        .with(telemetry::layer(&settings))
+        .init();
 
-            // Execute batch
-            match workspace_cli::commands::batch::execute_batch(service, inputs, &access_token).await {
-                Ok(output) => {
-                    if let Some(ref output_path) = cli.output {
-                        let file = std::fs::File::create(output_path)?;
-                        let mut file_formatter = Formatter::new(format).with_fields(fields.clone()).with_quiet(quiet).with_writer(file);
-                        file_formatter.write(&output)?;
-                    } else {
-                        formatter.write(&output)?;
-                    }
-                }
-                Err(e) => {
-                    eprintln!(r#"{{"status":"error","message":"Batch request failed: {}"}}"#, e);
-                    std::process::exit(1);
-                }
-            }
-        }
-    }
+    let code = cli::run(cli, config).await;
+    telemetry::shutdown().await;
 
-    Ok(())
+    std::process::exit(code);
 }
@@ -10,6 +10,7 @@ pub enum OutputFormat {
 }
 
 impl OutputFormat {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "json" => Some(Self::Json),
@@ -28,6 +29,7 @@ pub struct Formatter {
     csv_headers: Option<Vec<String>>,
     fields: Option<Vec<String>>,
     quiet: bool,
+    timings: bool,
 }
 
 impl Formatter {
@@ -39,6 +41,7 @@ impl Formatter {
             csv_headers: None,
             fields: None,
             quiet: false,
+            timings: false,
         }
     }
 
@@ -59,6 +62,12 @@ impl Formatter {
         self
     }
 
+    /// Append a `_timings` summary to JSON object output
+    pub fn with_timings(mut self, timings: bool) -> Self {
+        self.timings = timings;
+        self
+    }
+
     pub fn format(&self) -> OutputFormat {
         self.format
     }
@@ -182,7 +191,13 @@ impl Formatter {
         // Convert to JSON value for field filtering
         let value = serde_json::to_value(item)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        let filtered = self.filter_fields(value);
+        let mut filtered = self.filter_fields(value);
+
+        if self.timings && self.format != OutputFormat::Csv {
+            if let (Some(obj), Some(summary)) = (filtered.as_object_mut(), crate::telemetry::timings_summary()) {
+                obj.insert("_timings".to_string(), summary);
+            }
+        }
 
         match self.format {
             OutputFormat::Json => {
@@ -1,5 +1,6 @@
 mod formatter;
 mod pagination;
+pub mod progress;
 
 pub use formatter::{Formatter, OutputFormat, output_json, output_jsonl};
 pub use pagination::{
@@ -2,6 +2,8 @@ use futures::Stream;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 
+use super::progress::{self, ProgressEvent};
+
 /// Common pagination response wrapper for Google APIs
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -67,6 +69,8 @@ pub struct PaginationState {
     pub sync_token: Option<String>,
     /// Whether we've reached the end
     pub exhausted: bool,
+    /// Number of pages fetched so far
+    pub pages_fetched: u32,
 }
 
 impl PaginationState {
@@ -77,6 +81,7 @@ impl PaginationState {
             max_items: None,
             sync_token: None,
             exhausted: false,
+            pages_fetched: 0,
         }
     }
 
@@ -97,6 +102,7 @@ impl PaginationState {
             self.sync_token = response.next_sync_token.clone();
         }
         self.exhausted = self.page_token.is_none();
+        self.pages_fetched += 1;
     }
 
     /// Check if we should fetch more pages
@@ -156,6 +162,10 @@ impl<T> PaginatedResult<T> {
     }
 }
 
+/// Boxed future returned by [`Paginator::fetch_page`]
+pub type PageFuture<'a, T, E> =
+    Pin<Box<dyn std::future::Future<Output = Result<PagedResponse<T>, E>> + Send + 'a>>;
+
 /// Trait for creating paginated streams
 pub trait Paginator {
     type Item: Send;
@@ -165,7 +175,7 @@ pub trait Paginator {
     fn fetch_page(
         &self,
         page_token: Option<&str>,
-    ) -> Pin<Box<dyn std::future::Future<Output = Result<PagedResponse<Self::Item>, Self::Error>> + Send + '_>>;
+    ) -> PageFuture<'_, Self::Item, Self::Error>;
 }
 
 /// Create a stream that yields items from all pages
@@ -189,6 +199,11 @@ where
             let next_token = response.next_page_token.clone();
             let next_sync = response.next_sync_token.clone();
             let items = response.into_items();
+            progress::emit(ProgressEvent::Page {
+                page: state.pages_fetched + 1,
+                items: items.len(),
+                has_more: next_token.is_some(),
+            });
 
             // Update state with the actual tokens from the response
             state.page_token = next_token;
@@ -196,6 +211,7 @@ where
                 state.sync_token = next_sync;
             }
             state.exhausted = state.page_token.is_none();
+            state.pages_fetched += 1;
 
             for item in items {
                 state.add_items(1);
@@ -234,6 +250,11 @@ where
     loop {
         let response = paginator.fetch_page(state.page_token.as_deref()).await?;
         state.update(&response);
+        progress::emit(ProgressEvent::Page {
+            page: state.pages_fetched,
+            items: response.items.len(),
+            has_more: !state.exhausted,
+        });
 
         let items = response.into_items();
         let take_count = if let Some(max) = max_items {
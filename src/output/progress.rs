@@ -0,0 +1,292 @@
+//! Progress events for long-running operations
+//!
+//! With `--progress jsonl` every event is written to stderr as one JSON object
+//! per line. `--progress human` renders transfers as a progress bar and prints
+//! short status lines for retries, rate-limit waits, pages and batches.
+//! Progress is off by default and never touches stdout.
+
+use serde::Serialize;
+use std::io::Write;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+static MODE: OnceLock<ProgressMode> = OnceLock::new();
+
+/// Progress output mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressMode {
+    #[default]
+    Off,
+    Jsonl,
+    Human,
+}
+
+impl ProgressMode {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "jsonl" | "json" => Some(Self::Jsonl),
+            "human" | "bar" => Some(Self::Human),
+            _ => None,
+        }
+    }
+}
+
+/// A progress event
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    RequestStart {
+        method: String,
+        url: String,
+    },
+    RequestEnd {
+        method: String,
+        url: String,
+        status: u16,
+        elapsed_ms: u64,
+    },
+    Transfer {
+        operation: &'static str,
+        name: String,
+        bytes: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        total: Option<u64>,
+    },
+    TransferDone {
+        operation: &'static str,
+        name: String,
+        bytes: u64,
+        elapsed_ms: u64,
+    },
+    Retry {
+        attempt: u32,
+        backoff_ms: u64,
+        error: String,
+    },
+    RateLimitWait {
+        wait_ms: u64,
+    },
+    Page {
+        page: u32,
+        items: usize,
+        has_more: bool,
+    },
+    BatchStart {
+        service: String,
+        requests: usize,
+    },
+    BatchEnd {
+        service: String,
+        succeeded: usize,
+        failed: usize,
+        elapsed_ms: u64,
+    },
+    /// One item of a multi-item operation finished (e.g. a mail merge row)
+    Item {
+        operation: &'static str,
+        current: usize,
+        total: usize,
+        name: String,
+        status: String,
+    },
+}
+
+/// Set the progress mode for this process (first call wins)
+pub fn init(mode: ProgressMode) {
+    let _ = MODE.set(mode);
+}
+
+/// Current progress mode
+pub fn mode() -> ProgressMode {
+    MODE.get().copied().unwrap_or_default()
+}
+
+/// Whether progress events are being reported
+pub fn enabled() -> bool {
+    mode() != ProgressMode::Off
+}
+
+/// Report a progress event
+pub fn emit(event: ProgressEvent) {
+    match mode() {
+        ProgressMode::Off => {}
+        ProgressMode::Jsonl => emit_jsonl(&event),
+        ProgressMode::Human => emit_human(&event),
+    }
+}
+
+fn emit_jsonl(event: &ProgressEvent) {
+    let mut value = match serde_json::to_value(event) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "ts".to_string(),
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true).into(),
+        );
+    }
+    let mut stderr = std::io::stderr().lock();
+    let _ = writeln!(stderr, "{}", value);
+}
+
+fn emit_human(event: &ProgressEvent) {
+    let mut stderr = std::io::stderr().lock();
+    let _ = match event {
+        // Individual requests are too chatty for a terminal
+        ProgressEvent::RequestStart { .. } | ProgressEvent::RequestEnd { .. } => Ok(()),
+        ProgressEvent::Transfer { operation, name, bytes, total } => {
+            write!(stderr, "\r{}", render_bar(operation, name, *bytes, *total))
+        }
+        ProgressEvent::TransferDone { operation, name, bytes, elapsed_ms } => {
+            writeln!(
+                stderr,
+                "\r{} ({:.1}s)",
+                render_bar(operation, name, *bytes, Some(*bytes)),
+                *elapsed_ms as f64 / 1000.0
+            )
+        }
+        ProgressEvent::Retry { attempt, backoff_ms, error } => {
+            writeln!(stderr, "retry #{} in {}ms: {}", attempt, backoff_ms, error)
+        }
+        ProgressEvent::RateLimitWait { wait_ms } => {
+            writeln!(stderr, "rate limited, waiting {}ms", wait_ms)
+        }
+        ProgressEvent::Page { page, items, .. } => {
+            writeln!(stderr, "page {} fetched ({} items)", page, items)
+        }
+        ProgressEvent::BatchStart { service, requests } => {
+            writeln!(stderr, "{} batch: {} requests", service, requests)
+        }
+        ProgressEvent::BatchEnd { service, succeeded, failed, elapsed_ms } => {
+            writeln!(
+                stderr,
+                "{} batch done: {} ok, {} failed ({}ms)",
+                service, succeeded, failed, elapsed_ms
+            )
+        }
+        ProgressEvent::Item { operation, current, total, name, status } => {
+            writeln!(stderr, "{} {}/{} {}: {}", operation, current, total, name, status)
+        }
+    };
+}
+
+fn render_bar(operation: &str, name: &str, bytes: u64, total: Option<u64>) -> String {
+    const WIDTH: usize = 30;
+    match total {
+        Some(total) if total > 0 => {
+            let ratio = (bytes as f64 / total as f64).min(1.0);
+            let filled = (ratio * WIDTH as f64).round() as usize;
+            format!(
+                "{} {} [{}{}] {:>3}% {}/{}",
+                operation,
+                name,
+                "#".repeat(filled),
+                "-".repeat(WIDTH - filled),
+                (ratio * 100.0).round() as u32,
+                format_bytes(bytes),
+                format_bytes(total)
+            )
+        }
+        _ => format!("{} {} {}", operation, name, format_bytes(bytes)),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// Tracks bytes for an upload, download or export and emits throttled events
+pub struct TransferProgress {
+    operation: &'static str,
+    name: String,
+    total: Option<u64>,
+    bytes: u64,
+    started: Instant,
+    last_emit: Instant,
+}
+
+impl TransferProgress {
+    /// Minimum interval between transfer events
+    const INTERVAL: Duration = Duration::from_millis(200);
+
+    pub fn new(operation: &'static str, name: impl Into<String>, total: Option<u64>) -> Self {
+        let now = Instant::now();
+        let progress = Self {
+            operation,
+            name: name.into(),
+            total,
+            bytes: 0,
+            started: now,
+            last_emit: now,
+        };
+        progress.report();
+        progress
+    }
+
+    /// Record transferred bytes
+    pub fn advance(&mut self, bytes: u64) {
+        self.bytes += bytes;
+        if self.last_emit.elapsed() >= Self::INTERVAL {
+            self.last_emit = Instant::now();
+            self.report();
+        }
+    }
+
+    /// Emit the final event
+    pub fn finish(self) {
+        emit(ProgressEvent::TransferDone {
+            operation: self.operation,
+            name: self.name,
+            bytes: self.bytes,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        });
+    }
+
+    fn report(&self) {
+        emit(ProgressEvent::Transfer {
+            operation: self.operation,
+            name: self.name.clone(),
+            bytes: self.bytes,
+            total: self.total,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = ProgressEvent::Retry {
+            attempt: 2,
+            backoff_ms: 400,
+            error: "API error: rate limited".to_string(),
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["event"], "retry");
+        assert_eq!(value["attempt"], 2);
+        assert_eq!(value["backoff_ms"], 400);
+    }
+
+    #[test]
+    fn test_render_bar() {
+        let bar = render_bar("upload", "a.bin", 512, Some(1024));
+        assert!(bar.contains("50%"));
+        assert!(bar.contains("512B/1.0KB"));
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use super::Collector;
+
+/// A finished span
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanRecord {
+    pub name: String,
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+    /// Start time in nanoseconds since the Unix epoch
+    pub start_unix_nanos: u128,
+    pub duration_ms: f64,
+    pub fields: Map<String, Value>,
+}
+
+/// Per-span state kept in the registry extensions while the span is open
+struct SpanData {
+    trace_id: u128,
+    span_id: u64,
+    parent_id: Option<u64>,
+    start: SystemTime,
+    started: Instant,
+    fields: Map<String, Value>,
+}
+
+/// Tracing layer that records this crate's spans with their fields and latency
+pub struct TelemetryLayer {
+    collector: Arc<Collector>,
+}
+
+impl TelemetryLayer {
+    pub(crate) fn new(collector: Arc<Collector>) -> Self {
+        Self { collector }
+    }
+}
+
+impl<S> Layer<S> for TelemetryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !attrs.metadata().target().starts_with("workspace_cli") {
+            return;
+        }
+        let Some(span) = ctx.span(id) else { return };
+
+        // Inherit the trace from the nearest recorded ancestor
+        let parent = span.scope().skip(1).find_map(|ancestor| {
+            ancestor.extensions().get::<SpanData>().map(|d| (d.trace_id, d.span_id))
+        });
+
+        let mut fields = Map::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+
+        span.extensions_mut().insert(SpanData {
+            trace_id: parent.map(|(trace, _)| trace).unwrap_or_else(rand::random),
+            span_id: rand::random(),
+            parent_id: parent.map(|(_, span_id)| span_id),
+            start: SystemTime::now(),
+            started: Instant::now(),
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            values.record(&mut FieldVisitor(&mut data.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else { return };
+
+        self.collector.record(SpanRecord {
+            name: span.name().to_string(),
+            trace_id: format!("{:032x}", data.trace_id),
+            span_id: format!("{:016x}", data.span_id),
+            parent_span_id: data.parent_id.map(|p| format!("{:016x}", p)),
+            start_unix_nanos: data.start.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0),
+            duration_ms: data.started.elapsed().as_secs_f64() * 1000.0,
+            fields: data.fields,
+        });
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
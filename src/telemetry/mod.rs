@@ -0,0 +1,156 @@
+//! Span collection, export and per-invocation timing summaries
+//!
+//! Spans emitted by this crate (`api.request`, `retry.attempt`, `retry.backoff`,
+//! `rate_limit.acquire`, `batch.execute`, `auth.token`, `auth.refresh`) are
+//! collected by [`TelemetryLayer`]. Finished spans can be written as JSON lines
+//! to a file, sent to an OTLP/HTTP collector when the process exits, and
+//! summarized into `_timings` output metadata with `--timings`.
+
+mod layer;
+mod otlp;
+
+pub use layer::{SpanRecord, TelemetryLayer};
+
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+static COLLECTOR: OnceLock<Arc<Collector>> = OnceLock::new();
+
+/// Telemetry settings for one invocation
+#[derive(Debug, Clone, Default)]
+pub struct TelemetrySettings {
+    /// Append finished spans as JSON lines to this file
+    pub trace_file: Option<PathBuf>,
+    /// OTLP/HTTP collector base URL (e.g. http://localhost:4318)
+    pub otlp_endpoint: Option<String>,
+    /// Collect a timing summary for the output metadata
+    pub timings: bool,
+}
+
+impl TelemetrySettings {
+    fn is_enabled(&self) -> bool {
+        self.trace_file.is_some() || self.otlp_endpoint.is_some() || self.timings
+    }
+}
+
+/// Shared store for finished spans
+pub(crate) struct Collector {
+    started: Instant,
+    spans: Mutex<Vec<SpanRecord>>,
+    trace_file: Option<Mutex<std::fs::File>>,
+    otlp_endpoint: Option<String>,
+}
+
+impl Collector {
+    pub(crate) fn record(&self, span: SpanRecord) {
+        if let Some(ref file) = self.trace_file {
+            if let (Ok(mut file), Ok(line)) = (file.lock(), serde_json::to_string(&span)) {
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+        if let Ok(mut spans) = self.spans.lock() {
+            spans.push(span);
+        }
+    }
+}
+
+/// Create the telemetry layer, or None when no exporter or summary is requested
+pub fn layer(settings: &TelemetrySettings) -> Option<TelemetryLayer> {
+    if !settings.is_enabled() {
+        return None;
+    }
+
+    let trace_file = settings.trace_file.as_ref().and_then(|path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| eprintln!("Warning: cannot open trace file {}: {}", path.display(), e))
+            .ok()
+            .map(Mutex::new)
+    });
+
+    let collector = Arc::new(Collector {
+        started: Instant::now(),
+        spans: Mutex::new(Vec::new()),
+        trace_file,
+        otlp_endpoint: settings.otlp_endpoint.clone(),
+    });
+    let _ = COLLECTOR.set(collector.clone());
+
+    Some(TelemetryLayer::new(collector))
+}
+
+/// Summarize spans finished so far
+///
+/// Returns None when telemetry was not initialized.
+pub fn timings_summary() -> Option<Value> {
+    let collector = COLLECTOR.get()?;
+    let spans = collector.spans.lock().ok()?;
+
+    let mut by_name: BTreeMap<&str, (u64, f64, f64)> = BTreeMap::new();
+    for span in spans.iter() {
+        let entry = by_name.entry(span.name.as_str()).or_insert((0, 0.0, 0.0));
+        entry.0 += 1;
+        entry.1 += span.duration_ms;
+        entry.2 = entry.2.max(span.duration_ms);
+    }
+
+    let mut requests: Vec<&SpanRecord> = spans.iter().filter(|s| s.name == "api.request").collect();
+    requests.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+
+    let span_totals: Map<String, Value> = by_name
+        .into_iter()
+        .map(|(name, (count, total, max))| {
+            (name.to_string(), json!({
+                "count": count,
+                "totalMs": round_ms(total),
+                "maxMs": round_ms(max),
+            }))
+        })
+        .collect();
+
+    let slowest: Vec<Value> = requests
+        .iter()
+        .take(5)
+        .map(|s| {
+            let mut entry = s.fields.clone();
+            entry.insert("latencyMs".to_string(), json!(round_ms(s.duration_ms)));
+            Value::Object(entry)
+        })
+        .collect();
+
+    Some(json!({
+        "totalMs": round_ms(collector.started.elapsed().as_secs_f64() * 1000.0),
+        "requests": requests.len(),
+        "requestMs": round_ms(requests.iter().fold(0.0, |acc, s| acc + s.duration_ms)),
+        "spans": span_totals,
+        "slowest": slowest,
+    }))
+}
+
+/// Flush buffered spans to the OTLP collector, if configured
+pub async fn shutdown() {
+    let Some(collector) = COLLECTOR.get() else { return };
+    let Some(ref endpoint) = collector.otlp_endpoint else { return };
+
+    let spans = match collector.spans.lock() {
+        Ok(mut spans) => std::mem::take(&mut *spans),
+        Err(_) => return,
+    };
+    if spans.is_empty() {
+        return;
+    }
+
+    if let Err(e) = otlp::export(endpoint, &spans).await {
+        eprintln!("Warning: failed to export traces to {}: {}", endpoint, e);
+    }
+}
+
+fn round_ms(ms: f64) -> f64 {
+    (ms * 10.0).round() / 10.0
+}
@@ -0,0 +1,73 @@
+//! Minimal OTLP/HTTP JSON trace exporter
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::SpanRecord;
+
+/// POST spans to `<endpoint>/v1/traces` using the OTLP JSON encoding
+pub(crate) async fn export(endpoint: &str, spans: &[SpanRecord]) -> Result<(), reqwest::Error> {
+    let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+
+    reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(5))
+        .json(&encode(spans))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+fn encode(spans: &[SpanRecord]) -> Value {
+    let spans: Vec<Value> = spans.iter().map(encode_span).collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    attribute("service.name", &json!("workspace-cli")),
+                    attribute("service.version", &json!(env!("CARGO_PKG_VERSION"))),
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "workspace-cli", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }]
+        }]
+    })
+}
+
+fn encode_span(span: &SpanRecord) -> Value {
+    let end = span.start_unix_nanos + (span.duration_ms * 1_000_000.0) as u128;
+    let attributes: Vec<Value> = span.fields.iter().map(|(k, v)| attribute(k, v)).collect();
+    // SPAN_KIND_CLIENT for outgoing API calls, SPAN_KIND_INTERNAL otherwise
+    let kind = if span.name == "api.request" { 3 } else { 1 };
+
+    let mut encoded = json!({
+        "traceId": span.trace_id,
+        "spanId": span.span_id,
+        "name": span.name,
+        "kind": kind,
+        "startTimeUnixNano": span.start_unix_nanos.to_string(),
+        "endTimeUnixNano": end.to_string(),
+        "attributes": attributes,
+    });
+    if let Some(ref parent) = span.parent_span_id {
+        encoded["parentSpanId"] = json!(parent);
+    }
+    encoded
+}
+
+fn attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) if n.is_f64() => json!({ "doubleValue": n }),
+        // OTLP JSON encodes 64-bit integers as strings
+        Value::Number(n) => json!({ "intValue": n.to_string() }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
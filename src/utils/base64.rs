@@ -24,6 +24,20 @@ pub fn encode_base64url_string(input: &str) -> String {
     encode_base64url(input.as_bytes())
 }
 
+/// Serde adapter storing bytes as a base64url string: `#[serde(with = "serde_base64url")]`
+pub mod serde_base64url {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::encode_base64url(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        super::decode_base64url(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug)]
 pub enum Base64DecodeError {
     Base64(base64::DecodeError),
@@ -0,0 +1,202 @@
+use base64::{Engine as _, engine::general_purpose::{STANDARD, STANDARD_NO_PAD}};
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// Decode bytes in the named charset
+///
+/// Unknown or missing charsets are read as UTF-8, falling back to
+/// Windows-1252 when the bytes aren't valid UTF-8.
+pub fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .map(|c| c.trim().trim_matches('"'))
+        .and_then(|c| Encoding::for_label(c.as_bytes()));
+
+    match encoding {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned(),
+        },
+    }
+}
+
+/// Value of a parameter in a `Content-Type`-style header, e.g. `charset`
+pub fn content_type_param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, val) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| val.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Decode quoted-printable data; malformed escapes are kept literally
+pub fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < input.len() {
+        if input[i] == b'=' {
+            let rest = &input[i + 1..];
+            if rest.starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if rest.starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            if let (Some(high), Some(low)) = (rest.first().and_then(|&b| hex_value(b)), rest.get(1).and_then(|&b| hex_value(b))) {
+                output.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(input[i]);
+        i += 1;
+    }
+
+    output
+}
+
+/// Whether data still carries quoted-printable escapes
+///
+/// True when there is at least one `=` and every `=` starts a soft line
+/// break or an uppercase `=XX` escape, so already-decoded text such as
+/// `a=b` or `width="100"` is left alone.
+pub fn looks_quoted_printable(input: &[u8]) -> bool {
+    let mut found = false;
+    for (i, _) in input.iter().enumerate().filter(|(_, &b)| b == b'=') {
+        let rest = &input[i + 1..];
+        let escape = rest.len() >= 2 && is_upper_hex(rest[0]) && is_upper_hex(rest[1]);
+        if !(escape || rest.starts_with(b"\r\n") || rest.starts_with(b"\n")) {
+            return false;
+        }
+        found = true;
+    }
+    found
+}
+
+/// Decode RFC 2047 encoded-words (`=?charset?B|Q?text?=`) in a header value
+///
+/// Whitespace between adjacent encoded-words is dropped, and adjacent words
+/// in the same charset are decoded together so multi-byte sequences split
+/// across words survive. Malformed words are left as-is.
+pub fn decode_encoded_words(value: &str) -> String {
+    if !value.contains("=?") {
+        return value.to_string();
+    }
+
+    let mut output = String::with_capacity(value.len());
+    // Bytes of the current run of encoded-words and their charset
+    let mut pending: Option<(String, Vec<u8>)> = None;
+    let mut rest = value;
+
+    while let Some((start, end, charset, bytes)) = find_encoded_word(rest) {
+        let between = &rest[..start];
+        let joins_previous = pending.is_some() && between.trim().is_empty();
+
+        match pending.take() {
+            Some((current, mut buffer)) if joins_previous && current.eq_ignore_ascii_case(&charset) => {
+                buffer.extend(bytes);
+                pending = Some((current, buffer));
+            }
+            previous => {
+                if let Some((current, buffer)) = previous {
+                    output.push_str(&decode_charset(&buffer, Some(&current)));
+                }
+                if !joins_previous {
+                    output.push_str(between);
+                }
+                pending = Some((charset, bytes));
+            }
+        }
+        rest = &rest[end..];
+    }
+
+    if let Some((charset, buffer)) = pending {
+        output.push_str(&decode_charset(&buffer, Some(&charset)));
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Locate the first well-formed encoded-word in `input`
+///
+/// Returns its byte range, charset and decoded bytes.
+fn find_encoded_word(input: &str) -> Option<(usize, usize, String, Vec<u8>)> {
+    let mut offset = 0;
+    while let Some(found) = input[offset..].find("=?") {
+        let start = offset + found;
+        if let Some((len, charset, bytes)) = parse_encoded_word(&input[start..]) {
+            return Some((start, start + len, charset, bytes));
+        }
+        offset = start + 2;
+    }
+    None
+}
+
+fn parse_encoded_word(word: &str) -> Option<(usize, String, Vec<u8>)> {
+    let body = word.strip_prefix("=?")?;
+    let (charset, body) = body.split_once('?')?;
+    let (encoding, body) = body.split_once('?')?;
+    let end = body.find("?=")?;
+    let text = &body[..end];
+    if charset.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => STANDARD
+            .decode(text)
+            .or_else(|_| STANDARD_NO_PAD.decode(text.trim_end_matches('=')))
+            .ok()?,
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+
+    // RFC 2231 allows a language suffix: charset*lang
+    let charset = charset.split('*').next().unwrap_or(charset).to_string();
+    let len = word.len() - body.len() + end + 2;
+    Some((len, charset, bytes))
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+fn is_upper_hex(byte: u8) -> bool {
+    byte.is_ascii_digit() || (b'A'..=b'F').contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_encoded_words() {
+        assert_eq!(decode_encoded_words("=?UTF-8?B?44GT44KT44Gr44Gh44Gv?="), "こんにちは");
+        assert_eq!(
+            decode_encoded_words("=?ISO-8859-1?Q?Andr=E9?= Pirard <pirard@example.com>"),
+            "André Pirard <pirard@example.com>"
+        );
+        assert_eq!(decode_encoded_words("=?ISO-2022-JP?B?GyRCJEckOSEjGyhC?="), "です。");
+        // Adjacent words are joined, even when a character is split between them
+        assert_eq!(decode_encoded_words("=?UTF-8?B?44GT44KT?= =?UTF-8?B?44Gr44Gh44Gv?="), "こんにちは");
+        assert_eq!(decode_encoded_words("=?UTF-8?Q?a_b?= =?UTF-8?Q?c?="), "a bc");
+        assert_eq!(decode_encoded_words("Re: =?UTF-8?Q?caf=C3=A9?= time"), "Re: café time");
+        assert_eq!(decode_encoded_words("plain =? not encoded"), "plain =? not encoded");
+    }
+
+    #[test]
+    fn test_decode_body_bytes() {
+        assert_eq!(decode_charset(b"caf\xe9", Some("windows-1252")), "café");
+        assert_eq!(decode_charset(b"caf\xe9", None), "café");
+        assert_eq!(decode_charset(b"\x1b$B$3$s$K$A$O\x1b(B", Some("iso-2022-jp")), "こんにちは");
+        assert_eq!(content_type_param("text/plain; charset=\"ISO-8859-1\"; format=flowed", "charset").as_deref(), Some("ISO-8859-1"));
+
+        let encoded = b"caf=C3=A9 =\r\nsoft break";
+        assert!(looks_quoted_printable(encoded));
+        assert_eq!(decode_quoted_printable(encoded), "café soft break".as_bytes());
+        assert!(!looks_quoted_printable(b"<td width=\"100\">a=b</td>"));
+    }
+}
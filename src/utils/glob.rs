@@ -0,0 +1,98 @@
+/// Include/exclude glob patterns for relative `/`-separated paths
+///
+/// Patterns support `*` (within one path segment), `**` (any number of
+/// segments) and `?`. A pattern without `/` matches the file name at any
+/// depth, like `.gitignore`.
+#[derive(Debug, Clone, Default)]
+pub struct GlobFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl GlobFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Whether a file at `path` is selected
+    pub fn matches(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| path_matches(p, path)))
+            && !self.is_excluded(path)
+    }
+
+    /// Like `matches`, but also false when a parent directory is excluded
+    pub fn matches_nested(&self, path: &str) -> bool {
+        self.matches(path) && !path.match_indices('/').any(|(i, _)| self.is_excluded(&path[..i]))
+    }
+
+    /// Whether `path` (a file or a directory) matches an exclude pattern
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.exclude.iter().any(|p| path_matches(p, path))
+    }
+}
+
+/// Match a pattern against a relative path
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if pattern.contains('/') {
+        glob_match(pattern.trim_start_matches('/'), path)
+    } else {
+        glob_match(pattern, path.rsplit('/').next().unwrap_or(path))
+    }
+}
+
+/// Match `text` against a glob pattern
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, &text)
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            match pattern[2..].split_first() {
+                // `**/` matches zero or more whole directories
+                Some(('/', rest)) => {
+                    match_from(rest, text)
+                        || (0..text.len()).any(|i| text[i] == '/' && match_from(rest, &text[i + 1..]))
+                }
+                _ => (0..=text.len()).any(|i| match_from(&pattern[2..], &text[i..])),
+            }
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            (0..=text.len())
+                .take_while(|&i| i == 0 || text[i - 1] != '/')
+                .any(|i| match_from(rest, &text[i..]))
+        }
+        Some('?') => text.first().is_some_and(|&c| c != '/') && match_from(&pattern[1..], &text[1..]),
+        Some(&c) => text.first() == Some(&c) && match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_filter() {
+        assert!(path_matches("*.pdf", "reports/2026/q1.pdf"));
+        assert!(!path_matches("*.pdf", "reports/q1.pdfx"));
+        assert!(path_matches("reports/*.csv", "reports/a.csv"));
+        assert!(!path_matches("reports/*.csv", "reports/2026/a.csv"));
+        assert!(path_matches("reports/**/*.csv", "reports/a.csv"));
+        assert!(path_matches("reports/**/*.csv", "reports/2026/q1/a.csv"));
+        assert!(path_matches("**/node_modules", "web/node_modules"));
+        assert!(path_matches("file?.txt", "file1.txt"));
+
+        let filter = GlobFilter::new(vec!["*.md".into(), "docs/**".into()], vec!["drafts".into(), "*.tmp.md".into()]);
+        assert!(filter.matches("README.md"));
+        assert!(filter.matches("docs/img/logo.png"));
+        assert!(!filter.matches("src/main.rs"));
+        assert!(!filter.matches("notes.tmp.md"));
+        assert!(filter.is_excluded("docs/drafts"));
+        assert!(filter.matches("docs/drafts/a.md") && !filter.matches_nested("docs/drafts/a.md"));
+    }
+}
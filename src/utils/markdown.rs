@@ -0,0 +1,115 @@
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+
+const OPTIONS: Options = Options::ENABLE_TABLES
+    .union(Options::ENABLE_STRIKETHROUGH)
+    .union(Options::ENABLE_TASKLISTS);
+
+/// Render Markdown to an HTML fragment
+///
+/// Enables tables, strikethrough and task lists on top of CommonMark.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(markdown, OPTIONS);
+
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, parser);
+    output
+}
+
+/// Render Markdown to plain text, for the text/plain alternative of an email
+///
+/// Emphasis and code markers are dropped, links become `text (url)`, images
+/// their alt text, and list items get `-` or `1.` bullets. Raw HTML is left out.
+pub fn markdown_to_text(markdown: &str) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut links: Vec<String> = Vec::new();
+    // Next number of each open list (None for bullets)
+    let mut lists: Vec<Option<u64>> = Vec::new();
+
+    for event in Parser::new_ext(markdown, OPTIONS) {
+        match event {
+            Event::Text(text) | Event::Code(text) => output.push_str(&text),
+            Event::SoftBreak | Event::HardBreak => output.push('\n'),
+            Event::Rule => end_block(&mut output),
+            Event::TaskListMarker(done) => output.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::Start(Tag::List(start)) => {
+                if !output.ends_with('\n') && !output.is_empty() {
+                    output.push('\n');
+                }
+                lists.push(start);
+            }
+            Event::Start(Tag::Item) => {
+                if !output.ends_with('\n') && !output.is_empty() {
+                    output.push('\n');
+                }
+                output.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        output.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => output.push_str("- "),
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => links.push(dest_url.to_string()),
+            Event::End(TagEnd::Link) => {
+                if let Some(url) = links.pop() {
+                    if !url.is_empty() && !output.ends_with(url.as_str()) {
+                        output.push_str(&format!(" ({})", url));
+                    }
+                }
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    end_block(&mut output);
+                }
+            }
+            Event::End(TagEnd::Item) if !output.ends_with('\n') => output.push('\n'),
+            Event::End(TagEnd::TableCell) => output.push('\t'),
+            Event::End(TagEnd::TableHead | TagEnd::TableRow) => {
+                let trimmed = output.trim_end_matches('\t').len();
+                output.truncate(trimmed);
+                output.push('\n');
+            }
+            // Paragraphs inside list items stay on the item's line
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock | TagEnd::BlockQuote(_) | TagEnd::Table)
+                if lists.is_empty() => end_block(&mut output),
+            _ => {}
+        }
+    }
+
+    output.trim().to_string()
+}
+
+/// End a block with exactly one blank line
+fn end_block(output: &mut String) {
+    let trimmed = output.trim_end_matches('\n').len();
+    output.truncate(trimmed);
+    output.push_str("\n\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html("Hi **team**\n\n- one\n- two\n\n![logo](cid:logo)");
+        assert!(html.contains("<strong>team</strong>"));
+        assert!(html.contains("<li>one</li>"));
+        assert!(html.contains("src=\"cid:logo\""));
+    }
+
+    #[test]
+    fn test_markdown_to_text() {
+        let text = markdown_to_text(
+            "# Update\n\nNumbers are **up**, see the ~~old~~ [plan](https://example.com/plan) and `v2`.\n\n\
+             - one\n- two\n\n1. first\n2. second\n\n![logo](cid:logo)",
+        );
+        assert_eq!(
+            text,
+            "Update\n\nNumbers are up, see the old plan (https://example.com/plan) and v2.\n\n\
+             - one\n- two\n\n1. first\n2. second\n\nlogo"
+        );
+    }
+}
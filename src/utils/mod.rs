@@ -1,13 +1,22 @@
 pub mod base64;
+pub mod charset;
 pub mod field_mask;
+pub mod glob;
 pub mod html_to_md;
+pub mod markdown;
 
 // Re-export commonly used items
 pub use base64::{
     decode_base64url, decode_base64url_string, encode_base64url, encode_base64url_string,
     Base64DecodeError,
 };
+pub use charset::{
+    content_type_param, decode_charset, decode_encoded_words, decode_quoted_printable,
+    looks_quoted_printable,
+};
 pub use field_mask::{
     build_fields_param, defaults, parse_field_mask, validate_field_mask, FieldMaskError,
 };
+pub use glob::{glob_match, path_matches, GlobFilter};
 pub use html_to_md::{html_to_markdown, html_to_text, is_html, smart_convert};
+pub use markdown::{markdown_to_html, markdown_to_text};
@@ -16,6 +16,7 @@ fn test_help() {
     assert!(stdout.contains("workspace-cli"));
     assert!(stdout.contains("gmail"));
     assert!(stdout.contains("drive"));
+    assert!(stdout.contains("Exit codes:"));
 }
 
 #[test]
@@ -85,8 +86,64 @@ fn test_all_services_have_help() {
         let output = workspace_cli()
             .args([service, "--help"])
             .output()
-            .expect(&format!("Failed to execute {} --help", service));
+            .unwrap_or_else(|_| panic!("Failed to execute {} --help", service));
 
         assert!(output.status.success(), "Help failed for {}", service);
     }
 }
+
+#[test]
+fn test_cache_stats() {
+    let dir = std::env::temp_dir().join(format!("workspace-cli-test-cache-{}", std::process::id()));
+    let output = workspace_cli()
+        .args(["cache", "stats"])
+        .env("WORKSPACE_CACHE_DIR", &dir)
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"entries\""));
+    assert!(stdout.contains("\"hits\""));
+}
+
+#[test]
+fn test_error_output_is_valid_json() {
+    let home = std::env::temp_dir().join(format!("workspace-cli-test-home-{}", std::process::id()));
+    let output = workspace_cli()
+        .args(["auth", "switch", "missing \"quoted\" account"])
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &home)
+        .output()
+        .expect("Failed to execute");
+
+    assert_eq!(output.status.code(), Some(6));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error: serde_json::Value = serde_json::from_str(stderr.trim()).expect("stderr should be JSON");
+    assert_eq!(error["status"], "error");
+    assert_eq!(error["error_code"], "not_found");
+    assert!(error["message"].as_str().unwrap().contains("\"quoted\""));
+}
+
+#[test]
+fn test_schema_lists_commands_and_exit_codes() {
+    let output = workspace_cli()
+        .arg("schema")
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).expect("schema should be JSON");
+    let commands: Vec<&str> = schema["commands"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["name"].as_str())
+        .collect();
+    assert!(commands.contains(&"gmail"));
+    assert!(schema["exitCodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c["name"] == "not_found" && c["code"] == 6));
+}